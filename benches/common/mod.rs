@@ -0,0 +1,8 @@
+// 📚 The benchmark programs that `glint bench` runs, for the Rust benchmarks
+pub const PROGRAMS: &[(&str, &str)] = &[
+    ("dicts", include_str!("../dicts.glt")),
+    ("fib", include_str!("../fib.glt")),
+    ("loops", include_str!("../loops.glt")),
+    ("sort", include_str!("../sort.glt")),
+    ("strings", include_str!("../strings.glt")),
+];
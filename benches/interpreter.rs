@@ -0,0 +1,28 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use glint::bench::bench_program;
+use glint::parser::parser::parse_program;
+
+mod common;
+
+use common::PROGRAMS;
+
+// 🏎️ Interpreter speed on the benchmark programs (parsing is not timed)
+
+fn interpret(c: &mut Criterion) {
+    let mut group = c.benchmark_group("interpret");
+    group.sample_size(10);
+    for (name, source) in PROGRAMS {
+        let ast = parse_program(source).unwrap();
+        group.bench_function(*name, |b| {
+            b.iter_custom(|iterations| {
+                let stats = bench_program(&ast, 0, iterations as usize).unwrap();
+                stats.mean * iterations as u32
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, interpret);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use glint::parser::parser::parse_program;
+
+mod common;
+
+use common::PROGRAMS;
+
+// 🏎️ Parser throughput on the benchmark programs
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, source) in PROGRAMS {
+        group.bench_function(*name, |b| {
+            b.iter(|| parse_program(black_box(source)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -1,7 +1,26 @@
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Serialize};
+
+// 📍 Where a statement starts and ends in the source (1-based lines and columns)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+// 💬 A comment between the arms of a `coincide`, kept so the formatter can print it back
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ArmComment {
+    // How many arms come before it (the `default` arm counts last)
+    pub arm: usize,
+    // Whether it ends the line of the arm (or the `coincide ...:` header) before it
+    pub trailing: bool,
+    pub text: String,
+}
 
 // 🧩 Represents the Abstract Syntax Tree (AST)
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum AST {
     // 📝 A program consisting of multiple AST nodes
     Program(Vec<AST>),
@@ -19,13 +38,61 @@ pub enum AST {
         args: Vec<AST>,
     },
 
+    // λ An anonymous function: `fn(x) { ... }` has a block body, `x => x * 2` an expression body
+    Lambda {
+        args: Box<AST>,
+        body: Box<AST>,
+    },
+
+    // 📞 A call of the function an expression evaluates to (`make_adder(1)(2)`)
+    Call {
+        callee: Box<AST>,
+        args: Vec<AST>,
+    },
+
+    // 🔍 An element of an array, string or tuple, or a value of a dictionary (`arr[0]`, `dict["key"]`)
+    Index {
+        object: Box<AST>,
+        index: Box<AST>,
+    },
+
+    // ✂️ A part of an array or string (`arr[1:3]`); a missing bound means the start or the end
+    Slice {
+        object: Box<AST>,
+        start: Option<Box<AST>>,
+        end: Option<Box<AST>>,
+    },
+
+    // 🏷️ A field of a value an expression evaluates to (`rows[0].name`); `point.x` is an `Identifier`
+    Field {
+        object: Box<AST>,
+        name: String,
+    },
+
+    // 🏗️ A `struct Point { x, y }` declaration; a field may name its type (`x: int`)
+    Struct {
+        name: String,
+        fields: Vec<(String, Option<String>)>,
+    },
+
+    // 🧷 An `impl Point { ... }` block: methods of a struct or builtin type, called as `p.len()`
+    Impl {
+        name: String,
+        methods: Vec<AST>,
+    },
+
+    // 🆕 A struct value built from named fields (`Point(x: 1, y: 2)`)
+    Construct {
+        name: String,
+        fields: Vec<(String, AST)>,
+    },
+
     // 🔙 A return statement with an expression
     Return(Box<AST>),
 
     // ✍️ A write operation with a list of expressions
     Write(Vec<AST>),
 
-
     // ➕ A binary operation with left operand, operator, and right operand
     BinaryOp {
         left: Box<AST>,
@@ -63,13 +130,40 @@ pub enum AST {
         value: Box<AST>,
     },
 
-    // 🎯 A switch-like expression with cases and an optional default case
+    // 🎭 Assigns the items of a tuple (or array) to several variables (`q, r is divmod(a, b)`)
+    Destructure {
+        names: Vec<String>,
+        value: Box<AST>,
+    },
+
+    // ✏️ An assignment to an element or field (`arr[0] is 5`, `dict["k"] is v`, `point.x is 1`)
+    Assign {
+        target: Box<AST>,
+        value: Box<AST>,
+    },
+
+    // 🎯 A switch-like expression with cases, an optional default case and the comments
+    // between them
     Coincide {
         expr: Box<AST>,
         cases: Vec<(AST, AST)>,
         default: Option<Box<AST>>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        comments: Vec<ArmComment>,
+    },
+
+    // 🔀 A `coincide` arm with several patterns (`1, 2 then ...`); it matches if any of them does
+    Alternatives(Vec<AST>),
+
+    // 📏 A `coincide` pattern matching the values from `start` to `end`, both included (`1..5`)
+    Range {
+        start: Box<AST>,
+        end: Box<AST>,
     },
 
+    // 🏷️ A `coincide` pattern matching every value of a type (`int`, `string`, ...)
+    TypePattern(String),
+
     // 🧱 A block of multiple AST nodes
     Block(Vec<AST>),
 
@@ -81,5 +175,277 @@ pub enum AST {
     },
 
     // 📋 A list of function arguments
-    FunctionArgs(Vec<AST>)
+    FunctionArgs(Vec<AST>),
+
+    // 📦 An `import` of a module, named by a path (`"utils.glt"`) or by an identifier (`utils`)
+    Import(Box<AST>),
+
+    // 🗃️ The top-level code of an imported module, put where it was first imported;
+    // its names start with the module's `name` and a dot
+    Module {
+        name: String,
+        body: Box<AST>,
+    },
+
+    // 🧪 A `test "name" { ... }` block, run by `glint test` and skipped by `glint run`
+    Test {
+        name: String,
+        body: Box<AST>,
+    },
+
+    // ✔️ An `assert condition, "message"` statement (the message is optional)
+    Assert {
+        condition: Box<AST>,
+        message: Option<Box<AST>>,
+    },
+
+    // 💬 A `#` comment (the text after `#`), kept so the formatter can preserve it
+    Comment(String),
+
+    // 📍 A statement together with its position in the source; once imports are linked, `file`
+    // names the module file a statement of a module comes from (`None` for the program itself)
+    Located {
+        span: Span,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file: Option<String>,
+        node: Box<AST>,
+    },
+}
+
+impl AST {
+    /// 📋 The parameter names of a `FunctionArgs` node (none for any other node)
+    pub fn param_names(&self) -> Vec<String> {
+        match self {
+            AST::FunctionArgs(args) => args
+                .iter()
+                .filter_map(|arg| match arg {
+                    AST::Identifier(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// 🧽 Returns a copy of the tree without `Located` wrappers, for comparing
+    /// trees parsed from differently laid out sources
+    pub fn without_locations(&self) -> AST {
+        let strip = |nodes: &[AST]| nodes.iter().map(AST::without_locations).collect();
+        let strip_box = |node: &AST| Box::new(node.without_locations());
+        match self {
+            AST::Located { node, .. } => node.without_locations(),
+            AST::Program(nodes) => AST::Program(strip(nodes)),
+            AST::Block(nodes) => AST::Block(strip(nodes)),
+            AST::Write(nodes) => AST::Write(strip(nodes)),
+            AST::Array(nodes) => AST::Array(strip(nodes)),
+            AST::Tuple(nodes) => AST::Tuple(strip(nodes)),
+            AST::FunctionArgs(nodes) => AST::FunctionArgs(strip(nodes)),
+            AST::Alternatives(nodes) => AST::Alternatives(strip(nodes)),
+            AST::FunctionCall { name, args } => AST::FunctionCall {
+                name: name.clone(),
+                args: strip(args),
+            },
+            AST::Function { name, args, body } => AST::Function {
+                name: name.clone(),
+                args: strip_box(args),
+                body: strip_box(body),
+            },
+            AST::Lambda { args, body } => AST::Lambda {
+                args: strip_box(args),
+                body: strip_box(body),
+            },
+            AST::Call { callee, args } => AST::Call {
+                callee: strip_box(callee),
+                args: strip(args),
+            },
+            AST::Index { object, index } => AST::Index {
+                object: strip_box(object),
+                index: strip_box(index),
+            },
+            AST::Slice { object, start, end } => AST::Slice {
+                object: strip_box(object),
+                start: start.as_deref().map(strip_box),
+                end: end.as_deref().map(strip_box),
+            },
+            AST::Field { object, name } => AST::Field {
+                object: strip_box(object),
+                name: name.clone(),
+            },
+            AST::Assign { target, value } => AST::Assign {
+                target: strip_box(target),
+                value: strip_box(value),
+            },
+            AST::Destructure { names, value } => AST::Destructure {
+                names: names.clone(),
+                value: strip_box(value),
+            },
+            AST::Range { start, end } => AST::Range {
+                start: strip_box(start),
+                end: strip_box(end),
+            },
+            AST::Impl { name, methods } => AST::Impl {
+                name: name.clone(),
+                methods: strip(methods),
+            },
+            AST::Construct { name, fields } => AST::Construct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| (field.clone(), value.without_locations()))
+                    .collect(),
+            },
+            AST::Return(expr) => AST::Return(strip_box(expr)),
+            AST::BinaryOp { left, op, right } => AST::BinaryOp {
+                left: strip_box(left),
+                op: op.clone(),
+                right: strip_box(right),
+            },
+            AST::Dictionary(pairs) => AST::Dictionary(
+                pairs
+                    .iter()
+                    .map(|(key, value)| (key.without_locations(), value.without_locations()))
+                    .collect(),
+            ),
+            AST::VariableAssign { name, value } => AST::VariableAssign {
+                name: name.clone(),
+                value: strip_box(value),
+            },
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                comments,
+            } => AST::Coincide {
+                expr: strip_box(expr),
+                cases: cases
+                    .iter()
+                    .map(|(case, action)| (case.without_locations(), action.without_locations()))
+                    .collect(),
+                default: default.as_deref().map(strip_box),
+                comments: comments.clone(),
+            },
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => AST::IfElse {
+                condition: strip_box(condition),
+                if_block: strip_box(if_block),
+                else_block: else_block.as_deref().map(strip_box),
+            },
+            AST::Test { name, body } => AST::Test {
+                name: name.clone(),
+                body: strip_box(body),
+            },
+            AST::Import(module) => AST::Import(strip_box(module)),
+            AST::Module { name, body } => AST::Module {
+                name: name.clone(),
+                body: strip_box(body),
+            },
+            AST::Assert { condition, message } => AST::Assert {
+                condition: strip_box(condition),
+                message: message.as_deref().map(strip_box),
+            },
+            AST::Identifier(_)
+            | AST::Integer(_)
+            | AST::Float(_)
+            | AST::Bool(_)
+            | AST::String(_)
+            | AST::TypePattern(_)
+            | AST::Struct { .. }
+            | AST::Comment(_) => self.clone(),
+        }
+    }
+
+    /// 🔢 Counts the nodes of the tree, not counting `Located` wrappers
+    pub fn node_count(&self) -> usize {
+        let count = |nodes: &[AST]| nodes.iter().map(AST::node_count).sum::<usize>();
+        match self {
+            AST::Located { node, .. } => node.node_count(),
+            AST::Program(nodes)
+            | AST::Block(nodes)
+            | AST::Write(nodes)
+            | AST::Array(nodes)
+            | AST::Tuple(nodes)
+            | AST::FunctionArgs(nodes)
+            | AST::Alternatives(nodes)
+            | AST::Impl { methods: nodes, .. }
+            | AST::FunctionCall { args: nodes, .. } => 1 + count(nodes),
+            AST::Function { args, body, .. } | AST::Lambda { args, body } => {
+                1 + args.node_count() + body.node_count()
+            }
+            AST::Call { callee, args } => 1 + callee.node_count() + count(args),
+            AST::Index {
+                object: left,
+                index: right,
+            }
+            | AST::Assign {
+                target: left,
+                value: right,
+            }
+            | AST::Range {
+                start: left,
+                end: right,
+            } => 1 + left.node_count() + right.node_count(),
+            AST::Slice { object, start, end } => {
+                1 + object.node_count()
+                    + start.as_ref().map_or(0, |start| start.node_count())
+                    + end.as_ref().map_or(0, |end| end.node_count())
+            }
+            AST::Return(node)
+            | AST::Field { object: node, .. }
+            | AST::VariableAssign { value: node, .. }
+            | AST::Destructure { value: node, .. }
+            | AST::Test { body: node, .. }
+            | AST::Import(node)
+            | AST::Module { body: node, .. } => 1 + node.node_count(),
+            AST::Assert { condition, message } => {
+                1 + condition.node_count()
+                    + message.as_ref().map_or(0, |message| message.node_count())
+            }
+            AST::BinaryOp { left, right, .. } => 1 + left.node_count() + right.node_count(),
+            AST::Construct { fields, .. } => {
+                1 + fields
+                    .iter()
+                    .map(|(_, value)| value.node_count())
+                    .sum::<usize>()
+            }
+            AST::Dictionary(pairs) => {
+                1 + pairs
+                    .iter()
+                    .map(|(key, value)| key.node_count() + value.node_count())
+                    .sum::<usize>()
+            }
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                ..
+            } => {
+                1 + expr.node_count()
+                    + cases
+                        .iter()
+                        .map(|(case, action)| case.node_count() + action.node_count())
+                        .sum::<usize>()
+                    + default.as_ref().map_or(0, |action| action.node_count())
+            }
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => {
+                1 + condition.node_count()
+                    + if_block.node_count()
+                    + else_block.as_ref().map_or(0, |block| block.node_count())
+            }
+            AST::Identifier(_)
+            | AST::Integer(_)
+            | AST::Float(_)
+            | AST::Bool(_)
+            | AST::String(_)
+            | AST::TypePattern(_)
+            | AST::Struct { .. }
+            | AST::Comment(_) => 1,
+        }
+    }
 }
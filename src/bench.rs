@@ -0,0 +1,109 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::ast::AST;
+use crate::error::RuntimeError;
+use crate::interpreter::interpreter::Interpreter;
+
+// 🏎️ Timing of Glint programs behind `glint bench`
+
+// 📈 Summary of the timed runs of one benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean: Duration,
+    // Sample standard deviation (zero for a single run)
+    pub std_dev: Duration,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// 📈 Summarizes a non-empty list of run times
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let runs = sorted.len();
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / runs as f64;
+        let variance = if runs > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        } else {
+            sorted[runs / 2]
+        };
+        Stats {
+            runs,
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            min: sorted[0],
+            median,
+            max: sorted[runs - 1],
+        }
+    }
+}
+
+/// 🏁 Runs a program `warmup` times untimed, then `runs` (at least one) times timed;
+/// what it writes is discarded
+pub fn bench_program(ast: &AST, warmup: usize, runs: usize) -> Result<Stats, RuntimeError> {
+    let ast_json = serde_json::to_string(ast).expect("Failed to serialize AST");
+    for _ in 0..warmup {
+        run_once(&ast_json)?;
+    }
+    let samples = (0..runs.max(1))
+        .map(|_| run_once(&ast_json))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Stats::from_samples(&samples))
+}
+
+/// ⏱️ Loads and interprets a program in a fresh interpreter, returning how long it took
+fn run_once(ast_json: &str) -> Result<Duration, RuntimeError> {
+    let start = Instant::now();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(io::sink()));
+    interpreter.load_from_json(ast_json);
+    match interpreter.interpret() {
+        // `exit(0)` is a normal way for a benchmark to finish
+        Ok(()) | Err(RuntimeError::Exit { code: 0 }) => Ok(start.elapsed()),
+        Err(err) => Err(err),
+    }
+}
+
+/// 📂 The `.glt` files to benchmark: files as given, directories searched recursively, sorted by path
+pub fn collect_programs(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut programs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut programs)?;
+        } else {
+            programs.push(path.clone());
+        }
+    }
+    Ok(programs)
+}
+
+fn collect_dir(dir: &Path, programs: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_dir(&path, programs)?;
+        } else if path.extension().is_some_and(|ext| ext == "glt") {
+            programs.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 🕰️ A duration in milliseconds, e.g. `12.345 ms`
+pub fn millis(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,664 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{Span, AST};
+use crate::error::RuntimeError;
+use crate::interpreter::builtins::builtin_arity;
+use crate::interpreter::structs::StructType;
+use crate::modules::import_name;
+use crate::parser::parser::TYPE_PATTERNS;
+
+// 🩺 Static checks run by `glint check`: problems the interpreter would only hit at runtime
+
+// ⚖️ How serious a finding is; only errors make `glint check` fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// 📝 A finding together with where it was made
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    // The lint rule that produced it (`None` for `glint check` errors)
+    pub rule: Option<&'static str>,
+}
+
+/// 🩺 Checks a parsed program for undefined variables, unknown functions, arity
+/// mismatches, struct constructions that do not fit their declaration and `return`
+/// outside of a function; `source` (when available) is
+/// used to point at the offending name rather than at the start of its statement
+pub fn check_program(ast: &AST, source: Option<&str>) -> Vec<Diagnostic> {
+    let statements = match ast {
+        AST::Program(statements) => statements.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    let mut checker = Checker {
+        functions: HashMap::new(),
+        structs: HashMap::new(),
+        globals: HashSet::from(["args".to_string()]),
+        modules: HashSet::new(),
+        locator: source.map(Locator::new),
+        statement_span: Span::default(),
+        occurrences: HashMap::new(),
+        diagnostics: Vec::new(),
+    };
+    for statement in statements {
+        checker.collect(statement, true);
+    }
+
+    // `args` is set by the interpreter before the program starts
+    let mut scope = HashSet::from(["args".to_string()]);
+    checker.statements(statements, &mut scope, false);
+    checker.diagnostics
+}
+
+struct Checker {
+    // Every function is hoisted, so calls may come before the definition
+    functions: HashMap<String, usize>,
+    // Struct declarations are hoisted the same way
+    structs: HashMap<String, StructType>,
+    // Variables assigned at the top level, visible inside functions once they run
+    globals: HashSet<String>,
+    // The names of the imported modules; what they contain is only known once they are linked
+    modules: HashSet<String>,
+    locator: Option<Locator>,
+    // The statement being checked and how often each name has been seen in it so far
+    statement_span: Span,
+    occurrences: HashMap<String, usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker {
+    /// 📋 Records function, struct and method definitions (anywhere) and top-level assignments
+    fn collect(&mut self, node: &AST, top_level: bool) {
+        match node {
+            AST::Located { node, .. } => self.collect(node, top_level),
+            AST::Function { name, args, body } => {
+                // Like the interpreter, a later definition replaces an earlier one
+                let arity = match args.as_ref() {
+                    AST::FunctionArgs(args) => args.len(),
+                    _ => 0,
+                };
+                self.functions.insert(name.clone(), arity);
+                self.collect(body, false);
+            }
+            AST::Struct { name, fields } => {
+                let ty = StructType {
+                    name: name.clone(),
+                    fields: fields.clone(),
+                };
+                self.structs.insert(name.clone(), ty);
+            }
+            AST::Impl { name, methods } => {
+                // A method can also be called through its type (`Point.len(p)`)
+                for method in methods {
+                    let node = match method {
+                        AST::Located { node, .. } => node.as_ref(),
+                        other => other,
+                    };
+                    if let AST::Function {
+                        name: method,
+                        args,
+                        body,
+                    } = node
+                    {
+                        let arity = args.param_names().len();
+                        self.functions.insert(format!("{}.{}", name, method), arity);
+                        self.collect(body, false);
+                    }
+                }
+            }
+            AST::Test { body, .. } => self.collect(body, false),
+            AST::Import(module) => {
+                self.modules.extend(import_name(module));
+            }
+            AST::VariableAssign { name, .. } if top_level => {
+                self.globals.insert(name.clone());
+            }
+            AST::Destructure { names, .. } if top_level => {
+                self.globals.extend(names.iter().cloned());
+            }
+            AST::Block(statements) => {
+                for statement in statements {
+                    self.collect(statement, top_level);
+                }
+            }
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                self.collect(if_block, top_level);
+                if let Some(else_block) = else_block {
+                    self.collect(else_block, top_level);
+                }
+            }
+            AST::Coincide { cases, default, .. } => {
+                for (_, action) in cases {
+                    self.collect(action, top_level);
+                }
+                if let Some(action) = default {
+                    self.collect(action, top_level);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 🧱 Checks statements in order; `scope` holds the variables assigned so far
+    fn statements(&mut self, statements: &[AST], scope: &mut HashSet<String>, in_function: bool) {
+        for statement in statements {
+            self.statement(statement, scope, in_function);
+        }
+    }
+
+    fn statement(&mut self, node: &AST, scope: &mut HashSet<String>, in_function: bool) {
+        match node {
+            AST::Located { span, node, .. } => {
+                let outer = (self.statement_span, std::mem::take(&mut self.occurrences));
+                self.statement_span = *span;
+                self.statement(node, scope, in_function);
+                (self.statement_span, self.occurrences) = outer;
+            }
+            AST::Write(items) => {
+                for item in items {
+                    self.expression(item, scope, in_function);
+                }
+            }
+            AST::VariableAssign { name, value } => {
+                self.seen(name);
+                self.expression(value, scope, in_function);
+                scope.insert(name.clone());
+            }
+            AST::Destructure { names, value } => {
+                for name in names {
+                    self.seen(name);
+                }
+                self.expression(value, scope, in_function);
+                scope.extend(names.iter().cloned());
+            }
+            AST::Assign { target, value } => {
+                // The variable an element or field is in must already exist
+                self.expression(target, scope, in_function);
+                self.expression(value, scope, in_function);
+            }
+            AST::Return(value) => {
+                if !in_function {
+                    let span = self.statement_span;
+                    self.report(span, "'return' outside of a function".to_string());
+                }
+                self.expression(value, scope, in_function);
+            }
+            AST::Function { args, body, .. } => {
+                // A function body only sees its parameters, its own assignments and the globals
+                let mut locals = args.param_names().into_iter().collect();
+                self.block(body, &mut locals, true);
+            }
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => {
+                self.expression(condition, scope, in_function);
+                self.block(if_block, scope, in_function);
+                if let Some(else_block) = else_block {
+                    self.block(else_block, scope, in_function);
+                }
+            }
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                ..
+            } => {
+                self.expression(expr, scope, in_function);
+                for (pattern, action) in cases {
+                    self.pattern(pattern, false, scope, in_function);
+                    self.statement(action, scope, in_function);
+                }
+                if let Some(action) = default {
+                    self.statement(action, scope, in_function);
+                }
+            }
+            AST::Struct { name, fields } => {
+                // A typed field takes a value of a type name or of a struct
+                for (field, ty) in fields {
+                    let Some(ty) = ty else { continue };
+                    let nth = self.seen(ty);
+                    let known = TYPE_PATTERNS.contains(&ty.as_str())
+                        || self.structs.contains_key(ty)
+                        || self.in_module(ty);
+                    if !known {
+                        let message = format!("Unknown type '{}' for {}.{}", ty, name, field);
+                        self.report(self.locate(ty, nth), message);
+                    }
+                }
+            }
+            AST::Impl { name, methods } => {
+                let nth = self.seen(name);
+                let known = TYPE_PATTERNS.contains(&name.as_str())
+                    || self.structs.contains_key(name)
+                    || self.in_module(name);
+                if !known {
+                    let message = format!("Unknown type '{}' for impl", name);
+                    self.report(self.locate(name, nth), message);
+                }
+                for method in methods {
+                    if let AST::Located { span, node, .. } = method {
+                        if let AST::Function {
+                            name: method, args, ..
+                        } = node.as_ref()
+                        {
+                            if args.param_names().is_empty() {
+                                let message = format!(
+                                    "Method '{}.{}' has no parameter for the value it is called on",
+                                    name, method
+                                );
+                                self.report(*span, message);
+                            }
+                        }
+                    }
+                    self.statement(method, scope, in_function);
+                }
+            }
+            AST::Test { body, .. } => {
+                // A test runs on its own: only `args` and the functions are defined before it
+                let mut locals = HashSet::from(["args".to_string()]);
+                self.block(body, &mut locals, false);
+            }
+            AST::Assert { condition, message } => {
+                self.expression(condition, scope, in_function);
+                if let Some(message) = message {
+                    self.expression(message, scope, in_function);
+                }
+            }
+            AST::Block(statements) => self.statements(statements, scope, in_function),
+            AST::Comment(_) => {}
+            expr => self.expression(expr, scope, in_function),
+        }
+    }
+
+    /// 🧩 Checks a `coincide` pattern: names inside `[...]` and `{...}` are assigned by it,
+    /// the rest of the pattern is read
+    fn pattern(
+        &mut self,
+        pattern: &AST,
+        nested: bool,
+        scope: &mut HashSet<String>,
+        in_function: bool,
+    ) {
+        match pattern {
+            // The name of a struct matches its values rather than binding anything
+            AST::Identifier(name) if self.structs.contains_key(name) => {
+                self.seen(name);
+            }
+            AST::Identifier(name) if name == "_" || nested => {
+                self.seen(name);
+                scope.insert(name.clone());
+            }
+            AST::Alternatives(patterns) => {
+                for pattern in patterns {
+                    self.pattern(pattern, nested, scope, in_function);
+                }
+            }
+            AST::Range { start, end } => {
+                self.expression(start, scope, in_function);
+                self.expression(end, scope, in_function);
+            }
+            AST::Array(patterns) | AST::Tuple(patterns) => {
+                for pattern in patterns {
+                    self.pattern(pattern, true, scope, in_function);
+                }
+            }
+            AST::Dictionary(pairs) => {
+                for (key, pattern) in pairs {
+                    match key {
+                        AST::Identifier(name) => {
+                            self.seen(name);
+                        }
+                        key => self.expression(key, scope, in_function),
+                    }
+                    self.pattern(pattern, true, scope, in_function);
+                }
+            }
+            AST::Construct { name, fields } => {
+                // Like a dictionary pattern, a struct pattern may leave fields out
+                if let Some((ty, span)) = self.declared(name) {
+                    for (field, _) in fields {
+                        if let Err(err) = ty.position(field) {
+                            self.report(span, err.to_string());
+                        }
+                    }
+                }
+                for (_, pattern) in fields {
+                    self.pattern(pattern, true, scope, in_function);
+                }
+            }
+            AST::TypePattern(_) => {}
+            value => self.expression(value, scope, in_function),
+        }
+    }
+
+    fn block(&mut self, block: &AST, scope: &mut HashSet<String>, in_function: bool) {
+        match block {
+            AST::Block(statements) => self.statements(statements, scope, in_function),
+            other => self.statement(other, scope, in_function),
+        }
+    }
+
+    /// 🧮 Checks the names an expression reads and the calls it makes
+    fn expression(&mut self, node: &AST, scope: &HashSet<String>, in_function: bool) {
+        match node {
+            AST::Identifier(name) => {
+                let nth = self.seen(name);
+                let defined = self.is_variable(name, scope, in_function)
+                    || self.functions.contains_key(name)
+                    || self.in_module(name)
+                    || self.is_field(name, scope, in_function);
+                if !defined {
+                    let err = RuntimeError::UndefinedVariable { name: name.clone() };
+                    self.report(self.locate(name, nth), err.to_string());
+                }
+            }
+            AST::FunctionCall { name, args } => {
+                let nth = self.seen(name);
+                let arity = match self.functions.get(name) {
+                    // A variable may hold a function of any arity
+                    _ if self.is_variable(name, scope, in_function) => Some((0, usize::MAX)),
+                    // A method of a variable (`p.len()`) depends on the type of its value, even
+                    // when the variable is named like a type with that method (`Point.len`)
+                    _ if self.is_field(name, scope, in_function) => Some((0, usize::MAX)),
+                    // A struct called like a function takes every field in order
+                    _ if self.structs.contains_key(name) => {
+                        let fields = self.structs[name].fields.len();
+                        Some((fields, fields))
+                    }
+                    Some(&arity) => Some((arity, arity)),
+                    None if self.in_module(name) => Some((0, usize::MAX)),
+                    None => builtin_arity(name),
+                };
+                match arity {
+                    None => {
+                        let err = RuntimeError::UndefinedFunction { name: name.clone() };
+                        self.report(self.locate(name, nth), err.to_string());
+                    }
+                    Some((min, max)) if args.len() < min || args.len() > max => {
+                        let err = RuntimeError::ArityMismatch {
+                            name: name.clone(),
+                            expected: if args.len() < min { min } else { max },
+                            found: args.len(),
+                        };
+                        self.report(self.locate(name, nth), err.to_string());
+                    }
+                    Some(_) => {}
+                }
+                for arg in args {
+                    self.expression(arg, scope, in_function);
+                }
+            }
+            AST::Construct { name, fields } => {
+                if let Some((ty, span)) = self.declared(name) {
+                    for err in construction_errors(&ty, fields) {
+                        self.report(span, err.to_string());
+                    }
+                }
+                for (_, value) in fields {
+                    self.expression(value, scope, in_function);
+                }
+            }
+            AST::BinaryOp { left, right, .. } => {
+                self.expression(left, scope, in_function);
+                self.expression(right, scope, in_function);
+            }
+            AST::Array(items) | AST::Tuple(items) => {
+                for item in items {
+                    self.expression(item, scope, in_function);
+                }
+            }
+            AST::Dictionary(pairs) => {
+                for (key, value) in pairs {
+                    // Bare identifiers used as keys are string keys, not variables
+                    match key {
+                        AST::Identifier(name) => {
+                            self.seen(name);
+                        }
+                        key => self.expression(key, scope, in_function),
+                    }
+                    self.expression(value, scope, in_function);
+                }
+            }
+            AST::Lambda { args, body } => {
+                // A lambda sees the variables around it and runs later, when the globals are set
+                let mut locals = scope.clone();
+                locals.extend(args.param_names());
+                self.block(body, &mut locals, true);
+            }
+            AST::Index { object, index } => {
+                self.expression(object, scope, in_function);
+                self.expression(index, scope, in_function);
+            }
+            AST::Slice { object, start, end } => {
+                self.expression(object, scope, in_function);
+                for bound in [start, end].into_iter().flatten() {
+                    self.expression(bound, scope, in_function);
+                }
+            }
+            AST::Field { object, .. } => self.expression(object, scope, in_function),
+            AST::Call { callee, args } => {
+                self.expression(callee, scope, in_function);
+                for arg in args {
+                    self.expression(arg, scope, in_function);
+                }
+            }
+            AST::Located { node, .. } => self.expression(node, scope, in_function),
+            _ => {}
+        }
+    }
+
+    /// 🔤 Whether `name` is a variable that is assigned where it is read
+    fn is_variable(&self, name: &str, scope: &HashSet<String>, in_function: bool) -> bool {
+        scope.contains(name) || (in_function && self.globals.contains(name))
+    }
+
+    /// 🏷️ Whether `name` reads a field of a variable (`point.x`)
+    fn is_field(&self, name: &str, scope: &HashSet<String>, in_function: bool) -> bool {
+        name.split_once('.')
+            .is_some_and(|(variable, _)| self.is_variable(variable, scope, in_function))
+    }
+
+    /// 🏗️ The declaration of the struct named in a construction and where the name is;
+    /// reports the name if no struct has it (structs of imported modules are only known once
+    /// they are linked)
+    fn declared(&mut self, name: &str) -> Option<(StructType, Span)> {
+        let nth = self.seen(name);
+        if let Some(ty) = self.structs.get(name) {
+            return Some((ty.clone(), self.locate(name, nth)));
+        }
+        if !self.in_module(name) {
+            let err = RuntimeError::UndefinedStruct {
+                name: name.to_string(),
+            };
+            self.report(self.locate(name, nth), err.to_string());
+        }
+        None
+    }
+
+    /// 📦 Whether `name` is qualified with an imported module (`utils.helper`)
+    fn in_module(&self, name: &str) -> bool {
+        name.split_once('.')
+            .is_some_and(|(module, _)| self.modules.contains(module))
+    }
+
+    /// 👀 Notes another occurrence of `name` in the current statement; returns how many came before
+    fn seen(&mut self, name: &str) -> usize {
+        let count = self.occurrences.entry(name.to_string()).or_insert(0);
+        *count += 1;
+        *count - 1
+    }
+
+    /// 📍 Where the `nth` occurrence of `name` in the current statement is
+    fn locate(&self, name: &str, nth: usize) -> Span {
+        self.locator
+            .as_ref()
+            .and_then(|locator| locator.find(self.statement_span, name, nth))
+            .unwrap_or(self.statement_span)
+    }
+
+    fn report(&mut self, span: Span, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            span,
+            message,
+            rule: None,
+        });
+    }
+}
+
+/// 🏗️ What the interpreter would reject in a construction of `ty`: unknown, repeated and
+/// missing fields, and literals of the wrong type for their field
+fn construction_errors(ty: &StructType, fields: &[(String, AST)]) -> Vec<RuntimeError> {
+    let mut errors = Vec::new();
+    let mut given = HashSet::new();
+    for (field, value) in fields {
+        match ty.position(field) {
+            Err(err) => errors.push(err),
+            Ok(_) if !given.insert(field) => errors.push(ty.repeated(field)),
+            Ok(position) => {
+                if let Some((type_name, struct_name)) = literal_type(value) {
+                    if let Err(err) = ty.check_type(position, type_name, struct_name) {
+                        errors.push(err);
+                    }
+                }
+            }
+        }
+    }
+    for (field, _) in &ty.fields {
+        if !given.contains(field) {
+            errors.push(ty.missing(field));
+        }
+    }
+    errors
+}
+
+/// 🏷️ The type of the value an expression always evaluates to (and the name of its struct,
+/// for a struct), when that is known without running it
+fn literal_type(node: &AST) -> Option<(&'static str, Option<&str>)> {
+    let type_name = match node {
+        AST::Integer(_) => "int",
+        AST::Float(_) => "float",
+        AST::Bool(_) => "bool",
+        AST::String(_) => "string",
+        AST::Array(_) => "array",
+        AST::Dictionary(_) => "dictionary",
+        AST::Tuple(_) => "tuple",
+        AST::Lambda { .. } => "function",
+        AST::Construct { name, .. } => return Some(("struct", Some(name))),
+        _ => return None,
+    };
+    Some((type_name, None))
+}
+
+// 🔎 Finds names inside the source text of a statement
+pub struct Locator {
+    source: String,
+    line_starts: Vec<usize>,
+}
+
+impl Locator {
+    /// 🆕 Indexes the lines of `source`
+    pub fn new(source: &str) -> Self {
+        Locator {
+            source: source.to_string(),
+            line_starts: std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        }
+    }
+
+    /// 🎯 The span of the `nth` occurrence of the word (or qualified name) `name` within `within`,
+    /// skipping string literals and comments
+    pub fn find(&self, within: Span, name: &str, nth: usize) -> Option<Span> {
+        let start = self.offset(within.line, within.column)?;
+        let end = self.offset(within.end_line, within.end_column)?;
+        let text = self.source.get(start..end)?;
+
+        let mut found = 0;
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    for (_, c) in chars.by_ref() {
+                        if c == '"' {
+                            break;
+                        }
+                    }
+                }
+                '#' => {
+                    for (_, c) in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                c if is_word_char(c) => {
+                    let mut word_end = i + c.len_utf8();
+                    while let Some(&(j, c)) = chars.peek() {
+                        // A qualified name (`utils.helper`) is one word
+                        let qualified = c == '.'
+                            && text[j + 1..].chars().next().is_some_and(is_word_char);
+                        if !is_word_char(c) && !qualified {
+                            break;
+                        }
+                        word_end = j + c.len_utf8();
+                        chars.next();
+                    }
+                    if &text[i..word_end] == name {
+                        if found == nth {
+                            let (line, column) = self.position(start + i);
+                            let (end_line, end_column) = self.position(start + word_end);
+                            return Some(Span {
+                                line,
+                                column,
+                                end_line,
+                                end_column,
+                            });
+                        }
+                        found += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// 📍 The byte offset of a 1-based line and column
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let line_text = &self.source[line_start..];
+        let within_line = line_text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(line_text.len()))
+            .nth(column.checked_sub(1)?)?;
+        Some(line_start + within_line)
+    }
+
+    /// 📍 The 1-based line and column of a byte offset
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let column = self.source[self.line_starts[line - 1]..offset]
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
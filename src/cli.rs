@@ -0,0 +1,241 @@
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+// 🧭 Command-line interface definition
+
+/// ✧Glint✧ — a lightweight programming language
+#[derive(Debug, Parser)]
+#[command(
+    name = "glint",
+    version,
+    propagate_version = true,
+    allow_external_subcommands = true
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    /// Evaluate inline code, e.g. `glint -e 'write 1 + 2'`
+    #[arg(short = 'e', long = "eval", value_name = "CODE")]
+    pub eval: Option<String>,
+
+    /// Arguments passed to the inline code (after `--`), split off in `parse`
+    #[arg(skip)]
+    pub script_args: Vec<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// 🌐 Flags accepted by every subcommand
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Display dev info after the command: time and memory per phase, AST size, OS
+    #[arg(long, global = true)]
+    pub dev: bool,
+
+    /// Print the dev info as JSON on stderr (implies --dev)
+    #[arg(long, global = true)]
+    pub dev_json: bool,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a script
+    Run(RunArgs),
+
+    /// Create a new project with a glint.toml manifest
+    New {
+        /// Directory to create; its name is the project name
+        path: PathBuf,
+    },
+
+    /// Parse scripts and report problems without running them
+    Check {
+        /// Scripts to check
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Report likely mistakes using configurable rules
+    Lint(LintArgs),
+
+    /// Format scripts in place
+    Fmt {
+        /// Scripts to format
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Only report files that are not formatted (exit code 1 if any)
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Print the AST of a script as JSON
+    Ast {
+        /// Script to parse
+        file: PathBuf,
+    },
+
+    /// Start an interactive session
+    Repl,
+
+    /// Start a language server on stdin/stdout for editors
+    Lsp,
+
+    /// Start a debug adapter on stdin/stdout for editors
+    Dap,
+
+    /// Run a script under the terminal debugger
+    Debug {
+        /// Script to debug
+        file: PathBuf,
+
+        /// Arguments passed to the script (after `--`)
+        #[arg(last = true)]
+        script_args: Vec<String>,
+    },
+
+    /// Run the test suite
+    Test {
+        /// Test files or directories (defaults to `tests`)
+        paths: Vec<PathBuf>,
+
+        /// Rewrite the expected output of every test with what it printed
+        #[arg(long)]
+        bless: bool,
+    },
+
+    /// Compile a script to a binary AST file (.gltc) that `run` can execute
+    Build {
+        /// Script to compile
+        file: PathBuf,
+
+        /// Output file (defaults to the script name with a .gltc extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Run the benchmark suite
+    Bench {
+        /// Benchmark files or directories (defaults to `benches`)
+        paths: Vec<PathBuf>,
+
+        /// Untimed runs before measuring
+        #[arg(long, default_value_t = 3)]
+        warmup: usize,
+
+        /// Timed runs per benchmark
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+    },
+
+    /// Display version info and usage
+    Info,
+
+    /// Generate a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+
+    /// `glint script.glt args...` runs a script directly (used by `#!/usr/bin/env glint`)
+    #[command(external_subcommand)]
+    Script(Vec<String>),
+}
+
+/// ▶️ Arguments of `glint run`
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Script to run (.glt source, .gltc built by `glint build`, or `-` for stdin);
+    /// defaults to the entry point of the project's glint.toml
+    pub file: Option<PathBuf>,
+
+    /// Display JIT statistics (requires the `jit` feature)
+    #[arg(long)]
+    pub jit_stats: bool,
+
+    /// Print call counts and timings per function and the hottest lines (disables the JIT)
+    #[arg(long)]
+    pub profile: bool,
+
+    /// Write the profile as folded stacks for flame graph tools
+    #[arg(long, value_name = "FILE")]
+    pub profile_out: Option<PathBuf>,
+
+    /// Arguments passed to the script (after `--`)
+    #[arg(last = true)]
+    pub script_args: Vec<String>,
+}
+
+/// 🔧 Parses the process arguments, still accepting the legacy single-dash `-dev` flag
+pub fn parse() -> Cli {
+    let mut args: Vec<String> = std::env::args().collect();
+
+    // Everything after `--` belongs to the script and is passed through untouched
+    let separator = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    for arg in &mut args[..separator] {
+        if arg == "-dev" {
+            *arg = "--dev".to_string();
+        }
+    }
+
+    // `glint -e CODE -- a b` has no subcommand to collect `a b`, so split them off here
+    let eval = args[..separator]
+        .iter()
+        .any(|arg| arg == "-e" || arg == "--eval" || arg.starts_with("--eval="));
+    let script_args = if eval && separator < args.len() {
+        args.split_off(separator).split_off(1)
+    } else {
+        Vec::new()
+    };
+
+    let mut cli = Cli::parse_from(args);
+    if cli.eval.is_some() && cli.command.is_some() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "the argument '--eval <CODE>' cannot be used with a subcommand or script",
+            )
+            .exit();
+    }
+    cli.script_args = script_args;
+    cli
+}
+
+/// 🧹 Arguments of `glint lint`
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    /// Scripts to lint
+    #[arg(required_unless_present = "rules")]
+    pub files: Vec<PathBuf>,
+
+    /// Configuration file with a `[lint]` table (defaults to the nearest glint.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Turn a rule off
+    #[arg(short = 'A', long, value_name = "RULE")]
+    pub allow: Vec<String>,
+
+    /// Report a rule as a warning
+    #[arg(short = 'W', long, value_name = "RULE")]
+    pub warn: Vec<String>,
+
+    /// Report a rule as an error (exit code 1)
+    #[arg(short = 'D', long, value_name = "RULE")]
+    pub deny: Vec<String>,
+
+    /// List the available rules and their default levels
+    #[arg(long)]
+    pub rules: bool,
+}
@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::ast::{Span, AST};
+use crate::debugger::{
+    call_stack, describe_line, executable_lines, resolve_breakpoint, File, StepMode, Stepper,
+    StopReason,
+};
+use crate::error::RuntimeError;
+use crate::interpreter::hook::Hook;
+use crate::interpreter::interpreter::Interpreter;
+use crate::parser::parser::parse_expression;
+
+// 🐞 Terminal debugger behind `glint debug`
+
+const HELP: &str = "\
+  Commands:
+    break <line>     Pause before the statement on <line> (or the next one);
+                     <file>:<line> places it in an imported module
+    delete <line>    Remove the breakpoint on <line> (or <file>:<line>)
+    step             Run to the next statement, entering calls
+    next             Run to the next statement, stepping over calls
+    continue         Run until a breakpoint or the end of the program
+    print <expr>     Evaluate an expression in the current frame
+    locals           List the variables of the current frame
+    backtrace        Show the call stack
+    help             Show this help
+    quit             Stop the program and leave";
+
+/// 🐞 Runs a parsed program under the debugger, reading commands from `input`;
+/// the program starts paused before its first statement
+pub fn debug<R: BufRead + 'static, W: Write + 'static>(
+    source: &str,
+    ast: &AST,
+    script_args: &[String],
+    input: R,
+    output: W,
+) -> Result<(), RuntimeError> {
+    let ast_json = serde_json::to_string(ast).expect("Failed to serialize AST");
+    let mut interpreter = Interpreter::new();
+    interpreter.set_args(script_args);
+    interpreter.load_from_json(&ast_json);
+    interpreter.set_hook(Box::new(Console {
+        input: Box::new(input),
+        output: Box::new(output),
+        stepper: Stepper::new(true),
+        lines: executable_lines(ast),
+        sources: HashMap::from([(None, source.lines().map(str::to_string).collect())]),
+    }));
+    interpreter.interpret()
+}
+
+// ⌨️ Pauses the program and reads commands until one of them resumes it
+struct Console {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    stepper: Stepper,
+    // Lines a breakpoint can be placed on, in the program and in each module
+    lines: BTreeMap<File, BTreeSet<usize>>,
+    // The lines of the program and of the modules shown so far
+    sources: HashMap<File, Vec<String>>,
+}
+
+impl Hook for Console {
+    fn statement(
+        &mut self,
+        interpreter: &mut Interpreter,
+        span: Span,
+        file: Option<Rc<str>>,
+    ) -> Result<(), RuntimeError> {
+        let depth = interpreter.frames().len();
+        let file = file.as_deref();
+        let Some(reason) = self.stepper.should_stop(file, span.line, depth) else {
+            return Ok(());
+        };
+        if reason == StopReason::Breakpoint {
+            let frame = &call_stack(interpreter)[0];
+            self.say(format!(
+                "Breakpoint, {} in {}",
+                describe_line(file, span.line),
+                frame.label()
+            ));
+        }
+        self.show_line(file, span.line);
+
+        loop {
+            let _ = write!(self.output, "(glint) ");
+            let _ = self.output.flush();
+            let mut line = String::new();
+            // End of input leaves the debugger like `quit`
+            if self.input.read_line(&mut line).unwrap_or(0) == 0 {
+                return Err(RuntimeError::Exit { code: 0 });
+            }
+            let (command, argument) = line
+                .trim()
+                .split_once(char::is_whitespace)
+                .map(|(command, argument)| (command, argument.trim()))
+                .unwrap_or((line.trim(), ""));
+
+            match command {
+                "" => {}
+                "break" | "b" => match self.location(argument) {
+                    Ok((file, line)) => {
+                        let lines = self.lines.get(&file);
+                        match lines.and_then(|lines| resolve_breakpoint(lines, line)) {
+                            Some(line) => {
+                                let place = describe_line(file.as_deref(), line);
+                                self.stepper.breakpoints.insert((file, line));
+                                self.say(format!("Breakpoint set on {}", place));
+                            }
+                            None => self.say(format!(
+                                "No statement on or after {}",
+                                describe_line(file.as_deref(), line)
+                            )),
+                        }
+                    }
+                    Err(message) => self.say(message),
+                },
+                "delete" | "d" => match self.location(argument) {
+                    Ok(location) if self.stepper.breakpoints.remove(&location) => {
+                        let (file, line) = location;
+                        let place = describe_line(file.as_deref(), line);
+                        self.say(format!("Deleted the breakpoint on {}", place));
+                    }
+                    _ => self.say(format!("No breakpoint on line '{}'", argument)),
+                },
+                "step" | "s" => {
+                    self.stepper.resume(StepMode::StepIn);
+                    return Ok(());
+                }
+                "next" | "n" => {
+                    self.stepper.resume(StepMode::StepOver(depth));
+                    return Ok(());
+                }
+                "continue" | "c" => {
+                    self.stepper.resume(StepMode::Continue);
+                    return Ok(());
+                }
+                "print" | "p" => {
+                    let value = parse_expression(argument)
+                        .map_err(|err| err.to_string())
+                        .and_then(|ast| {
+                            let expr = serde_json::to_value(&ast).expect("Failed to serialize AST");
+                            interpreter.evaluate(&expr).map_err(|err| err.to_string())
+                        });
+                    match value {
+                        Ok(value) => self.say(value.repr()),
+                        Err(message) => self.say(format!("Error: {}", message)),
+                    }
+                }
+                "locals" | "l" => {
+                    // At the top level the program's locals are its globals
+                    let locals = interpreter
+                        .frames()
+                        .last()
+                        .map(|frame| frame.variables.borrow());
+                    let variables = match &locals {
+                        Some(locals) => &**locals,
+                        None => interpreter.variables(),
+                    };
+                    let mut variables: Vec<_> = variables.iter().collect();
+                    variables.sort_by(|a, b| a.0.cmp(b.0));
+                    for (name, value) in variables {
+                        self.say(format!("{} = {}", name, value.repr()));
+                    }
+                }
+                "backtrace" | "bt" => {
+                    for (i, entry) in call_stack(interpreter).iter().enumerate() {
+                        let place = describe_line(entry.file.as_deref(), entry.line);
+                        self.say(format!("#{} {} at {}", i, entry.label(), place));
+                    }
+                }
+                "help" | "h" => self.say(HELP.to_string()),
+                "quit" | "q" => return Err(RuntimeError::Exit { code: 0 }),
+                _ => self.say(format!(
+                    "Unknown command '{}'. Type help for a list.",
+                    command
+                )),
+            }
+        }
+    }
+}
+
+impl Console {
+    fn say(&mut self, text: String) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    /// 📄 Prints the source line the program is paused on, reading a module's file the first
+    /// time one of its lines is shown
+    fn show_line(&mut self, file: Option<&str>, line: usize) {
+        let source = self
+            .sources
+            .entry(file.map(str::to_string))
+            .or_insert_with(|| {
+                let text = file.and_then(|file| std::fs::read_to_string(file).ok());
+                text.unwrap_or_default()
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            });
+        let text = source.get(line - 1).cloned().unwrap_or_default();
+        match file {
+            Some(file) => self.say(format!("{} {:>4} | {}", file, line, text)),
+            None => self.say(format!("{:>4} | {}", line, text)),
+        }
+    }
+
+    /// 🎯 Parses `<line>`, a line of the program, or `<file>:<line>`, a line of the imported
+    /// module whose path is or ends with `<file>`
+    fn location(&self, argument: &str) -> Result<(File, usize), String> {
+        let (file, line) = match argument.rsplit_once(':') {
+            Some((file, line)) => (Some(file.trim()), line),
+            None => (None, argument),
+        };
+        let Ok(line) = line.trim().parse() else {
+            return Err("Usage: break [<file>:]<line>".to_string());
+        };
+        let file = match file {
+            Some(file) => Some(
+                self.lines
+                    .keys()
+                    .flatten()
+                    .find(|module| Path::new(module).ends_with(file))
+                    .ok_or_else(|| format!("No imported module '{}'", file))?
+                    .clone(),
+            ),
+            None => None,
+        };
+        Ok((file, line))
+    }
+}
@@ -0,0 +1,565 @@
+use serde_json::{json, Value as Json};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::ast::{Span, AST};
+use crate::debugger::{
+    call_stack, executable_lines, resolve_breakpoint, File, StepMode, Stepper, StopReason,
+};
+use crate::error::RuntimeError;
+use crate::interpreter::hook::Hook;
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::value::Value;
+use crate::lsp::{read_message, write_message};
+use crate::manifest;
+use crate::modules::{canonical, link_program};
+use crate::parser::parser::{parse_expression, parse_program};
+
+// 🐞 Debug adapter for editors, spoken over stdio by `glint dap`
+
+// Glint runs a single thread, so every request names this one
+const THREAD_ID: i64 = 1;
+
+/// 🚀 Serves a debug session from stdin until the client disconnects
+pub fn run_stdio() -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+/// 🔁 Reads framed DAP requests from `reader` and writes responses and events to `writer`
+pub fn serve<R: BufRead + 'static, W: Write + 'static>(reader: R, writer: W) -> io::Result<()> {
+    let client = Rc::new(RefCell::new(Client {
+        reader: Box::new(reader),
+        writer: Box::new(writer),
+        seq: 0,
+    }));
+    let session = Rc::new(RefCell::new(Session {
+        client: client.clone(),
+        program: None,
+        stepper: Stepper::new(false),
+        handles: Vec::new(),
+        disconnected: false,
+        failure: None,
+    }));
+
+    loop {
+        let Some(request) = client.borrow_mut().read()? else {
+            return Ok(());
+        };
+        let action = session.borrow_mut().handle(&request, None)?;
+        match action {
+            Action::Wait | Action::Resume => {}
+            Action::Disconnect => return Ok(()),
+            Action::Run => {
+                run(&session)?;
+                if session.borrow().disconnected {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// 🎬 Runs the launched program with the debugger attached, then reports how it ended
+fn run(session: &Rc<RefCell<Session>>) -> io::Result<()> {
+    let Some(program) = session.borrow().program.clone() else {
+        return Ok(());
+    };
+    let ast_json = serde_json::to_string(&program.ast).expect("Failed to serialize AST");
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_args(&program.args);
+    interpreter.load_from_json(&ast_json);
+    interpreter.set_output(Box::new(Output {
+        client: session.borrow().client.clone(),
+        buffer: Vec::new(),
+    }));
+    interpreter.set_hook(Box::new(Debugger(session.clone())));
+    let result = interpreter.interpret();
+    // Dropping the interpreter flushes any output without a trailing newline
+    drop(interpreter);
+
+    let mut session = session.borrow_mut();
+    if let Some(err) = session.failure.take() {
+        return Err(err);
+    }
+    if session.disconnected {
+        return Ok(());
+    }
+    let mut client = session.client.borrow_mut();
+    let code = match result {
+        Ok(()) => 0,
+        Err(RuntimeError::Exit { code }) => code,
+        Err(err) => {
+            let output = format!("Runtime error: {}\n", err);
+            client.event("output", json!({ "category": "stderr", "output": output }))?;
+            1
+        }
+    };
+    client.event("exited", json!({ "exitCode": code }))?;
+    client.event("terminated", json!({}))
+}
+
+// 📡 The connection to the editor
+struct Client {
+    reader: Box<dyn BufRead>,
+    writer: Box<dyn Write>,
+    // Sequence number of the last message sent
+    seq: i64,
+}
+
+impl Client {
+    fn read(&mut self) -> io::Result<Option<Json>> {
+        read_message(&mut self.reader)
+    }
+
+    fn send(&mut self, mut message: Json) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn respond(&mut self, request: &Json, body: Json) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn fail(&mut self, request: &Json, message: String) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+}
+
+// 📄 The script named by `launch`
+#[derive(Clone)]
+struct Program {
+    path: PathBuf,
+    ast: AST,
+    args: Vec<String>,
+    // Lines a breakpoint can be placed on, in the program and in each module
+    lines: BTreeMap<File, BTreeSet<usize>>,
+}
+
+impl Program {
+    /// 🔎 Which file of the program an editor's `path` names, if any
+    fn file_of(&self, path: &Path) -> Option<File> {
+        let path = canonical(path);
+        if path == canonical(&self.path) {
+            return Some(None);
+        }
+        self.lines
+            .keys()
+            .flatten()
+            .find(|module| canonical(Path::new(module)) == path)
+            .map(|module| Some(module.clone()))
+    }
+}
+
+// 🚦 What the request loop does after handling a request
+enum Action {
+    Wait,
+    Run,
+    Resume,
+    Disconnect,
+}
+
+struct Session {
+    client: Rc<RefCell<Client>>,
+    program: Option<Program>,
+    stepper: Stepper,
+    // Variables shown for each `variablesReference` handed out since the last stop
+    handles: Vec<Vec<(String, Value)>>,
+    disconnected: bool,
+    // An I/O error hit while the program was paused
+    failure: Option<io::Error>,
+}
+
+impl Session {
+    /// 📨 Handles one request; `paused` is the interpreter when the program is stopped
+    fn handle(&mut self, request: &Json, paused: Option<&mut Interpreter>) -> io::Result<Action> {
+        let args = &request["arguments"];
+        let command = request["command"].as_str().unwrap_or_default();
+        let connection = self.client.clone();
+        let mut client = connection.borrow_mut();
+
+        match (command, paused) {
+            ("initialize", _) => {
+                let capabilities = json!({
+                    "supportsConfigurationDoneRequest": true,
+                    "supportsEvaluateForHovers": true,
+                });
+                client.respond(request, capabilities)?;
+            }
+            ("launch", _) => match launch(args) {
+                Ok(program) => {
+                    self.stepper = Stepper::new(args["stopOnEntry"].as_bool().unwrap_or(false));
+                    self.program = Some(program);
+                    client.respond(request, json!({}))?;
+                    // Breakpoints can only be checked against the program, so ask for them now
+                    client.event("initialized", json!({}))?;
+                }
+                Err(message) => client.fail(request, message)?,
+            },
+            ("setBreakpoints", _) => {
+                // The request replaces the breakpoints of one file: the program or a module
+                let path = args["source"]["path"].as_str().map(Path::new);
+                let file = self
+                    .program
+                    .as_ref()
+                    .zip(path)
+                    .and_then(|(program, path)| program.file_of(path));
+                let lines = self
+                    .program
+                    .as_ref()
+                    .zip(file.as_ref())
+                    .and_then(|(program, file)| program.lines.get(file).cloned())
+                    .unwrap_or_default();
+                if let Some(file) = &file {
+                    self.stepper.breakpoints.retain(|(at, _)| at != file);
+                }
+                let mut breakpoints = Vec::new();
+                for requested in args["breakpoints"].as_array().into_iter().flatten() {
+                    let line = requested["line"].as_u64().unwrap_or(0) as usize;
+                    match (&file, resolve_breakpoint(&lines, line)) {
+                        (Some(file), Some(line)) => {
+                            self.stepper.breakpoints.insert((file.clone(), line));
+                            breakpoints.push(json!({ "verified": true, "line": line }));
+                        }
+                        (Some(_), None) => breakpoints.push(json!({
+                            "verified": false,
+                            "line": line,
+                            "message": "No statement on or after this line",
+                        })),
+                        (None, _) => breakpoints.push(json!({
+                            "verified": false,
+                            "line": line,
+                            "message": "This file is not part of the launched program",
+                        })),
+                    }
+                }
+                client.respond(request, json!({ "breakpoints": breakpoints }))?;
+            }
+            ("configurationDone", None) => {
+                client.respond(request, json!({}))?;
+                return Ok(Action::Run);
+            }
+            ("threads", _) => {
+                let threads = json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] });
+                client.respond(request, threads)?;
+            }
+            ("disconnect" | "terminate", _) => {
+                client.respond(request, json!({}))?;
+                self.disconnected = true;
+                return Ok(Action::Disconnect);
+            }
+            ("stackTrace", Some(interpreter)) => {
+                let frames: Vec<Json> = call_stack(interpreter)
+                    .iter()
+                    .enumerate()
+                    .map(|(id, entry)| {
+                        json!({
+                            "id": id,
+                            "name": entry.label(),
+                            "line": entry.line,
+                            "column": 1,
+                            "source": self.source(entry.file.as_deref()),
+                        })
+                    })
+                    .collect();
+                let total = frames.len();
+                client.respond(
+                    request,
+                    json!({ "stackFrames": frames, "totalFrames": total }),
+                )?;
+            }
+            ("scopes", Some(interpreter)) => {
+                let frame = args["frameId"].as_u64().unwrap_or(0) as usize;
+                let depth = interpreter.frames().len();
+                let mut scopes = Vec::new();
+                // Frame 0 is the innermost call; the last one is the program itself
+                if frame < depth {
+                    let locals = sorted(
+                        interpreter.frames()[depth - 1 - frame]
+                            .variables
+                            .borrow()
+                            .iter(),
+                    );
+                    let reference = self.handle_for(locals);
+                    scopes.push(json!({
+                        "name": "Locals",
+                        "variablesReference": reference,
+                        "expensive": false,
+                    }));
+                }
+                let globals = sorted(interpreter.variables().iter());
+                let reference = self.handle_for(globals);
+                scopes.push(json!({
+                    "name": "Globals",
+                    "variablesReference": reference,
+                    "expensive": false,
+                }));
+                client.respond(request, json!({ "scopes": scopes }))?;
+            }
+            ("variables", Some(_)) => {
+                let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+                let entries = match reference.checked_sub(1) {
+                    Some(index) => self.handles.get(index).cloned().unwrap_or_default(),
+                    None => Vec::new(),
+                };
+                let variables: Vec<Json> = entries
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let reference = match children(&value) {
+                            Some(children) => self.handle_for(children),
+                            None => 0,
+                        };
+                        json!({
+                            "name": name,
+                            "value": value.repr(),
+                            "variablesReference": reference,
+                        })
+                    })
+                    .collect();
+                client.respond(request, json!({ "variables": variables }))?;
+            }
+            ("evaluate", Some(interpreter)) => {
+                // The expression may call functions that `write`, which goes through the client
+                drop(client);
+                let result = parse_expression(args["expression"].as_str().unwrap_or_default())
+                    .map_err(|err| err.to_string())
+                    .and_then(|ast| {
+                        let expr = serde_json::to_value(&ast).expect("Failed to serialize AST");
+                        interpreter.evaluate(&expr).map_err(|err| err.to_string())
+                    });
+                let mut client = connection.borrow_mut();
+                match result {
+                    Ok(value) => {
+                        let reference = match children(&value) {
+                            Some(children) => self.handle_for(children),
+                            None => 0,
+                        };
+                        client.respond(
+                            request,
+                            json!({ "result": value.repr(), "variablesReference": reference }),
+                        )?;
+                    }
+                    Err(message) => client.fail(request, message)?,
+                }
+            }
+            ("continue", Some(_)) => {
+                self.stepper.resume(StepMode::Continue);
+                client.respond(request, json!({ "allThreadsContinued": true }))?;
+                return Ok(Action::Resume);
+            }
+            ("next", Some(interpreter)) => {
+                self.stepper
+                    .resume(StepMode::StepOver(interpreter.frames().len()));
+                client.respond(request, json!({}))?;
+                return Ok(Action::Resume);
+            }
+            ("stepIn", Some(_)) => {
+                self.stepper.resume(StepMode::StepIn);
+                client.respond(request, json!({}))?;
+                return Ok(Action::Resume);
+            }
+            ("stepOut", Some(interpreter)) => {
+                self.stepper
+                    .resume(StepMode::StepOut(interpreter.frames().len()));
+                client.respond(request, json!({}))?;
+                return Ok(Action::Resume);
+            }
+            ("pause", Some(_)) => client.respond(request, json!({}))?,
+            (
+                "stackTrace" | "scopes" | "variables" | "evaluate" | "continue" | "next" | "stepIn"
+                | "stepOut" | "pause",
+                None,
+            ) => client.fail(request, "The program is not paused".to_string())?,
+            (command, _) => client.fail(request, format!("Unsupported request '{}'", command))?,
+        }
+        Ok(Action::Wait)
+    }
+
+    /// 📄 The DAP `source` of the launched program, or of one of the module files it imports
+    fn source(&self, file: Option<&str>) -> Json {
+        let path = match (&self.program, file) {
+            (_, Some(file)) => canonical(Path::new(file)),
+            (Some(program), None) => program.path.clone(),
+            (None, None) => return Json::Null,
+        };
+        json!({
+            "name": path.file_name().map(|name| name.to_string_lossy()),
+            "path": path,
+        })
+    }
+
+    /// 🔖 Remembers variables to show later and returns their `variablesReference`
+    fn handle_for(&mut self, variables: Vec<(String, Value)>) -> usize {
+        self.handles.push(variables);
+        self.handles.len()
+    }
+}
+
+/// 📂 Reads, parses and links the script named in the `launch` arguments
+fn launch(args: &Json) -> Result<Program, String> {
+    let path = PathBuf::from(
+        args["program"]
+            .as_str()
+            .ok_or("Missing 'program' in launch arguments")?,
+    );
+    let source = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    let ast = parse_program(&source).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let search_path = manifest::search_path_for(Some(&path))?;
+    let ast = link_program(ast, Some(&path), &search_path).map_err(|err| err.to_string())?;
+    let args = args["args"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.as_str().map(str::to_string))
+        .collect();
+    Ok(Program {
+        lines: executable_lines(&ast),
+        path,
+        ast,
+        args,
+    })
+}
+
+/// 🔤 Variables ordered by name, so the editor shows them in a stable order
+fn sorted<'a>(variables: impl Iterator<Item = (&'a String, &'a Value)>) -> Vec<(String, Value)> {
+    let mut variables: Vec<(String, Value)> = variables
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    variables.sort_by(|a, b| a.0.cmp(&b.0));
+    variables
+}
+
+/// 🌳 The elements of an array, tuple or dictionary, shown as expandable children
+fn children(value: &Value) -> Option<Vec<(String, Value)>> {
+    match value {
+        Value::Array(items) | Value::Tuple(items) => Some(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (format!("[{}]", i), item.clone()))
+                .collect(),
+        ),
+        Value::Dictionary(pairs) => Some(
+            pairs
+                .iter()
+                .map(|(key, value)| (key.repr(), value.clone()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+// 🪝 Pauses the interpreter where the stepper says and serves requests until it resumes
+struct Debugger(Rc<RefCell<Session>>);
+
+impl Hook for Debugger {
+    fn statement(
+        &mut self,
+        interpreter: &mut Interpreter,
+        span: Span,
+        file: Option<Rc<str>>,
+    ) -> Result<(), RuntimeError> {
+        let mut session = self.0.borrow_mut();
+        let depth = interpreter.frames().len();
+        let Some(reason) = session
+            .stepper
+            .should_stop(file.as_deref(), span.line, depth)
+        else {
+            return Ok(());
+        };
+
+        let reason = match reason {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+        };
+        session.handles.clear();
+        let result = session.client.borrow_mut().event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        );
+
+        let result = result.and_then(|()| loop {
+            let request = session.client.borrow_mut().read()?;
+            let Some(request) = request else {
+                break Ok(Action::Disconnect);
+            };
+            match session.handle(&request, Some(interpreter))? {
+                Action::Wait | Action::Run => continue,
+                action => break Ok(action),
+            }
+        });
+        match result {
+            Ok(Action::Resume) => Ok(()),
+            // Unwind the program without reporting an error
+            Ok(_) => {
+                session.disconnected = true;
+                Err(RuntimeError::Exit { code: 0 })
+            }
+            Err(err) => {
+                session.failure = Some(err);
+                Err(RuntimeError::Exit { code: 1 })
+            }
+        }
+    }
+}
+
+// 🖨️ Sends what the program writes to the editor as `output` events, a line at a time
+struct Output {
+    client: Rc<RefCell<Client>>,
+    buffer: Vec<u8>,
+}
+
+impl Output {
+    fn send(&mut self, end: usize) -> io::Result<()> {
+        let text: Vec<u8> = self.buffer.drain(..end).collect();
+        let output = String::from_utf8_lossy(&text);
+        self.client
+            .borrow_mut()
+            .event("output", json!({ "category": "stdout", "output": output }))
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if let Some(newline) = self.buffer.iter().rposition(|&b| b == b'\n') {
+            self.send(newline + 1)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.send(self.buffer.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::AST;
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::value::Value;
+
+// 🐞 Breakpoints and stepping shared by `glint dap` and `glint debug`
+
+// 👣 How execution continues after a pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepMode {
+    // Run until a breakpoint
+    Continue,
+    // Stop at the very next statement, entering calls
+    StepIn,
+    // Stop at the next statement at this call depth or shallower
+    StepOver(usize),
+    // Stop at the next statement after the current function returns
+    StepOut(usize),
+}
+
+// ⏸️ Why execution paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+}
+
+// 📄 The file a line is in: `None` for the program itself, or the name of a module file it
+// imports, as the linker located its statements
+pub type File = Option<String>;
+
+// 🧭 Decides where a paused program stops next
+pub struct Stepper {
+    pub breakpoints: BTreeSet<(File, usize)>,
+    mode: StepMode,
+    stop_on_entry: bool,
+}
+
+impl Stepper {
+    /// 🆕 Starts in `Continue` mode, optionally pausing before the first statement
+    pub fn new(stop_on_entry: bool) -> Self {
+        Stepper {
+            breakpoints: BTreeSet::new(),
+            mode: StepMode::Continue,
+            stop_on_entry,
+        }
+    }
+
+    /// ▶️ Resumes execution in the given mode
+    pub fn resume(&mut self, mode: StepMode) {
+        self.mode = mode;
+    }
+
+    /// ⏸️ Called before a statement on `line` of `file` at call depth `depth`; says whether to
+    /// pause there
+    pub fn should_stop(
+        &mut self,
+        file: Option<&str>,
+        line: usize,
+        depth: usize,
+    ) -> Option<StopReason> {
+        if std::mem::take(&mut self.stop_on_entry) {
+            return Some(StopReason::Entry);
+        }
+        let stepped = match self.mode {
+            StepMode::Continue => false,
+            StepMode::StepIn => true,
+            StepMode::StepOver(from) => depth <= from,
+            StepMode::StepOut(from) => depth < from,
+        };
+        if stepped {
+            Some(StopReason::Step)
+        } else if self
+            .breakpoints
+            .iter()
+            .any(|(at, at_line)| *at_line == line && at.as_deref() == file)
+        {
+            Some(StopReason::Breakpoint)
+        } else {
+            None
+        }
+    }
+}
+
+// 🖼️ One entry of a backtrace
+pub struct StackEntry {
+    pub name: String,
+    pub args: Vec<(String, Value)>,
+    pub line: usize,
+    pub file: File,
+}
+
+impl StackEntry {
+    /// 🏷️ `name(a = 1, b = 2)`, or just the name for the program itself
+    pub fn label(&self) -> String {
+        if self.name == PROGRAM_FRAME {
+            return self.name.clone();
+        }
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value.repr()))
+            .collect();
+        format!("{}({})", self.name, args.join(", "))
+    }
+}
+
+/// 🏷️ Name shown for the top-level program in backtraces
+pub const PROGRAM_FRAME: &str = "<program>";
+
+/// 📚 The call stack of a paused program, innermost call first
+pub fn call_stack(interpreter: &Interpreter) -> Vec<StackEntry> {
+    let mut stack: Vec<StackEntry> = interpreter
+        .frames()
+        .iter()
+        .rev()
+        .map(|frame| StackEntry {
+            name: frame.function.name.clone(),
+            args: frame
+                .function
+                .args
+                .iter()
+                .map(|arg| {
+                    let value = frame
+                        .variables
+                        .borrow()
+                        .get(arg)
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    (arg.clone(), value)
+                })
+                .collect(),
+            line: frame.line,
+            file: frame.file.as_deref().map(str::to_string),
+        })
+        .collect();
+    stack.push(StackEntry {
+        name: PROGRAM_FRAME.to_string(),
+        args: Vec::new(),
+        line: interpreter.line(),
+        file: interpreter.file().map(str::to_string),
+    });
+    stack
+}
+
+/// 📍 `line 3`, or `line 3 of utils.glt` for a line of a module
+pub fn describe_line(file: Option<&str>, line: usize) -> String {
+    match file {
+        Some(file) => format!("line {} of {}", line, file),
+        None => format!("line {}", line),
+    }
+}
+
+/// 📍 Lines that hold a statement a breakpoint can stop at (not definitions or comments), for
+/// the program and each module file it imports
+pub fn executable_lines(ast: &AST) -> BTreeMap<File, BTreeSet<usize>> {
+    let mut lines = BTreeMap::new();
+    collect_lines(ast, &mut lines);
+    lines
+}
+
+fn collect_lines(node: &AST, lines: &mut BTreeMap<File, BTreeSet<usize>>) {
+    match node {
+        AST::Located { span, file, node } => {
+            // Test blocks only run under `glint test`
+            if matches!(node.as_ref(), AST::Test { .. }) {
+                return;
+            }
+            if !matches!(
+                node.as_ref(),
+                AST::Function { .. }
+                    | AST::Struct { .. }
+                    | AST::Impl { .. }
+                    | AST::Comment(_)
+                    | AST::Module { .. }
+            ) {
+                lines.entry(file.clone()).or_default().insert(span.line);
+            }
+            collect_lines(node, lines);
+        }
+        AST::Module { body, .. } => collect_lines(body, lines),
+        AST::Impl { methods, .. } => {
+            for method in methods {
+                collect_lines(method, lines);
+            }
+        }
+        AST::Program(statements) | AST::Block(statements) => {
+            for statement in statements {
+                collect_lines(statement, lines);
+            }
+        }
+        AST::Function { body, .. } => collect_lines(body, lines),
+        AST::IfElse {
+            if_block,
+            else_block,
+            ..
+        } => {
+            collect_lines(if_block, lines);
+            if let Some(else_block) = else_block {
+                collect_lines(else_block, lines);
+            }
+        }
+        AST::Coincide { cases, default, .. } => {
+            for (_, action) in cases {
+                collect_lines(action, lines);
+            }
+            if let Some(action) = default {
+                collect_lines(action, lines);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 🎯 Moves a requested breakpoint to the first executable line at or after it
+pub fn resolve_breakpoint(lines: &BTreeSet<usize>, line: usize) -> Option<usize> {
+    lines.range(line..).next().copied()
+}
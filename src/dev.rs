@@ -0,0 +1,145 @@
+use colored::Colorize;
+use serde_json::json;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+use glint::ast::AST;
+
+use crate::PEAK_ALLOC;
+
+// 📊 The `--dev` report: time and memory per phase, printed once the command has finished
+
+const MB: f64 = 1024.0 * 1024.0;
+
+// ⏱️ One measured step of a command (read, parse, link, serialize, interpret)
+struct Phase {
+    name: &'static str,
+    time: Duration,
+    // Highest and final heap usage while the phase ran, in bytes
+    peak: usize,
+    current: usize,
+}
+
+#[derive(Default)]
+struct Report {
+    phases: Vec<Phase>,
+    ast_nodes: Option<usize>,
+    // Highest heap usage over the whole run (phases reset the allocator's peak)
+    peak: usize,
+}
+
+thread_local! {
+    // `None` unless --dev was given, so `phase` costs nothing otherwise
+    static REPORT: RefCell<Option<Report>> = const { RefCell::new(None) };
+}
+
+/// ▶️ Starts recording phases
+pub fn enable() {
+    REPORT.with(|report| *report.borrow_mut() = Some(Report::default()));
+}
+
+/// ⏱️ Runs one phase of the command, recording its time and memory when --dev is on
+pub fn phase<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let enabled = REPORT.with(|report| report.borrow().is_some());
+    if !enabled {
+        return f();
+    }
+
+    let peak_before = PEAK_ALLOC.peak_usage();
+    PEAK_ALLOC.reset_peak_usage();
+    let start = Instant::now();
+    let result = f();
+    let time = start.elapsed();
+
+    REPORT.with(|report| {
+        if let Some(report) = report.borrow_mut().as_mut() {
+            let peak = PEAK_ALLOC.peak_usage();
+            report.peak = report.peak.max(peak_before).max(peak);
+            report.phases.push(Phase {
+                name,
+                time,
+                peak,
+                current: PEAK_ALLOC.current_usage(),
+            });
+        }
+    });
+    result
+}
+
+/// 🌳 Records how big the parsed program is
+pub fn record_ast(ast: &AST) {
+    REPORT.with(|report| {
+        if let Some(report) = report.borrow_mut().as_mut() {
+            report.ast_nodes = Some(ast.node_count());
+        }
+    });
+}
+
+/// 🖨️ Prints the report on stderr (so it never mixes with the script's output)
+pub fn print(start_time: Instant, as_json: bool) {
+    let elapsed = start_time.elapsed();
+    let Some(report) = REPORT.with(|report| report.borrow_mut().take()) else {
+        return;
+    };
+    let peak = report.peak.max(PEAK_ALLOC.peak_usage());
+    let current = PEAK_ALLOC.current_usage();
+    let os = format!(
+        "{:?} {}",
+        os_info::get().os_type(),
+        System::os_version().unwrap_or_default()
+    );
+
+    if as_json {
+        let phases: Vec<_> = report
+            .phases
+            .iter()
+            .map(|phase| {
+                json!({
+                    "name": phase.name,
+                    "secs": phase.time.as_secs_f64(),
+                    "peak_bytes": phase.peak,
+                    "current_bytes": phase.current,
+                })
+            })
+            .collect();
+        let report = json!({
+            "elapsed_secs": elapsed.as_secs_f64(),
+            "phases": phases,
+            "ast_nodes": report.ast_nodes,
+            "peak_bytes": peak,
+            "current_bytes": current,
+            "os": os,
+        });
+        eprintln!("{}", report);
+        return;
+    }
+
+    let label = |text: &str| text.truecolor(41, 176, 255);
+    eprintln!("{} Dev Info {}", "<=> ".blue(), " <=>".blue());
+    eprintln!("{}: {:.4}s", label("Elapsed time"), elapsed.as_secs_f64());
+    if !report.phases.is_empty() {
+        eprintln!("{}:", "Phases".truecolor(0, 76, 120));
+        for phase in &report.phases {
+            eprintln!(
+                "  └─ {}: {:.4}s (peak {:.4} MB, current {:.4} MB)",
+                label(phase.name),
+                phase.time.as_secs_f64(),
+                phase.peak as f64 / MB,
+                phase.current as f64 / MB
+            );
+        }
+    }
+    if let Some(nodes) = report.ast_nodes {
+        eprintln!("{}: {}", label("AST nodes"), nodes);
+    }
+    eprintln!("{}:", "Resource consumption".truecolor(0, 76, 120));
+    eprintln!("  └─ {}: {:.4} MB", label("RAM Usage"), current as f64 / MB);
+    eprintln!(
+        "  └─ {}: {:.4} MB",
+        label("Peak RAM Usage"),
+        peak as f64 / MB
+    );
+    eprintln!("  └─ {}: {}", label("OS"), os);
+    eprintln!("{} End Dev Info {}", "<=> ".blue(), " <=>".blue());
+}
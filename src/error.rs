@@ -2,13 +2,14 @@
 use std::fmt;
 // Importing the IO module from the standard library.
 use std::io;
+use std::path::PathBuf;
 use nom::error::Error;
 
 // Defining the ParseError enum with various variants.
 #[derive(Debug)]
 pub enum ParseError {
-    // An unknown token with the token string and line number.
-    UnknownToken { token: String, line: usize },
+    // An unknown token with the token string, line and column.
+    UnknownToken { token: String, line: usize, column: usize },
     // An IO error.
     IoError(io::Error),
     // A syntax error with a message and line number.
@@ -28,7 +29,7 @@ impl From<io::Error> for ParseError {
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ParseError::UnknownToken { token, line } => write!(f, "Unknown token '{}' on line {}", token, line),
+            ParseError::UnknownToken { token, line, column } => write!(f, "Unknown token '{}' on line {}, column {}", token, line, column),
             ParseError::IoError(err) => write!(f, "IO Error: {}", err),
             ParseError::SyntaxError { message, line } => write!(f, "Syntax error on line {}: {}", line, message),
             ParseError::NomError(err) => write!(f, "Nom Error: {:?}", err),
@@ -36,5 +37,148 @@ impl fmt::Display for ParseError {
     }
 }
 
+impl ParseError {
+    /// 📍 The line and column the error points at (the start of the input when it has none)
+    pub fn location(&self) -> (usize, usize) {
+        match self {
+            ParseError::UnknownToken { line, column, .. } => (*line, *column),
+            ParseError::SyntaxError { line, .. } => (*line, 1),
+            ParseError::IoError(_) | ParseError::NomError(_) => (1, 1),
+        }
+    }
+}
+
 // Implementing the Error trait for ParseError.
 impl std::error::Error for ParseError {}
+
+// Defining the RuntimeError enum for errors raised while interpreting a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    // A variable was read before it was assigned.
+    UndefinedVariable { name: String },
+    // A call to a function that was never defined.
+    UndefinedFunction { name: String },
+    // A function was called with the wrong number of arguments.
+    ArityMismatch { name: String, expected: usize, found: usize },
+    // An operation was applied to values of the wrong type.
+    TypeError { message: String },
+    // An operator the interpreter does not know.
+    UnknownOperator { op: String },
+    // Division (integer or float) by zero.
+    DivisionByZero,
+    // Integer arithmetic overflowed.
+    Overflow { op: String },
+    // An index past either end of an array, string or tuple of length `len`.
+    IndexOutOfRange { index: i64, len: usize },
+    // A method that takes an item out of an array (`pop`) was called on an empty one.
+    EmptyArray { method: String },
+    // A dictionary has no such key; `key` is printed the way `write` shows it inside a collection.
+    KeyNotFound { key: String },
+    // A struct was constructed that no `struct` declaration defines.
+    UndefinedStruct { name: String },
+    // A struct has no field with this name.
+    UnknownField { name: String, field: String },
+    // A value has no method with this name (`type_name` is a type name or the name of a struct).
+    UndefinedMethod { type_name: String, method: String },
+    // An `assert` whose condition was false, with the position of the statement (and the
+    // module file it is in, if it is not in the program itself).
+    AssertionFailed { message: String, file: Option<String>, line: usize, column: usize },
+    // The program called `exit(code)`; not a failure, it unwinds to the caller.
+    Exit { code: u8 },
+    // A function passed to a builtin (`map(arr, f)`) failed; `line` is where the builtin was called.
+    CallbackFailed { builtin: String, line: usize, error: Box<RuntimeError> },
+}
+
+// Implementing the Display trait for formatting RuntimeError.
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::UndefinedVariable { name } => write!(f, "Undefined variable '{}'", name),
+            RuntimeError::UndefinedFunction { name } => write!(f, "Function '{}' not found", name),
+            RuntimeError::ArityMismatch { name, expected, found } => write!(
+                f,
+                "Function '{}' expects {} arguments but {} were provided",
+                name, expected, found
+            ),
+            RuntimeError::TypeError { message } => write!(f, "Type error: {}", message),
+            RuntimeError::UnknownOperator { op } => write!(f, "Unknown binary operator: {}", op),
+            RuntimeError::DivisionByZero => write!(f, "Division by zero"),
+            RuntimeError::Overflow { op } => write!(f, "Integer overflow in '{}'", op),
+            RuntimeError::IndexOutOfRange { index, len } => {
+                write!(f, "Index {} out of range for length {}", index, len)
+            }
+            RuntimeError::EmptyArray { method } => {
+                write!(f, "Cannot {}() from an empty array", method)
+            }
+            RuntimeError::KeyNotFound { key } => write!(f, "Key {} not found", key),
+            RuntimeError::UndefinedStruct { name } => write!(f, "Struct '{}' not found", name),
+            RuntimeError::UnknownField { name, field } => {
+                write!(f, "{} has no field '{}'", name, field)
+            }
+            RuntimeError::UndefinedMethod { type_name, method } => {
+                write!(f, "{} has no method '{}'", type_name, method)
+            }
+            RuntimeError::AssertionFailed { message, file: None, line, column } => write!(
+                f,
+                "Assertion failed on line {}, column {}: {}",
+                line, column, message
+            ),
+            RuntimeError::AssertionFailed { message, file: Some(file), line, column } => write!(
+                f,
+                "Assertion failed in {} on line {}, column {}: {}",
+                file, line, column, message
+            ),
+            RuntimeError::Exit { code } => write!(f, "Exited with code {}", code),
+            RuntimeError::CallbackFailed { builtin, line, error } => write!(
+                f,
+                "{} (in the function passed to {}() on line {})",
+                error, builtin, line
+            ),
+        }
+    }
+}
+
+// Implementing the Error trait for RuntimeError.
+impl std::error::Error for RuntimeError {}
+
+// Defining the ImportError enum for errors raised while resolving the imports of a program.
+#[derive(Debug)]
+pub enum ImportError {
+    // No file for the module next to the importing file or on the search path.
+    NotFound { module: String, importer: String, searched: Vec<PathBuf> },
+    // A module imports itself, directly or through others; the chain runs from the program to the repeated module.
+    Cycle { chain: Vec<String> },
+    // An `import` inside a function, test or other block.
+    NotTopLevel { file: String, line: usize },
+    // A module could not be read.
+    IoError { file: String, error: io::Error },
+    // A module could not be parsed.
+    ParseError { file: String, error: ParseError },
+}
+
+// Implementing the Display trait for formatting ImportError.
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::NotFound { module, importer, searched } => {
+                let searched: Vec<String> = searched.iter().map(|dir| dir.display().to_string()).collect();
+                write!(
+                    f,
+                    "{}: module '{}' not found (searched {})",
+                    importer,
+                    module,
+                    searched.join(", ")
+                )
+            }
+            ImportError::Cycle { chain } => write!(f, "Import cycle: {}", chain.join(" -> ")),
+            ImportError::NotTopLevel { file, line } => {
+                write!(f, "{}: `import` on line {} is not at the top level", file, line)
+            }
+            ImportError::IoError { file, error } => write!(f, "Error reading {}: {}", file, error),
+            ImportError::ParseError { file, error } => write!(f, "{}: {}", file, error),
+        }
+    }
+}
+
+// Implementing the Error trait for ImportError.
+impl std::error::Error for ImportError {}
@@ -0,0 +1,354 @@
+use crate::ast::{ArmComment, AST};
+use crate::error::ParseError;
+use crate::parser::parser::parse_program;
+
+// 🎨 Canonical source formatter used by `glint fmt`
+
+const INDENT: &str = "    ";
+
+/// 🎨 Formats a script; the result parses to the same AST as `source` (apart from positions)
+pub fn format_source(source: &str) -> Result<String, ParseError> {
+    let ast = parse_program(source)?;
+    let mut output = String::new();
+    // The parser skips the shebang line, so copy it over untouched
+    if let Some(shebang) = source.lines().next().filter(|line| line.starts_with("#!")) {
+        output.push_str(shebang.trim_end());
+        output.push('\n');
+    }
+    output.push_str(&format_program(&ast));
+    Ok(output)
+}
+
+/// 🖨️ Prints a parsed program back as source code
+pub fn format_program(ast: &AST) -> String {
+    let mut formatter = Formatter {
+        output: String::new(),
+    };
+    match ast {
+        AST::Program(statements) => formatter.statements(statements, 0),
+        other => formatter.statements(std::slice::from_ref(other), 0),
+    }
+    formatter.output
+}
+
+struct Formatter {
+    output: String,
+}
+
+impl Formatter {
+    /// 🧱 Prints statements one per line, keeping single blank lines and trailing comments
+    fn statements(&mut self, statements: &[AST], depth: usize) {
+        let mut previous_end_line = None;
+        for statement in statements {
+            let (span, node) = match statement {
+                AST::Located { span, node, .. } if span.line > 0 => (Some(span), node.as_ref()),
+                other => (None, other),
+            };
+
+            if let (Some(span), Some(end_line)) = (span, previous_end_line) {
+                // A comment on the same line as the previous statement stays there
+                if let AST::Comment(text) = node {
+                    if span.line == end_line {
+                        self.output.pop();
+                        self.output.push_str(&format!(" #{}\n", text));
+                        continue;
+                    }
+                }
+                if span.line > end_line + 1 {
+                    self.output.push('\n');
+                }
+            }
+
+            self.output.push_str(&INDENT.repeat(depth));
+            self.statement(node, depth);
+            self.output.push('\n');
+            previous_end_line = span.map(|span| span.end_line);
+        }
+    }
+
+    /// ▶️ Prints a single statement (without indentation or newline)
+    fn statement(&mut self, node: &AST, depth: usize) {
+        match node {
+            AST::Located { node, .. } => self.statement(node, depth),
+            AST::Comment(text) => self.output.push_str(&format!("#{}", text)),
+            AST::Write(items) if items.is_empty() => self.output.push_str("write"),
+            AST::Write(items) => self
+                .output
+                .push_str(&format!("write {}", list_at(items, depth))),
+            AST::VariableAssign { name, value } => {
+                self.output
+                    .push_str(&format!("{} is {}", name, expression_at(value, depth)));
+            }
+            AST::Destructure { names, value } => self.output.push_str(&format!(
+                "{} is {}",
+                names.join(", "),
+                expression_at(value, depth)
+            )),
+            AST::Assign { target, value } => self.output.push_str(&format!(
+                "{} is {}",
+                expression_at(target, depth),
+                expression_at(value, depth)
+            )),
+            AST::Struct { name, fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, ty)| match ty {
+                        Some(ty) => format!("{}: {}", field, ty),
+                        None => field.clone(),
+                    })
+                    .collect();
+                self.output
+                    .push_str(&format!("struct {} {{ {} }}", name, fields.join(", ")));
+            }
+            AST::Impl { name, methods } => {
+                self.output.push_str(&format!("impl {} ", name));
+                self.block(&AST::Block(methods.clone()), depth);
+            }
+            AST::Return(value) => self
+                .output
+                .push_str(&format!("return {}", expression_at(value, depth))),
+            AST::Function { name, args, body } => {
+                let args = match args.as_ref() {
+                    AST::FunctionArgs(args) => list_at(args, depth),
+                    other => expression_at(other, depth),
+                };
+                self.output.push_str(&format!("{}({}) ", name, args));
+                self.block(body, depth);
+            }
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => {
+                self.output
+                    .push_str(&format!("if {} ", expression_at(condition, depth)));
+                self.block(if_block, depth);
+                if let Some(else_block) = else_block {
+                    self.output.push_str(" else ");
+                    self.block(else_block, depth);
+                }
+            }
+            AST::Import(module) => self
+                .output
+                .push_str(&format!("import {}", expression_at(module, depth))),
+            AST::Test { name, body } => {
+                self.output.push_str(&format!("test \"{}\" ", name));
+                self.block(body, depth);
+            }
+            AST::Assert { condition, message } => {
+                self.output
+                    .push_str(&format!("assert {}", expression_at(condition, depth)));
+                if let Some(message) = message {
+                    self.output
+                        .push_str(&format!(", {}", expression_at(message, depth)));
+                }
+            }
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                comments,
+            } => {
+                self.output
+                    .push_str(&format!("coincide {}:", expression_at(expr, depth)));
+                let indent = INDENT.repeat(depth + 1);
+                let comments_before = |arm: usize| comments.iter().filter(move |c| c.arm == arm);
+                for (arm, (value, action)) in cases.iter().enumerate() {
+                    self.arm_comments(comments_before(arm), &indent);
+                    self.output.push_str(&format!(
+                        "\n{}{} then ",
+                        indent,
+                        expression_at(value, depth)
+                    ));
+                    self.arm(action, depth + 1);
+                }
+                if let Some(action) = default {
+                    self.arm_comments(comments_before(cases.len()), &indent);
+                    self.output.push_str(&format!("\n{}default ", indent));
+                    self.arm(action, depth + 1);
+                }
+            }
+            other => self.output.push_str(&expression_at(other, depth)),
+        }
+    }
+
+    /// 💬 Prints the comments before a `coincide` arm: trailing ones at the end of the current
+    /// line, the others on lines of their own
+    fn arm_comments<'a>(&mut self, comments: impl Iterator<Item = &'a ArmComment>, indent: &str) {
+        for comment in comments {
+            if comment.trailing {
+                self.output.push_str(&format!(" #{}", comment.text));
+            } else {
+                self.output
+                    .push_str(&format!("\n{}#{}", indent, comment.text));
+            }
+        }
+    }
+
+    /// 🎯 Prints the body of a `coincide` arm: a block or a single statement
+    fn arm(&mut self, action: &AST, depth: usize) {
+        match action {
+            AST::Block(_) => self.block(action, depth),
+            action => self.statement(action, depth),
+        }
+    }
+
+    /// 📦 Prints `{ ... }` with the statements indented one level deeper
+    fn block(&mut self, block: &AST, depth: usize) {
+        let statements = match block {
+            AST::Block(statements) => statements.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        if statements.is_empty() {
+            self.output.push_str("{}");
+            return;
+        }
+        self.output.push_str("{\n");
+        self.statements(statements, depth + 1);
+        self.output.push_str(&INDENT.repeat(depth));
+        self.output.push('}');
+    }
+}
+
+/// 🧮 Prints an expression with single spaces around operators and only the parentheses it needs
+pub fn expression(node: &AST) -> String {
+    expression_at(node, 0)
+}
+
+/// 🧮 Prints an expression that is part of a statement `depth` levels deep (where the lines
+/// of a `fn(x) { ... }` body are indented from)
+fn expression_at(node: &AST, depth: usize) -> String {
+    let list = |items: &[AST]| list_at(items, depth);
+    match node {
+        AST::Located { node, .. } => expression_at(node, depth),
+        AST::Integer(n) => n.to_string(),
+        AST::Float(f) => float(*f),
+        AST::Bool(b) => b.to_string(),
+        AST::String(s) => format!("\"{}\"", s),
+        AST::Identifier(name) => name.clone(),
+        AST::FunctionCall { name, args } => format!("{}({})", name, list(args)),
+        AST::Call { callee, args } => format!("{}({})", operand(callee, 3, depth), list(args)),
+        AST::Construct { name, fields } => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(field, value)| format!("{}: {}", field, expression_at(value, depth)))
+                .collect();
+            format!("{}({})", name, fields.join(", "))
+        }
+        AST::Lambda { args, body } => {
+            let params = args.param_names();
+            match body.as_ref() {
+                AST::Block(_) => {
+                    let mut formatter = Formatter {
+                        output: String::new(),
+                    };
+                    formatter.block(body, depth);
+                    format!("fn({}) {}", params.join(", "), formatter.output)
+                }
+                body => match params.as_slice() {
+                    [param] => format!("{} => {}", param, expression_at(body, depth)),
+                    _ => format!("({}) => {}", params.join(", "), expression_at(body, depth)),
+                },
+            }
+        }
+        AST::Index { object, index } => format!(
+            "{}[{}]",
+            operand(object, 3, depth),
+            expression_at(index, depth)
+        ),
+        AST::Slice { object, start, end } => {
+            let bound = |bound: &Option<Box<AST>>| {
+                bound
+                    .as_ref()
+                    .map_or(String::new(), |bound| expression_at(bound, depth))
+            };
+            format!(
+                "{}[{}:{}]",
+                operand(object, 3, depth),
+                bound(start),
+                bound(end)
+            )
+        }
+        AST::Field { object, name } => match object.as_ref() {
+            // `point.x` would read back as a single dotted name
+            AST::Identifier(_) => format!("({}).{}", expression_at(object, depth), name),
+            object => format!("{}.{}", operand(object, 3, depth), name),
+        },
+        AST::Array(items) => format!("[{}]", list(items)),
+        AST::Alternatives(patterns) => list(patterns),
+        AST::Range { start, end } => {
+            format!(
+                "{}..{}",
+                expression_at(start, depth),
+                expression_at(end, depth)
+            )
+        }
+        AST::TypePattern(name) => name.clone(),
+        // A tuple with one item needs a trailing comma to not read back as parentheses
+        AST::Tuple(items) if items.len() == 1 => format!("({},)", list(items)),
+        AST::Tuple(items) => format!("({})", list(items)),
+        AST::Dictionary(pairs) => {
+            let pairs: Vec<String> = pairs
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}: {}",
+                        expression_at(key, depth),
+                        expression_at(value, depth)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", pairs.join(", "))
+        }
+        AST::BinaryOp { left, op, right } => {
+            let precedence = precedence(op);
+            // Operators are left-associative, so only a right operand of equal precedence needs parentheses
+            let left = operand(left, precedence - 1, depth);
+            let right = operand(right, precedence, depth);
+            format!("{} {} {}", left, op, right)
+        }
+        other => format!("{:?}", other),
+    }
+}
+
+/// 🔗 Prints expressions separated by `, `
+fn list_at(items: &[AST], depth: usize) -> String {
+    items
+        .iter()
+        .map(|item| expression_at(item, depth))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 🧷 Prints an operand, parenthesized if it binds no tighter than `min` (an arrow lambda
+/// would take everything after it as its body)
+fn operand(node: &AST, min: u8, depth: usize) -> String {
+    match node {
+        AST::BinaryOp { op, .. } if precedence(op) <= min => {
+            format!("({})", expression_at(node, depth))
+        }
+        AST::Lambda { body, .. } if !matches!(body.as_ref(), AST::Block(_)) => {
+            format!("({})", expression_at(node, depth))
+        }
+        _ => expression_at(node, depth),
+    }
+}
+
+/// 📐 How tightly an operator binds (comparisons bind loosest)
+fn precedence(op: &str) -> u8 {
+    match op {
+        "*" | "/" => 3,
+        "+" | "-" => 2,
+        _ => 1,
+    }
+}
+
+/// 🔢 Prints a float so that it reads back as a float (`3.0`, not `3`)
+fn float(f: f64) -> String {
+    let text = f.to_string();
+    if text.contains('.') || !f.is_finite() {
+        text
+    } else {
+        format!("{}.0", text)
+    }
+}
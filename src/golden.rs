@@ -0,0 +1,246 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+// 🥇 Golden-file tests behind `glint test`: run a script and compare what it prints with what it should
+
+// Comment annotations holding expected output lines
+const EXPECT: &str = "# expect:";
+const EXPECT_ERROR: &str = "# expect-error:";
+
+// 📋 Where a test keeps its expected output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expectations {
+    // `# expect:` / `# expect-error:` comments in the script itself
+    Annotations,
+    // Sibling `name.out` (stdout) and `name.err` (stderr) files, missing meaning empty
+    Files,
+}
+
+// 🧾 The result of one golden test
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub expectations: Expectations,
+    pub passed: bool,
+    // Rendered differences, one per stream that did not match
+    pub diffs: Vec<String>,
+}
+
+/// 🥇 Runs `script` with `glint run` (using the `glint` executable) and compares its
+/// stdout and stderr with the expectations; with `bless`, rewrites the expectations instead
+pub fn run_golden(glint: &Path, script: &Path, bless: bool) -> io::Result<Outcome> {
+    let source = fs::read_to_string(script)?;
+    let expectations = if has_annotations(&source) {
+        Expectations::Annotations
+    } else {
+        Expectations::Files
+    };
+
+    if bless {
+        match expectations {
+            Expectations::Annotations => {
+                // Annotations are comments, so the script runs as it is, and rewriting them in
+                // place keeps the line numbers its messages mention
+                let (stdout, stderr) = run_script(glint, script)?;
+                fs::write(script, reannotate(&source, &stdout, &stderr))?;
+            }
+            Expectations::Files => {
+                let (stdout, stderr) = run_script(glint, script)?;
+                write_expectation(&script.with_extension("out"), &stdout)?;
+                write_expectation(&script.with_extension("err"), &stderr)?;
+            }
+        }
+        return Ok(Outcome {
+            expectations,
+            passed: true,
+            diffs: Vec::new(),
+        });
+    }
+
+    let (expected_stdout, expected_stderr) = match expectations {
+        Expectations::Annotations => annotated_output(&source),
+        Expectations::Files => (
+            read_expectation(&script.with_extension("out"))?,
+            read_expectation(&script.with_extension("err"))?,
+        ),
+    };
+    let (mut stdout, mut stderr) = run_script(glint, script)?;
+    if expectations == Expectations::Annotations {
+        // Annotations are whole lines, so a missing final newline cannot be expressed
+        for output in [&mut stdout, &mut stderr] {
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+        }
+    }
+
+    let mut diffs = Vec::new();
+    for (stream, expected, actual) in [
+        ("stdout", &expected_stdout, &stdout),
+        ("stderr", &expected_stderr, &stderr),
+    ] {
+        if expected != actual {
+            diffs.push(format!(
+                "--- expected {}\n+++ actual {}\n{}",
+                stream,
+                stream,
+                diff_lines(expected, actual)
+            ));
+        }
+    }
+    Ok(Outcome {
+        expectations,
+        passed: diffs.is_empty(),
+        diffs,
+    })
+}
+
+/// ▶️ Runs a script from its own directory, so messages name it the same way wherever the tests run from
+fn run_script(glint: &Path, script: &Path) -> io::Result<(String, String)> {
+    let directory = script
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = script.file_name().unwrap_or(script.as_os_str());
+    let output = Command::new(glint)
+        .arg("run")
+        .arg(name)
+        .current_dir(directory)
+        .output()?;
+    Ok((
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    ))
+}
+
+fn has_annotations(source: &str) -> bool {
+    source.lines().any(|line| annotation(line).is_some())
+}
+
+/// 🏷️ The stream and text of the annotation on a line, if it has one
+fn annotation(line: &str) -> Option<(&'static str, &str)> {
+    for (marker, stream) in [(EXPECT_ERROR, "stderr"), (EXPECT, "stdout")] {
+        if let Some(index) = line.find(marker) {
+            let text = &line[index + marker.len()..];
+            return Some((stream, text.strip_prefix(' ').unwrap_or(text)));
+        }
+    }
+    None
+}
+
+/// 📋 The stdout and stderr the annotations of a script expect
+fn annotated_output(source: &str) -> (String, String) {
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    for (stream, text) in source.lines().filter_map(annotation) {
+        let output = if stream == "stdout" {
+            &mut stdout
+        } else {
+            &mut stderr
+        };
+        output.push_str(text);
+        output.push('\n');
+    }
+    (stdout, stderr)
+}
+
+/// ✍️ Rewrites the annotations of a script in place for `stdout` and `stderr`: the n-th
+/// annotation of a stream gets its n-th output line, annotations left over are removed, and
+/// output lines left over are appended to the end of the script
+fn reannotate(source: &str, stdout: &str, stderr: &str) -> String {
+    let mut stdout = stdout.lines();
+    let mut stderr = stderr.lines();
+    let mut annotated = String::new();
+    for line in source.lines() {
+        let Some((stream, _)) = annotation(line) else {
+            annotated.push_str(line);
+            annotated.push('\n');
+            continue;
+        };
+        let (marker, output) = if stream == "stdout" {
+            (EXPECT, &mut stdout)
+        } else {
+            (EXPECT_ERROR, &mut stderr)
+        };
+        let code = &line[..line.find(marker).unwrap_or(line.len())];
+        match output.next() {
+            Some(text) => annotated.push_str(format!("{}{} {}", code, marker, text).trim_end()),
+            // A whole-line annotation goes away with its line
+            None if code.trim().is_empty() => continue,
+            None => annotated.push_str(code.trim_end()),
+        }
+        annotated.push('\n');
+    }
+
+    let extra: Vec<String> = stdout
+        .map(|line| format!("{} {}", EXPECT, line))
+        .chain(stderr.map(|line| format!("{} {}", EXPECT_ERROR, line)))
+        .collect();
+    if !extra.is_empty() {
+        annotated = annotated.trim_end().to_string();
+        annotated.push_str("\n\n");
+        for line in extra {
+            annotated.push_str(line.trim_end());
+            annotated.push('\n');
+        }
+    }
+    annotated
+}
+
+fn read_expectation(path: &Path) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        result => result,
+    }
+}
+
+/// 💾 Writes an expectation file, or removes it when nothing is expected
+fn write_expectation(path: &Path, contents: &str) -> io::Result<()> {
+    if !contents.is_empty() {
+        return fs::write(path, contents);
+    }
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// 🔀 A line diff of two texts: ` ` for common lines, `-` for expected only, `+` for actual only
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    // Texts that differ only in the final newline have identical lines
+    if diff.lines().all(|line| line.starts_with(' ')) {
+        diff.push_str("\\ the final newline differs\n");
+    }
+    diff
+}
@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+use std::env;
+
+use crate::error::RuntimeError;
+use crate::interpreter::function::Closure;
+use crate::interpreter::value::Value;
+
+/// 🧰 Functions every program can call without defining them
+pub const BUILTINS: &[&str] = &[
+    "env",
+    "exit",
+    "map",
+    "filter",
+    "reduce",
+    "any",
+    "all",
+    "find",
+    "sort_by",
+    "zip",
+    "enumerate",
+];
+
+/// 📲 How builtins such as `map` call the function they were given back
+pub type Callback<'a> = dyn FnMut(&Closure, Vec<Value>) -> Result<Value, RuntimeError> + 'a;
+
+/// 📞 Calls a builtin function, running function arguments with `callback`; returns `None`
+/// if `name` is not a builtin
+pub fn call_builtin(
+    name: &str,
+    args: &[Value],
+    callback: &mut Callback,
+) -> Option<Result<Value, RuntimeError>> {
+    let result = match name {
+        "env" => builtin_env(args),
+        "exit" => builtin_exit(args),
+        "map" => builtin_map(args, callback),
+        "filter" => builtin_filter(args, callback),
+        "reduce" => builtin_reduce(args, callback),
+        "any" => builtin_any(args, callback),
+        "all" => builtin_all(args, callback),
+        "find" => builtin_find(args, callback),
+        "sort_by" => builtin_sort_by(args, callback),
+        "zip" => builtin_zip(args),
+        "enumerate" => builtin_enumerate(args),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// 🔢 The smallest and largest number of arguments a builtin accepts, or `None` if `name` is not a builtin
+pub fn builtin_arity(name: &str) -> Option<(usize, usize)> {
+    match name {
+        "env" => Some((1, 2)),
+        "exit" => Some((0, 1)),
+        "map" | "filter" | "any" | "all" | "find" | "sort_by" | "zip" => Some((2, 2)),
+        "reduce" => Some((3, 3)),
+        "enumerate" => Some((1, 1)),
+        _ => None,
+    }
+}
+
+/// 🌱 `env(name, default)`: the value of an environment variable, or `default` (null if
+/// omitted) when it is not set
+fn builtin_env(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [Value::String(name), rest @ ..] if rest.len() <= 1 => Ok(env::var(name)
+            .map(Value::String)
+            .unwrap_or_else(|_| rest.first().cloned().unwrap_or(Value::Null))),
+        [other] | [other, _] => Err(RuntimeError::TypeError {
+            message: format!("env() expects a string, got {}", other.type_name()),
+        }),
+        _ => Err(arity_mismatch("env", 2, args.len())),
+    }
+}
+
+/// 🚪 `exit(code)`: stops the program with the given exit status, 0 to 255 (0 if omitted)
+fn builtin_exit(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [] => Err(RuntimeError::Exit { code: 0 }),
+        [Value::Integer(code)] => match u8::try_from(*code) {
+            Ok(code) => Err(RuntimeError::Exit { code }),
+            Err(_) => Err(RuntimeError::TypeError {
+                message: format!("exit() expects a code from 0 to 255, got {}", code),
+            }),
+        },
+        [other] => Err(RuntimeError::TypeError {
+            message: format!("exit() expects an int, got {}", other.type_name()),
+        }),
+        _ => Err(arity_mismatch("exit", 1, args.len())),
+    }
+}
+
+/// 🗺️ `map(arr, f)`: a new array holding `f(item)` for every item
+fn builtin_map(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, f) = array_and_function("map", args)?;
+    items
+        .iter()
+        .map(|item| callback(f, vec![item.clone()]))
+        .collect::<Result<_, _>>()
+        .map(Value::Array)
+}
+
+/// 🧹 `filter(arr, f)`: the items for which `f(item)` is truthy
+fn builtin_filter(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, f) = array_and_function("filter", args)?;
+    let mut kept = Vec::new();
+    for item in items {
+        if callback(f, vec![item.clone()])?.is_truthy() {
+            kept.push(item.clone());
+        }
+    }
+    Ok(Value::Array(kept))
+}
+
+/// 🧮 `reduce(arr, init, f)`: folds the items into one value, starting from `init`,
+/// with `acc = f(acc, item)`
+fn builtin_reduce(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, init, f) = match args {
+        [Value::Array(items), init, Value::Function(f)] => (items, init, f),
+        [Value::Array(_), _, other] => return Err(not_a_function("reduce", "third", other)),
+        [other, _, _] => return Err(not_an_array("reduce", other)),
+        _ => return Err(arity_mismatch("reduce", 3, args.len())),
+    };
+    items.iter().try_fold(init.clone(), |acc, item| {
+        callback(f, vec![acc, item.clone()])
+    })
+}
+
+/// 🔍 `any(arr, f)`: whether `f(item)` is truthy for some item (stops at the first)
+fn builtin_any(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, f) = array_and_function("any", args)?;
+    for item in items {
+        if callback(f, vec![item.clone()])?.is_truthy() {
+            return Ok(Value::Bool(true));
+        }
+    }
+    Ok(Value::Bool(false))
+}
+
+/// ✅ `all(arr, f)`: whether `f(item)` is truthy for every item (stops at the first that is not)
+fn builtin_all(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, f) = array_and_function("all", args)?;
+    for item in items {
+        if !callback(f, vec![item.clone()])?.is_truthy() {
+            return Ok(Value::Bool(false));
+        }
+    }
+    Ok(Value::Bool(true))
+}
+
+/// 🎯 `find(arr, f)`: the first item for which `f(item)` is truthy, or null
+fn builtin_find(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, f) = array_and_function("find", args)?;
+    for item in items {
+        if callback(f, vec![item.clone()])?.is_truthy() {
+            return Ok(item.clone());
+        }
+    }
+    Ok(Value::Null)
+}
+
+/// 🗂️ `sort_by(arr, key)`: a sorted copy of the array, ordered by `key(item)`; items with
+/// equal keys keep their order
+fn builtin_sort_by(args: &[Value], callback: &mut Callback) -> Result<Value, RuntimeError> {
+    let (items, key) = array_and_function("sort_by", args)?;
+    // Every key is computed once, before sorting
+    let keys = items
+        .iter()
+        .map(|item| callback(key, vec![item.clone()]))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut error = None;
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        keys[a].sort_order(&keys[b]).unwrap_or_else(|| {
+            error.get_or_insert_with(|| RuntimeError::TypeError {
+                message: format!(
+                    "sort_by() cannot order a {} key and a {} key",
+                    keys[a].type_name(),
+                    keys[b].type_name()
+                ),
+            });
+            Ordering::Equal
+        })
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(Value::Array(
+            order.into_iter().map(|i| items[i].clone()).collect(),
+        )),
+    }
+}
+
+/// 🤝 `zip(a, b)`: `(a[i], b[i])` tuples, as many as the shorter array has items
+fn builtin_zip(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [Value::Array(left), Value::Array(right)] => Ok(Value::Array(
+            left.iter()
+                .zip(right)
+                .map(|(l, r)| Value::Tuple(vec![l.clone(), r.clone()]))
+                .collect(),
+        )),
+        [Value::Array(_), other] | [other, _] => Err(not_an_array("zip", other)),
+        _ => Err(arity_mismatch("zip", 2, args.len())),
+    }
+}
+
+/// 🔢 `enumerate(arr)`: `(index, item)` tuples, counting from 0
+fn builtin_enumerate(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [Value::Array(items)] => Ok(Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| Value::Tuple(vec![Value::Integer(i as i64), item.clone()]))
+                .collect(),
+        )),
+        [other] => Err(not_an_array("enumerate", other)),
+        _ => Err(arity_mismatch("enumerate", 1, args.len())),
+    }
+}
+
+/// 📚 The array and the function of a builtin called as `name(arr, f)`
+fn array_and_function<'a>(
+    name: &str,
+    args: &'a [Value],
+) -> Result<(&'a [Value], &'a Closure), RuntimeError> {
+    match args {
+        [Value::Array(items), Value::Function(f)] => Ok((items, f)),
+        [Value::Array(_), other] => Err(not_a_function(name, "second", other)),
+        [other, _] => Err(not_an_array(name, other)),
+        _ => Err(arity_mismatch(name, 2, args.len())),
+    }
+}
+
+fn not_an_array(name: &str, value: &Value) -> RuntimeError {
+    RuntimeError::TypeError {
+        message: format!("{}() expects an array, got {}", name, value.type_name()),
+    }
+}
+
+fn not_a_function(name: &str, position: &str, value: &Value) -> RuntimeError {
+    RuntimeError::TypeError {
+        message: format!(
+            "{}() expects a function as its {} argument, got {}",
+            name,
+            position,
+            value.type_name()
+        ),
+    }
+}
+
+fn arity_mismatch(name: &str, expected: usize, found: usize) -> RuntimeError {
+    RuntimeError::ArityMismatch {
+        name: name.to_string(),
+        expected,
+        found,
+    }
+}
@@ -0,0 +1,84 @@
+use serde_json::{json, Value as Json};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::interpreter::value::Value;
+
+/// 🏷️ The name anonymous functions go by in errors, stack traces and profiles
+pub const ANONYMOUS: &str = "<fn>";
+
+// 🛠️ A user-defined function hoisted out of the program
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub args: Vec<String>,
+    pub body: Json,
+}
+
+impl Function {
+    /// 🏗️ Builds a Function from the JSON form of an `AST::Function` node
+    pub fn from_json(func_obj: &Json) -> Self {
+        Function {
+            name: func_obj["name"].as_str().unwrap().to_string(),
+            args: params(&func_obj["args"]),
+            body: func_obj["body"].clone(),
+        }
+    }
+
+    /// 🏗️ Builds a Function from the JSON form of an `AST::Lambda` node; an expression body
+    /// (`x => x * 2`) becomes a body that returns it
+    pub fn from_lambda(lambda: &Json) -> Self {
+        let body = match lambda["body"].get("Block") {
+            Some(_) => lambda["body"].clone(),
+            None => json!({ "Block": [{ "Return": lambda["body"] }] }),
+        };
+        Function {
+            name: ANONYMOUS.to_string(),
+            args: params(&lambda["args"]),
+            body,
+        }
+    }
+
+    /// 📋 Returns the statements of the function body
+    pub fn statements(&self) -> &[Json] {
+        self.body["Block"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+/// 📋 The parameter names of a `FunctionArgs` node
+fn params(args: &Json) -> Vec<String> {
+    args["FunctionArgs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|arg| arg["Identifier"].as_str().unwrap_or_default().to_string())
+        .collect()
+}
+
+// 🗂️ The local variables of one function call, shared with the lambdas created during it
+pub type Scope = Rc<RefCell<HashMap<String, Value>>>;
+
+// 🎁 A function used as a value: a lambda with the scopes it was created in (innermost
+// first), which it reads and changes while it runs, or a named function (which captures nothing)
+#[derive(Debug)]
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub captured: Vec<Scope>,
+}
+
+// ⚖️ Two function values are equal when they are the same function capturing the same scopes
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.function, &other.function)
+            && self.captured.len() == other.captured.len()
+            && self
+                .captured
+                .iter()
+                .zip(&other.captured)
+                .all(|(a, b)| Rc::ptr_eq(a, b))
+    }
+}
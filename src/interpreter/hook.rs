@@ -0,0 +1,24 @@
+use std::rc::Rc;
+
+use crate::ast::Span;
+use crate::error::RuntimeError;
+use crate::interpreter::function::Function;
+use crate::interpreter::interpreter::Interpreter;
+
+// 🪝 Lets tools such as debuggers watch a program as it runs
+pub trait Hook {
+    /// 📍 Called before every statement that has a source position, with the module file it is
+    /// in (`None` for the program itself); returning an error stops the program with it
+    fn statement(
+        &mut self,
+        interpreter: &mut Interpreter,
+        span: Span,
+        file: Option<Rc<str>>,
+    ) -> Result<(), RuntimeError>;
+
+    /// 📞 Called when a function starts, after its frame has been pushed
+    fn call(&mut self, _interpreter: &Interpreter, _function: &Function) {}
+
+    /// ↩️ Called when a function finishes (also when it fails), before its frame is popped
+    fn returned(&mut self, _interpreter: &Interpreter, _function: &Function) {}
+}
@@ -1,541 +1,1057 @@
-use serde_json::{from_str, Value};
+use serde_json::{from_str, Value as Json};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{self, Write};
-
-struct Interpreter {
-    functions: HashMap<String, Function>,
+use std::rc::Rc;
+
+use crate::ast::AST;
+use crate::error::RuntimeError;
+use crate::formatter::expression;
+use crate::interpreter::builtins::call_builtin;
+use crate::interpreter::function::{Closure, Function, Scope};
+use crate::interpreter::hook::Hook;
+use crate::interpreter::methods::{self, MUTATING};
+#[cfg(feature = "jit")]
+use crate::interpreter::jit::Jit;
+use crate::interpreter::structs::StructType;
+use crate::interpreter::value::{Step, Value};
+
+pub struct Interpreter {
+    functions: HashMap<String, Rc<Function>>,
+    structs: HashMap<String, Rc<StructType>>,
     variables: HashMap<String, Value>,
-    program: Vec<Value>,
-    cache: HashMap<String, Value>,
+    frames: Vec<Frame>,
+    program: Vec<Json>,
+    // The line of the top-level statement being executed, and the module file it is in
+    line: usize,
+    file: Option<Rc<str>>,
+    hook: Option<Box<dyn Hook>>,
+    // Where `write` prints (stdout when not set)
+    output: Option<Box<dyn Write>>,
+    #[cfg(feature = "jit")]
+    jit: Jit,
+}
+
+/// 🖼️ A running function call: its local variables and the line it is executing
+pub struct Frame {
+    pub function: Rc<Function>,
+    pub variables: Scope,
+    // The scopes a lambda was created in, searched after its own variables
+    pub captured: Vec<Scope>,
+    pub line: usize,
+    // The module file the line is in (`None` for the program itself)
+    pub file: Option<Rc<str>>,
+}
+
+impl Frame {
+    /// 🔍 The scope of this call that holds `name`: its own variables, then what it captured
+    fn scope_of(&self, name: &str) -> Option<&Scope> {
+        std::iter::once(&self.variables)
+            .chain(&self.captured)
+            .find(|scope| scope.borrow().contains_key(name))
+    }
+}
+
+/// 🚦 Tells the caller whether a statement finished normally or hit a `return`
+enum Flow {
+    Normal,
+    Return(Value),
+}
+
+/// 🏷️ Splits an externally tagged AST node (e.g. `{"Integer": 5}`) into its variant name and payload
+pub fn node_kind(node: &Json) -> Option<(&str, &Json)> {
+    node.as_object()
+        .and_then(|obj| obj.iter().next())
+        .map(|(kind, payload)| (kind.as_str(), payload))
 }
 
-#[derive(Debug, Clone)]
-struct Function {
-    name: String,
-    args: Vec<String>,
-    body: Value,
+/// 📍 Like `node_kind`, but looks through the `Located` wrapper the parser puts around statements
+pub fn statement_kind(statement: &Json) -> Option<(&str, &Json)> {
+    match node_kind(statement) {
+        Some(("Located", located)) => statement_kind(&located["node"]),
+        kind => kind,
+    }
+}
+
+/// 📲 Points an error from a function passed to a builtin at the line the builtin was called
+/// on; `exit()` and failed assertions go through unchanged
+fn callback_failed(builtin: &str, line: usize, error: RuntimeError) -> RuntimeError {
+    match error {
+        RuntimeError::Exit { .. } | RuntimeError::AssertionFailed { .. } => error,
+        error => RuntimeError::CallbackFailed {
+            builtin: builtin.to_string(),
+            line,
+            error: Box::new(error),
+        },
+    }
 }
 
 impl Interpreter {
     /// 🆕 Initializes a new Interpreter with an empty function map and program list
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             functions: HashMap::new(),
+            structs: HashMap::new(),
             variables: HashMap::new(),
+            frames: Vec::new(),
             program: Vec::new(),
-            cache: HashMap::new(),
+            line: 0,
+            file: None,
+            hook: None,
+            output: None,
+            #[cfg(feature = "jit")]
+            jit: Jit::new(),
         }
     }
 
     /// ➕ Adds a new function to the functions map
     fn add_function(&mut self, func: Function) {
-        self.functions.insert(func.name.clone(), func);
-    }
-
-    /// 🎬 Interprets the loaded program by processing function calls and write statements
-    fn interpret(&mut self) {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-
-        write!(handle, "\n\nFunctions:\n\n").unwrap();
-        for (name, func) in self.functions.iter() {
-            write!(
-                handle,
-                "Function:\n  Name: \"{}\"\n  Args: {:?}\n  Body: {}\n\n",
-                name,
-                func.args,
-                serde_json::to_string_pretty(&func.body).unwrap()
-            )
-                .unwrap();
-        }
-
-        let program_len = self.program.len();
-        for i in 0..program_len {
-            let element = self.program[i].clone(); // Clone element to avoid borrowing conflicts
-
-            // Process IfElse elements
-            self.process_if_else(&element);
-
-            let write_objs = element.get("Write").map(|v| {
-                if v.is_array() {
-                    v.as_array().unwrap().clone()
-                } else {
-                    panic!("Expected 'Write' to be an array but got something else.");
-                }
-            });
-
-            self.process_function_call(&element);
-
-            if let Some(write_array) = write_objs {
-                let mut output_line = String::new();
-                for write_elem in write_array {
-                    if let Some(write_obj) = write_elem.as_object() {
-                        output_line.push_str(&self.process_write(write_obj, &HashMap::new()));
-                    }
-                }
-                writeln!(handle, "{}", output_line).unwrap();
-            } else {
-                self.process_variable_assign(&element);
-            }
+        // Native code compiled for the old definition (or for its callers) is now stale
+        #[cfg(feature = "jit")]
+        if self.functions.contains_key(&func.name) {
+            self.jit = Jit::new();
         }
+        self.functions.insert(func.name.clone(), Rc::new(func));
     }
 
+    /// 📥 Exposes the command-line arguments to the script as the `args` array
+    pub fn set_args(&mut self, args: &[String]) {
+        let args = args.iter().cloned().map(Value::String).collect();
+        self.variables
+            .insert("args".to_string(), Value::Array(args));
+    }
 
-    /// 🆕 Executes the entire block depending on the result from "process_if_else" (True for if_block, False for else_block)
-    fn process_if_else(&mut self, element: &Value) {
-        if let Some(if_else) = element.get("IfElse").and_then(Value::as_object) {
-            // Process condition
-            if let Some(condition) = if_else.get("condition").and_then(Value::as_object) {
-                if let Some(binary_op) = condition.get("BinaryOp") {
-                    if let Some(result) = self.evaluate_if_else_condition(binary_op) {
-                        let stdout = io::stdout();
-                        let mut handle = stdout.lock();
-                        writeln!(handle, "{}", if result { "True" } else { "False" }).unwrap();
+    /// 📋 Returns the global variables
+    pub fn variables(&self) -> &HashMap<String, Value> {
+        &self.variables
+    }
 
-                        // Execute corresponding block based on condition result
-                        if result {
-                            self.execute_block(if_else.get("if_block").unwrap());
-                        } else {
-                            self.execute_block(if_else.get("else_block").unwrap());
-                        }
-                    }
-                }
-            }
-        }
+    /// 📋 Returns the defined functions
+    pub fn functions(&self) -> &HashMap<String, Rc<Function>> {
+        &self.functions
     }
 
+    /// 🖼️ Returns the active function calls, innermost last
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
 
-    /// 🆕 Evaluates the condition for IfElse, checking if left == right for "=" operator
-    fn evaluate_if_else_condition(&self, binary_op: &Value) -> Option<bool> {
-        let left = binary_op.get("left")?;
-        let right = binary_op.get("right")?;
-        let op = binary_op.get("op")?.as_str()?;
+    /// 📍 Returns the line of the top-level statement being executed
+    pub fn line(&self) -> usize {
+        self.line
+    }
 
-        if op == "=" {
-            let left_val = self.get_value_from_identifier_or_value(left);
-            let right_val = self.get_value_from_identifier_or_value(right);
-            return Some(left_val == right_val);
-        }
+    /// 📄 Returns the module file of the top-level statement being executed (`None` for the
+    /// program itself)
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
 
-        None
+    /// 🪝 Installs a hook that is called before every statement and around every call (disables the JIT)
+    pub fn set_hook(&mut self, hook: Box<dyn Hook>) {
+        self.hook = Some(hook);
     }
 
-    /// 🆕 Extracts the value for an Identifier or directly from a Value
-    fn get_value_from_identifier_or_value(&self, val: &Value) -> Value {
-        if let Some(identifier) = val.get("Identifier") {
-            let id_str = identifier.as_str().unwrap();
-            self.variables.get(id_str).cloned().unwrap_or(Value::Null)
-        } else {
-            val.clone()
-        }
-    }
-
-    /// 🆕 Executes a block of code (if_block or else_block)
-    fn execute_block(&mut self, block: &Value) {
-        if let Some(block) = block.get("Block").and_then(Value::as_array) {
-            for statement in block {
-                // Process each statement in the block
-                if let Some(write_array) = statement.get("Write").and_then(Value::as_array) {
-                    let stdout = io::stdout();
-                    let mut handle = stdout.lock();
-                    let mut output_line = String::new();
-                    for write_elem in write_array {
-                        if let Some(write_obj) = write_elem.as_object() {
-                            output_line.push_str(&self.process_write(write_obj, &HashMap::new()));
-                        }
-                    }
-                    writeln!(handle, "{}", output_line).unwrap();
-                }
+    /// 🖨️ Sends the output of `write` somewhere other than stdout
+    pub fn set_output(&mut self, output: Box<dyn Write>) {
+        self.output = Some(output);
+    }
 
-                // Handle variable assignments and function calls
-                self.process_variable_assign(statement);
-                self.process_function_call(statement); // Handle any function calls in the block
+    /// 🎬 Interprets the loaded program statement by statement
+    pub fn interpret(&mut self) -> Result<(), RuntimeError> {
+        let program = self.program.clone();
+        for element in &program {
+            if let Flow::Return(_) = self.execute_statement(element)? {
+                break;
             }
         }
+        Ok(())
+    }
+
+    /// 🧪 Returns the name and line of every top-level `test` block, in order
+    pub fn tests(&self) -> Vec<(String, usize)> {
+        self.test_blocks()
+            .map(|(test, span)| {
+                let name = test["name"].as_str().unwrap_or_default().to_string();
+                (name, span["line"].as_u64().unwrap_or_default() as usize)
+            })
+            .collect()
     }
 
+    /// 🧪 Runs the body of the `index`th test block (as listed by `tests`) as top-level code,
+    /// after the code of the modules the program imports
+    pub fn run_test(&mut self, index: usize) -> Result<(), RuntimeError> {
+        let Some(body) = self
+            .test_blocks()
+            .nth(index)
+            .map(|(test, _)| test["body"].clone())
+        else {
+            return Ok(());
+        };
+        let modules: Vec<Json> = self
+            .program
+            .iter()
+            .filter(|statement| matches!(statement_kind(statement), Some(("Module", _))))
+            .cloned()
+            .collect();
+        for module in &modules {
+            self.execute_statement(module)?;
+        }
+        self.execute_block(&body)?;
+        Ok(())
+    }
 
+    /// 🔎 The top-level `test` blocks of the program with their spans
+    fn test_blocks(&self) -> impl Iterator<Item = (&Json, &Json)> {
+        self.program.iter().filter_map(|statement| {
+            let (test, span) = match node_kind(statement)? {
+                ("Located", located) => (&located["node"]["Test"], &located["span"]),
+                ("Test", test) => (test, &Json::Null),
+                _ => return None,
+            };
+            test.is_object().then_some((test, span))
+        })
+    }
 
+    /// 🧱 Executes every statement of a `Block` node, stopping at the first `return`
+    fn execute_block(&mut self, block: &Json) -> Result<Flow, RuntimeError> {
+        if let Some(statements) = block.get("Block").and_then(Json::as_array) {
+            for statement in statements {
+                if let Flow::Return(value) = self.execute_statement(statement)? {
+                    return Ok(Flow::Return(value));
+                }
+            }
+        }
+        Ok(Flow::Normal)
+    }
 
-    /// ➕ Processes a variable assignment and adds it to the variables map
-    fn process_variable_assign(&mut self, element: &Value) {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
+    /// ▶️ Executes a single statement
+    fn execute_statement(&mut self, statement: &Json) -> Result<Flow, RuntimeError> {
+        match node_kind(statement) {
+            Some(("Located", located)) => {
+                let node = &located["node"];
+                if !matches!(
+                    node_kind(node),
+                    Some(("Function" | "Struct" | "Impl" | "Comment" | "Test" | "Module", _))
+                ) {
+                    self.enter_line(located)?;
+                }
+                if let Some(("Assert", assert)) = node_kind(node) {
+                    self.process_assert(assert, located)?;
+                    return Ok(Flow::Normal);
+                }
+                self.execute_statement(node)
+            }
+            Some(("Write", items)) => {
+                self.process_write(items)?;
+                Ok(Flow::Normal)
+            }
+            Some(("VariableAssign", assign)) => {
+                self.process_variable_assign(assign)?;
+                Ok(Flow::Normal)
+            }
+            Some(("Destructure", destructure)) => {
+                self.process_destructure(destructure)?;
+                Ok(Flow::Normal)
+            }
+            Some(("Assign", assign)) => {
+                self.process_assign(assign)?;
+                Ok(Flow::Normal)
+            }
+            Some(("IfElse", if_else)) => self.process_if_else(if_else),
+            Some(("Coincide", coincide)) => self.process_coincide(coincide),
+            Some(("FunctionCall", call)) => {
+                self.process_function_call(call)?;
+                Ok(Flow::Normal)
+            }
+            Some(("Call", _)) => {
+                self.evaluate(statement)?;
+                Ok(Flow::Normal)
+            }
+            Some(("Return", expr)) => Ok(Flow::Return(self.evaluate(expr)?)),
+            Some(("Assert", assert)) => {
+                self.process_assert(assert, &Json::Null)?;
+                Ok(Flow::Normal)
+            }
+            Some(("Module", module)) => {
+                // A `return` in a module's code ends the module, not the program
+                self.execute_block(&module["body"])?;
+                Ok(Flow::Normal)
+            }
+            // Functions, structs and methods are hoisted by `load_from_json`, test blocks only run
+            // through `run_test`, comments do nothing
+            _ => Ok(Flow::Normal),
+        }
+    }
 
-        if let Some(var_assign) = element.get("VariableAssign").and_then(Value::as_object) {
-            let var_name = var_assign.get("name").unwrap().as_str().unwrap();
-            let var_value = var_assign.get("value").unwrap();
+    /// 📍 Records the line (and file) of a located statement about to run and lets the hook
+    /// (if any) see it first
+    fn enter_line(&mut self, located: &Json) -> Result<(), RuntimeError> {
+        let span = &located["span"];
+        let line = span["line"].as_u64().unwrap_or_default() as usize;
+        let (current_line, current_file) = match self.frames.last_mut() {
+            Some(frame) => (&mut frame.line, &mut frame.file),
+            None => (&mut self.line, &mut self.file),
+        };
+        *current_line = line;
+        // Only a call into another file allocates its name
+        let file = located["file"].as_str();
+        if current_file.as_deref() != file {
+            *current_file = file.map(Rc::from);
+        }
+        let file = current_file.clone();
 
-            let cache_key = format!("VariableAssign:{:?}", var_assign);
+        if let Some(mut hook) = self.hook.take() {
+            let span = serde_json::from_value(span.clone()).unwrap_or_default();
+            let result = hook.statement(self, span, file);
+            self.hook = Some(hook);
+            result?;
+        }
+        Ok(())
+    }
 
-            write!(handle, "{}\n", cache_key).unwrap();
+    /// 🆕 Executes the if_block or the else_block depending on the condition
+    fn process_if_else(&mut self, if_else: &Json) -> Result<Flow, RuntimeError> {
+        let condition = self.evaluate(&if_else["condition"])?;
+        if condition.is_truthy() {
+            self.execute_block(&if_else["if_block"])
+        } else if !if_else["else_block"].is_null() {
+            self.execute_block(&if_else["else_block"])
+        } else {
+            Ok(Flow::Normal)
+        }
+    }
 
-            if let Some(cached_result) = self.cache.get(&cache_key) {
-                self.variables
-                    .insert(var_name.to_string(), cached_result.clone());
-            } else {
-                self.variables
-                    .insert(var_name.to_string(), var_value.clone());
-                self.cache.insert(cache_key, var_value.clone());
+    /// 🎯 Runs the first arm whose pattern matches the value (or the `default` arm), after
+    /// assigning the names the pattern binds
+    fn process_coincide(&mut self, coincide: &Json) -> Result<Flow, RuntimeError> {
+        let value = self.evaluate(&coincide["expr"])?;
+        for case in coincide["cases"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+            let mut bindings = Vec::new();
+            if self.matches(&case[0], &value, false, &mut bindings)? {
+                for (name, bound) in bindings {
+                    self.assign_variable(&name, bound);
+                }
+                return self.execute_arm(&case[1]);
             }
         }
+        match &coincide["default"] {
+            Json::Null => Ok(Flow::Normal),
+            action => self.execute_arm(action),
+        }
+    }
+
+    /// 🧱 Executes the body of a `coincide` arm: a block or a single statement
+    fn execute_arm(&mut self, action: &Json) -> Result<Flow, RuntimeError> {
+        match node_kind(action) {
+            Some(("Block", _)) => self.execute_block(action),
+            _ => self.execute_statement(action),
+        }
     }
 
-    /// 🖋️ Handles the Write statement, which can be a string, identifier, integer, function call, or binary operation
-    fn process_write(
+    /// 🧩 Whether `value` matches a `coincide` pattern, collecting the names it binds; a name
+    /// on its own is compared with its value, a name inside `[...]` or `{...}` is bound
+    /// (`_` matches anything, the name of a struct matches its values)
+    fn matches(
         &mut self,
-        write_obj: &serde_json::Map<String, Value>,
-        local_scope: &HashMap<String, Value>,
-    ) -> String {
-        let cache_key = format!("{:?}", write_obj); // Creating a key for the cache
-        if let Some(cached_result) = self.cache.get(&cache_key) {
-            return cached_result.as_str().unwrap().to_string(); // Returning the cached result
-        }
-
-        let result = if let Some(binary_op) = write_obj.get("BinaryOp") {
-            let result = self.evaluate_binary_op(binary_op, local_scope);
-            result.to_string()
-        } else if let Some(string_val) = write_obj.get("String") {
-            string_val.as_str().unwrap().to_string()
-        } else if let Some(identifier) = write_obj.get("Identifier") {
-            let id_str = identifier.as_str().unwrap();
-            // First, let's check in the local scope (if in the function)
-            if let Some(val) = local_scope.get(id_str) {
-                let resolved_value = self.extract_value(val);
-                match resolved_value {
-                    Value::Number(n) => n.to_string(),
-                    Value::String(s) => s,
-                    _ => "Unsupported type".to_string(),
+        pattern: &Json,
+        value: &Value,
+        nested: bool,
+        bindings: &mut Vec<(String, Value)>,
+    ) -> Result<bool, RuntimeError> {
+        match node_kind(pattern) {
+            Some(("Identifier", name)) if name == "_" => Ok(true),
+            // The name of a struct matches any value of that struct
+            Some(("Identifier", name)) if self.structs.contains_key(name.as_str().unwrap()) => {
+                Ok(matches!(value, Value::Struct(ty, _) if ty.name == *name))
+            }
+            Some(("Identifier", name)) if nested => {
+                bindings.push((name.as_str().unwrap().to_string(), value.clone()));
+                Ok(true)
+            }
+            Some(("Alternatives", patterns)) => {
+                for pattern in patterns.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    let mut alternative = Vec::new();
+                    if self.matches(pattern, value, nested, &mut alternative)? {
+                        bindings.extend(alternative);
+                        return Ok(true);
+                    }
                 }
-            } else if let Some(global_val) = self.variables.get(id_str) {
-                // If it is not found in the local scope, check the global variables
-                let resolved_value = self.extract_value(global_val);
-                match resolved_value {
-                    Value::Number(n) => n.to_string(),
-                    Value::String(s) => s,
-                    _ => "Unsupported type".to_string(),
+                Ok(false)
+            }
+            Some(("Range", range)) => {
+                let start = self.evaluate(&range["start"])?;
+                let end = self.evaluate(&range["end"])?;
+                // Values that cannot be ordered against the bounds are outside the range
+                Ok(start.sort_order(value).is_some_and(|order| order.is_le())
+                    && value.sort_order(&end).is_some_and(|order| order.is_le()))
+            }
+            Some(("TypePattern", name)) => Ok(name == value.type_name()),
+            Some(("Construct", construct)) => {
+                let Value::Struct(ty, values) = value else {
+                    return Ok(false);
+                };
+                if ty.name != construct["name"] {
+                    return Ok(false);
                 }
-            } else {
-                format!("Identifier '{}' not found", id_str)
+                // The fields the pattern names must match; other fields are ignored
+                for field in construct["fields"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    let position = ty.position(field[0].as_str().unwrap())?;
+                    if !self.matches(&field[1], &values[position], true, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
             }
-        } else if let Some(integer_val) = write_obj.get("Integer") {
-            integer_val.as_i64().unwrap().to_string()
-        } else {
-            "Unknown data type in Write statement".to_string()
-        };
-
-        self.cache.insert(cache_key, Value::String(result.clone())); // Caching the result
-        result
-    }
-
-    /// 📞 Processes a function call and returns its result
-    fn process_function_call(&mut self, element: &Value) -> i64 {
-        if let Some(call_obj) = element.get("FunctionCall").and_then(Value::as_object) {
-            let stdout = io::stdout();
-            let mut handle = stdout.lock();
-            let cache_key = format!("{:?}", call_obj); // Creating a key for caching
-            write!(handle, "{}\n", cache_key).unwrap();
-
-            if let Some(name) = call_obj.get("name").and_then(Value::as_str) {
-                if let Some(func) = self.functions.get(name).cloned() {
-                    let args = call_obj["args"].as_array().unwrap();
-                    if args.len() == func.args.len() {
-                        let arg_map: HashMap<String, Value> = func
-                            .args
-                            .iter()
-                            .cloned()
-                            .zip(args.iter().cloned())
-                            .collect();
-
-                        // Creating a new array of variables for the local scope of the function
-                        let mut local_scope = self.variables.clone(); // Cloning global variables
-                        local_scope.extend(arg_map.clone()); // Adding arguments to the local scope
-
-                        // We execute all commands from the body of the function with a local scope
-                        let result = self.execute_function_body(&func.body, &local_scope);
-
-                        return result;
-                    } else {
-                        write!(
-                            handle,
-                            "Error: Function '{}' expects {} arguments but {} were provided\n",
-                            name,
-                            func.args.len(),
-                            args.len()
-                        )
-                            .unwrap();
+            Some((kind @ ("Array" | "Tuple"), patterns)) => {
+                let patterns = patterns.as_array().map(Vec::as_slice).unwrap_or(&[]);
+                let items = match (kind, value) {
+                    ("Array", Value::Array(items)) | ("Tuple", Value::Tuple(items)) => items,
+                    _ => return Ok(false),
+                };
+                if items.len() != patterns.len() {
+                    return Ok(false);
+                }
+                for (pattern, item) in patterns.iter().zip(items) {
+                    if !self.matches(pattern, item, true, bindings)? {
+                        return Ok(false);
                     }
-                } else {
-                    write!(handle, "Function '{}' not found\n", name).unwrap();
                 }
+                Ok(true)
             }
+            Some(("Dictionary", pairs)) => {
+                let Value::Dictionary(entries) = value else {
+                    return Ok(false);
+                };
+                // The keys the pattern names must be there; other keys are ignored
+                for pair in pairs.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    let key = match node_kind(&pair[0]) {
+                        Some(("Identifier", id)) => Value::String(id.as_str().unwrap().to_string()),
+                        _ => self.evaluate(&pair[0])?,
+                    };
+                    let Some((_, entry)) = entries.iter().find(|(existing, _)| *existing == key) else {
+                        return Ok(false);
+                    };
+                    if !self.matches(&pair[1], entry, true, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            _ => Ok(self.evaluate(pattern)? == *value),
         }
-        0
     }
 
+    /// ✔️ Fails with the assertion's message (or its condition) if the condition is false
+    fn process_assert(&mut self, assert: &Json, located: &Json) -> Result<(), RuntimeError> {
+        if self.evaluate(&assert["condition"])?.is_truthy() {
+            return Ok(());
+        }
+        let message = if assert["message"].is_null() {
+            let condition: AST = serde_json::from_value(assert["condition"].clone())
+                .expect("Failed to deserialize assert condition");
+            expression(&condition)
+        } else {
+            self.evaluate(&assert["message"])?.to_string()
+        };
+        let span = &located["span"];
+        Err(RuntimeError::AssertionFailed {
+            message,
+            file: located["file"].as_str().map(str::to_string),
+            line: span["line"].as_u64().unwrap_or_default() as usize,
+            column: span["column"].as_u64().unwrap_or_default() as usize,
+        })
+    }
 
-    /// 🛠️ Executes the body of a function and returns a result (if any)
-    fn execute_function_body(&mut self, body: &Value, local_scope: &HashMap<String, Value>) -> i64 {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        let cache_key = format!("{:?}{:?}", body, local_scope);
+    /// ➕ Processes a variable assignment in the current scope
+    fn process_variable_assign(&mut self, var_assign: &Json) -> Result<(), RuntimeError> {
+        let var_name = var_assign["name"].as_str().unwrap();
+        let value = self.evaluate(&var_assign["value"])?;
+        self.assign_variable(var_name, value);
+        Ok(())
+    }
 
-        // Вывод ключа кэша для наблюдения
-        write!(handle, "Cash key: {}\n", cache_key).unwrap();
+    /// 🎭 Assigns the items of a tuple or array to the names on the left, in order (`_` skips one)
+    fn process_destructure(&mut self, destructure: &Json) -> Result<(), RuntimeError> {
+        let names = destructure["names"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        let items = match self.evaluate(&destructure["value"])? {
+            Value::Tuple(items) | Value::Array(items) if items.len() == names.len() => items,
+            Value::Tuple(items) | Value::Array(items) => {
+                return Err(RuntimeError::TypeError {
+                    message: format!(
+                        "cannot unpack {} values into {} names",
+                        items.len(),
+                        names.len()
+                    ),
+                })
+            }
+            other => {
+                return Err(RuntimeError::TypeError {
+                    message: format!("cannot unpack a value of type {}", other.type_name()),
+                })
+            }
+        };
+        for (name, item) in names.iter().zip(items) {
+            let name = name.as_str().unwrap();
+            if name != "_" {
+                self.assign_variable(name, item);
+            }
+        }
+        Ok(())
+    }
 
-        // Не используем закэшированный результат для повторного выполнения
-        let mut return_value: Option<i64> = None;
-        let current_scope = local_scope.clone();
+    /// ✏️ Processes an assignment to an element or field, changing the variable it is in
+    fn process_assign(&mut self, assign: &Json) -> Result<(), RuntimeError> {
+        let value = self.evaluate(&assign["value"])?;
+        let (name, path) = self.place(&assign["target"])?;
+        self.assign_place(&name, &path, value)
+    }
 
-        if let Some(block) = body.get("Block").and_then(Value::as_array) {
-            for statement in block {
-                // Обрабатываем каждую команду `Write` независимо
-                if let Some(write_array) = statement.get("Write").and_then(Value::as_array) {
-                    let mut output_line = String::new();
-                    for write_elem in write_array {
-                        if let Some(write_obj) = write_elem.as_object() {
-                            output_line.push_str(&self.process_write(write_obj, &current_scope));
-                        }
-                    }
-                    writeln!(handle, "{}", output_line).unwrap();
+    /// ✏️ Replaces what `path` leads to inside the variable `name`
+    fn assign_place(
+        &mut self,
+        name: &str,
+        path: &[Step],
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        match self.frames.last().map(|frame| frame.scope_of(name)) {
+            Some(Some(scope)) => {
+                if let Some(root) = scope.borrow_mut().get_mut(name) {
+                    return root.set_path(path, value);
                 }
+            }
+            Some(None) => {}
+            None => {
+                if let Some(root) = self.variables.get_mut(name) {
+                    return root.set_path(path, value);
+                }
+            }
+        }
+        // Like `x is ...`, changing a global inside a function changes a local copy
+        let mut root = self.variables[name].clone();
+        root.set_path(path, value)?;
+        self.assign_variable(name, root);
+        Ok(())
+    }
 
-                // Обработка присвоения переменной и возвратного значения
-                self.process_variable_assign(statement);
-
-                if let Some(return_obj) = statement.get("Return").and_then(Value::as_object) {
-                    return_value = Some(self.process_return(return_obj, &current_scope));
+    /// 📌 The variable an assignment target is in, and the indexes and fields that lead from it
+    /// to the target
+    fn place(&mut self, target: &Json) -> Result<(String, Vec<Step>), RuntimeError> {
+        match node_kind(target) {
+            Some(("Identifier", id)) => {
+                let name = id.as_str().unwrap();
+                if self.has_variable(name) {
+                    return Ok((name.to_string(), Vec::new()));
                 }
+                self.fields(name).ok_or_else(|| RuntimeError::UndefinedVariable {
+                    name: name.to_string(),
+                })
+            }
+            Some(("Index", index)) => {
+                let (name, mut path) = self.place(&index["object"])?;
+                path.push(Step::Index(self.evaluate(&index["index"])?));
+                Ok((name, path))
+            }
+            Some(("Field", field)) => {
+                let (name, mut path) = self.place(&field["object"])?;
+                path.push(Step::Field(field["name"].as_str().unwrap().to_string()));
+                Ok((name, path))
+            }
+            _ => {
+                let target: AST = serde_json::from_value(target.clone())
+                    .expect("Failed to deserialize assignment target");
+                Err(RuntimeError::TypeError {
+                    message: format!("cannot assign to {}", expression(&target)),
+                })
             }
         }
+    }
 
-        // Возвращаем результат, не кэшируя его для повторного использования
-        return_value.unwrap_or(0)
+    /// 🌱 Whether an expression is a variable, or an index or field read from one (`a`,
+    /// `a[0]`, `a.b[1]`), and so names a place a value can be stored back to
+    fn is_place(target: &Json) -> bool {
+        match node_kind(target) {
+            Some(("Identifier", _)) => true,
+            Some(("Index" | "Field", node)) => Self::is_place(&node["object"]),
+            _ => false,
+        }
     }
 
+    /// 🏷️ Splits a dotted name (`point.x`, `config.db.port`) into the longest leading part that
+    /// is a variable and the fields read from it
+    fn fields(&self, name: &str) -> Option<(String, Vec<Step>)> {
+        let mut end = name.len();
+        while let Some(dot) = name[..end].rfind('.') {
+            if self.has_variable(&name[..dot]) {
+                let fields = name[dot + 1..]
+                    .split('.')
+                    .map(|field| Step::Field(field.to_string()))
+                    .collect();
+                return Some((name[..dot].to_string(), fields));
+            }
+            end = dot;
+        }
+        None
+    }
 
-    /// ↩️ Processes the Return statement and extracts the value to be returned
-    fn process_return(
-        &mut self,
-        return_obj: &serde_json::Map<String, Value>,
-        local_scope: &HashMap<String, Value>,
-    ) -> i64 {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        let cache_key = format!("Return:{:?}{:?}", return_obj, local_scope); // Creating a key for caching
-
-        write!(handle, "{}\n", cache_key).unwrap();
-
-        if let Some(cached_result) = self.cache.get(&cache_key) {
-            return cached_result.as_i64().unwrap(); // Returning the result from the cache
-        }
-
-        let result = if let Some(identifier) = return_obj.get("Identifier") {
-            if let Some(val) = local_scope.get(identifier.as_str().unwrap()) {
-                self.extract_value(val).as_i64().unwrap()
-            } else {
-                write!(
-                    handle,
-                    "Return identifier '{}' not found\n",
-                    identifier.as_str().unwrap()
-                )
-                    .unwrap();
-                0
-            }
-        } else if let Some(binary_op) = return_obj.get("BinaryOp") {
-            self.evaluate_binary_op(binary_op, local_scope)
-                .as_i64()
-                .unwrap()
-        } else {
-            write!(handle, "Unknown return type\n").unwrap();
-            0
-        };
+    /// 🖋️ Handles the Write statement: evaluates every expression and prints them space-separated
+    fn process_write(&mut self, items: &Json) -> Result<(), RuntimeError> {
+        let mut output = Vec::new();
+        for item in items.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+            output.push(self.evaluate(item)?.to_string());
+        }
 
-        self.cache.insert(cache_key, Value::Number(result.into())); // Caching the result
-        result
+        match &mut self.output {
+            Some(out) => writeln!(out, "{}", output.join(" ")).unwrap(),
+            None => writeln!(io::stdout().lock(), "{}", output.join(" ")).unwrap(),
+        }
+        Ok(())
     }
 
-    /// ➕ Evaluates a binary operation (e.g., addition, subtraction, multiplication, division)
-    fn evaluate_binary_op(
-        &mut self,
-        binary_op: &Value,
-        local_scope: &HashMap<String, Value>,
-    ) -> Value {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        let left = self.resolve_value(&binary_op["left"], local_scope);
-        let right = self.resolve_value(&binary_op["right"], local_scope);
-        let op = binary_op["op"].as_str().unwrap();
-
-        let cache_key = format!("{:?} {} {:?}", left, op, right); // Creating a key for the cache
-        write!(handle, "{}\n", cache_key).unwrap();
-
-        if let Some(cached_result) = self.cache.get(&cache_key) {
-            return cached_result.clone(); // Returning a value from the cache, if there is one
-        }
-
-        let result = match op {
-            "+" => {
-                if let (Some(left_int), Some(right_int)) = (left.as_i64(), right.as_i64()) {
-                    Value::Number((left_int + right_int).into())
-                } else {
-                    write!(
-                        handle,
-                        "BinaryOp error: one of the operands is not an integer.\n"
-                    )
-                        .unwrap();
-                    Value::Null
+    /// 🧮 Evaluates an expression node to a runtime value
+    pub fn evaluate(&mut self, expr: &Json) -> Result<Value, RuntimeError> {
+        match node_kind(expr) {
+            Some(("Integer", n)) => Ok(Value::Integer(n.as_i64().unwrap())),
+            Some(("Float", f)) => Ok(Value::Float(f.as_f64().unwrap())),
+            Some(("Bool", b)) => Ok(Value::Bool(b.as_bool().unwrap())),
+            Some(("String", s)) => Ok(Value::String(s.as_str().unwrap().to_string())),
+            Some(("Identifier", id)) => {
+                let name = id.as_str().unwrap();
+                match (self.variable(name), self.functions.get(name)) {
+                    (Some(value), _) => Ok(value),
+                    // A named function used as a value
+                    (None, Some(func)) => Ok(Value::Function(Rc::new(Closure {
+                        function: Rc::clone(func),
+                        captured: Vec::new(),
+                    }))),
+                    (None, None) => {
+                        let Some((root, fields)) = self.fields(name) else {
+                            return Err(RuntimeError::UndefinedVariable {
+                                name: name.to_string(),
+                            });
+                        };
+                        let root = self.variable(&root).unwrap();
+                        fields.iter().try_fold(root, |value, field| value.get(field))
+                    }
                 }
             }
-            "-" => {
-                if let (Some(left_int), Some(right_int)) = (left.as_i64(), right.as_i64()) {
-                    Value::Number((left_int - right_int).into())
-                } else {
-                    write!(
-                        handle,
-                        "BinaryOp error: one of the operands is not an integer.\n"
-                    )
-                        .unwrap();
-                    Value::Null
+            Some(("Index", index)) => {
+                let object = self.evaluate(&index["object"])?;
+                object.index(&self.evaluate(&index["index"])?)
+            }
+            Some(("Slice", slice)) => {
+                let object = self.evaluate(&slice["object"])?;
+                let start = self.evaluate_optional(&slice["start"])?;
+                let end = self.evaluate_optional(&slice["end"])?;
+                object.slice(start.as_ref(), end.as_ref())
+            }
+            Some(("Field", field)) => {
+                let object = self.evaluate(&field["object"])?;
+                object.field(field["name"].as_str().unwrap())
+            }
+            Some(("Lambda", lambda)) => {
+                // The scopes it is created in are shared with the lambda, so it sees (and
+                // changes) their variables as they are when it runs; so are the globals
+                let captured = self
+                    .frames
+                    .last()
+                    .map(|frame| {
+                        std::iter::once(&frame.variables)
+                            .chain(&frame.captured)
+                            .map(Rc::clone)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Ok(Value::Function(Rc::new(Closure {
+                    function: Rc::new(Function::from_lambda(lambda)),
+                    captured,
+                })))
+            }
+            Some(("Call", call)) => {
+                if let Some(("Field", field)) = node_kind(&call["callee"]) {
+                    let method = field["name"].as_str().unwrap();
+                    return self.process_method_call(&field["object"], method, &call["args"]);
+                }
+                let callee = self.evaluate(&call["callee"])?;
+                let args = self.evaluate_list(&call["args"])?;
+                match callee {
+                    Value::Function(closure) => {
+                        let name = closure.function.name.clone();
+                        self.call_closure(&name, &closure, args)
+                    }
+                    other => Err(RuntimeError::TypeError {
+                        message: format!("cannot call a value of type {}", other.type_name()),
+                    }),
                 }
             }
-            "*" => {
-                if let (Some(left_int), Some(right_int)) = (left.as_i64(), right.as_i64()) {
-                    Value::Number((left_int * right_int).into())
-                } else {
-                    write!(
-                        handle,
-                        "BinaryOp error: one of the operands is not an integer.\n"
-                    )
-                        .unwrap();
-                    Value::Null
+            Some(("BinaryOp", binary_op)) => {
+                let left = self.evaluate(&binary_op["left"])?;
+                let right = self.evaluate(&binary_op["right"])?;
+                left.binary_op(binary_op["op"].as_str().unwrap(), &right)
+            }
+            Some(("FunctionCall", call)) => self.process_function_call(call),
+            Some(("Construct", construct)) => {
+                let name = construct["name"].as_str().unwrap();
+                let Some(ty) = self.structs.get(name).cloned() else {
+                    return Err(RuntimeError::UndefinedStruct {
+                        name: name.to_string(),
+                    });
+                };
+                let mut fields = Vec::new();
+                for field in construct["fields"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    let value = self.evaluate(&field[1])?;
+                    fields.push((field[0].as_str().unwrap().to_string(), value));
                 }
+                ty.construct(fields)
             }
-            "/" => {
-                if let (Some(left_int), Some(right_int)) = (left.as_i64(), right.as_i64()) {
-                    if right_int != 0 {
-                        Value::Number((left_int / right_int).into())
-                    } else {
-                        write!(handle, "Error: Division by zero\n").unwrap();
-                        Value::Null
-                    }
-                } else {
-                    write!(
-                        handle,
-                        "BinaryOp error: one of the operands is not an integer.\n"
-                    )
-                        .unwrap();
-                    Value::Null
+            Some(("Array", items)) => Ok(Value::Array(self.evaluate_list(items)?)),
+            Some(("Tuple", items)) => Ok(Value::Tuple(self.evaluate_list(items)?)),
+            Some(("Dictionary", pairs)) => {
+                let mut dictionary = Vec::new();
+                for pair in pairs.as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    // Bare identifiers used as keys (`{a: 1}`) are treated as string keys
+                    let key = match node_kind(&pair[0]) {
+                        Some(("Identifier", id)) => Value::String(id.as_str().unwrap().to_string()),
+                        _ => self.evaluate(&pair[0])?,
+                    };
+                    let value = self.evaluate(&pair[1])?;
+                    dictionary.push((key, value));
                 }
+                Ok(Value::Dictionary(dictionary))
             }
-            _ => {
-                write!(handle, "Unknown binary operator: {}\n", op).unwrap();
-                Value::Null
+            _ => Err(RuntimeError::TypeError {
+                message: format!("cannot evaluate {}", expr),
+            }),
+        }
+    }
+
+    /// ❔ Evaluates an optional expression (`null` in the JSON when it is left out)
+    fn evaluate_optional(&mut self, expr: &Json) -> Result<Option<Value>, RuntimeError> {
+        if expr.is_null() {
+            return Ok(None);
+        }
+        self.evaluate(expr).map(Some)
+    }
+
+    /// 📚 Evaluates every element of a JSON array of expressions
+    fn evaluate_list(&mut self, items: &Json) -> Result<Vec<Value>, RuntimeError> {
+        items
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+            .iter()
+            .map(|item| self.evaluate(item))
+            .collect()
+    }
+
+    /// 🔍 Looks a variable up in the current function frame and what it captured, then in
+    /// the globals
+    fn variable(&self, name: &str) -> Option<Value> {
+        match self.frames.last().and_then(|frame| frame.scope_of(name)) {
+            Some(scope) => scope.borrow().get(name).cloned(),
+            None => self.variables.get(name).cloned(),
+        }
+    }
+
+    /// ❓ Tells whether `name` is a variable, without copying its value
+    fn has_variable(&self, name: &str) -> bool {
+        self.frames
+            .last()
+            .and_then(|frame| frame.scope_of(name))
+            .is_some()
+            || self.variables.contains_key(name)
+    }
+
+    /// 📝 Assigns a variable: inside a function it is local unless a lambda captured it,
+    /// otherwise global
+    fn assign_variable(&mut self, name: &str, value: Value) {
+        match self.frames.last() {
+            Some(frame) => {
+                let scope = frame.scope_of(name).unwrap_or(&frame.variables);
+                scope.borrow_mut().insert(name.to_string(), value);
             }
+            None => {
+                self.variables.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    /// 📞 Evaluates the arguments of a function call and invokes the function (or a builtin)
+    fn process_function_call(&mut self, call_obj: &Json) -> Result<Value, RuntimeError> {
+        let name = call_obj["name"].as_str().unwrap();
+        // A variable holding a function hides a named function or builtin with the same name
+        if let Some(Value::Function(closure)) = self.variable(name) {
+            let args = self.evaluate_list(&call_obj["args"])?;
+            return self.call_closure(name, &closure, args);
+        }
+        // A struct called like a function takes its fields in order (`Point(1, 2)`)
+        if let Some(ty) = self.structs.get(name).cloned() {
+            let args = self.evaluate_list(&call_obj["args"])?;
+            return ty.build(args);
+        }
+        // `p.len()` calls a method on the variable `p` (or on one of its fields), even when
+        // the variable is named like a type with that method (`Point.len`)
+        if let Some((root, mut path)) = self.fields(name) {
+            let Some(Step::Field(method)) = path.pop() else {
+                unreachable!("a dotted name ends in a field")
+            };
+            let args = self.evaluate_list(&call_obj["args"])?;
+            return self.call_method_at(&root, &path, &method, args);
+        }
+        let Some(func) = self.functions.get(name).cloned() else {
+            let args = self.evaluate_list(&call_obj["args"])?;
+            let line = self.frames.last().map_or(self.line, |frame| frame.line);
+            let mut callback = |closure: &Closure, args: Vec<Value>| {
+                self.call_closure(&closure.function.name, closure, args)
+                    .map_err(|error| callback_failed(name, line, error))
+            };
+            return call_builtin(name, &args, &mut callback).unwrap_or_else(|| {
+                Err(RuntimeError::UndefinedFunction {
+                    name: name.to_string(),
+                })
+            });
         };
 
-        self.cache.insert(cache_key, result.clone()); // Saving the result to the cache
-        write!(handle, "Caching completed successfully!\n").unwrap();
-        result
-    }
-
-    /// 🔍 Resolves a value from an identifier, string, integer, or binary operation
-    fn resolve_value(&mut self, value: &Value, local_scope: &HashMap<String, Value>) -> Value {
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
-        if let Some(identifier) = value.as_object().and_then(|v| v.get("Identifier")) {
-            let id_str = identifier.as_str().unwrap();
-            if let Some(val) = local_scope.get(id_str) {
-                self.extract_value(val) // 🧲 Getting the value of the variable
-            } else if let Some(global_val) = self.variables.get(id_str) {
-                self.extract_value(global_val) // 🧲 We get the value of the global variable
-            } else {
-                write!(handle, "Identifier '{}' not found\n", id_str).unwrap();
-                Value::Null
-            }
-        } else if let Some(integer_obj) = value.as_object().and_then(|v| v.get("Integer")) {
-            Value::Number(integer_obj.as_i64().unwrap().into()) // 🔢 Extracts and returns the integer directly
-        } else if let Some(binary_op) = value.as_object().and_then(|v| v.get("BinaryOp")) {
-            self.evaluate_binary_op(binary_op, local_scope) // ➕ Processes and returns the result of a binary operation
-        } else {
-            write!(handle, "Unexpected value type: {:?}\n", value).unwrap(); // ⚠️ Unexpected type error
-            Value::Null
+        let args = self.evaluate_list(&call_obj["args"])?;
+        if args.len() != func.args.len() {
+            return Err(RuntimeError::ArityMismatch {
+                name: name.to_string(),
+                expected: func.args.len(),
+                found: args.len(),
+            });
+        }
+
+        self.call_function(func, args)
+    }
+
+    /// 📨 Evaluates `object.method(args)`; a method that changes its receiver (`rows[0].push(x)`)
+    /// changes the variable the receiver is in
+    fn process_method_call(
+        &mut self,
+        object: &Json,
+        method: &str,
+        args: &Json,
+    ) -> Result<Value, RuntimeError> {
+        // A method that changes a temporary (`f()[0].push(2)`) changes only the copy
+        if MUTATING.contains(&method) && Self::is_place(object) {
+            let (name, path) = self.place(object)?;
+            let args = self.evaluate_list(args)?;
+            return self.call_method_at(&name, &path, method, args);
         }
+        let mut receiver = self.evaluate(object)?;
+        let args = self.evaluate_list(args)?;
+        self.call_method(&mut receiver, method, args)
     }
 
-    /// 🧲 Extracts the actual value from a Value type (e.g., Integer, String, or other)
-    fn extract_value(&self, value: &Value) -> Value {
-        if let Some(integer) = value.get("Integer") {
-            Value::Number(integer.as_i64().unwrap().into()) // 🔢 Extracts an integer value
-        } else if let Some(string) = value.get("String") {
-            Value::String(string.as_str().unwrap().to_string()) // 📝 Extracts a string value
-        } else {
-            value.clone() // 📝 Returns the value as-is for other types
+    /// 📨 Calls a method on what `path` leads to inside the variable `name`, storing the
+    /// receiver back if the method changes it
+    fn call_method_at(
+        &mut self,
+        name: &str,
+        path: &[Step],
+        method: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let root = self.variable(name).unwrap();
+        let mut receiver = path.iter().try_fold(root, |value, step| value.get(step))?;
+        let result = self.call_method(&mut receiver, method, args)?;
+        if MUTATING.contains(&method) {
+            self.assign_place(name, path, receiver)?;
         }
+        Ok(result)
     }
 
-    /// 📂 Loads the program and functions from a JSON string
-    fn load_from_json(&mut self, json_str: &str) {
-        let data: Value = from_str(json_str).unwrap();
-        self.program = data["Program"].as_array().unwrap().to_vec();
+    /// 📨 Calls a method on a value: a function stored under that name in a dictionary or
+    /// struct field, a method from an `impl` block for the value's type, or a builtin method
+    fn call_method(
+        &mut self,
+        receiver: &mut Value,
+        method: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        // Other values stored under the method's name leave the method reachable (`{len: 5}.len()`)
+        let stored = match &*receiver {
+            Value::Struct(ty, values) => ty.position(method).ok().map(|i| &values[i]),
+            Value::Dictionary(pairs) => pairs
+                .iter()
+                .find(|(key, _)| *key == Value::String(method.to_string()))
+                .map(|(_, value)| value),
+            _ => None,
+        };
+        if let Some(Value::Function(closure)) = stored {
+            let closure = Rc::clone(closure);
+            return self.call_closure(method, &closure, args);
+        }
 
-        // Создаем копию `self.program`, чтобы избежать заимствований
-        let program_copy = self.program.clone();
-        self.extract_functions_recursive(&program_copy);
+        let type_name = match &*receiver {
+            Value::Struct(ty, _) => ty.name.clone(),
+            other => other.type_name().to_string(),
+        };
+        let qualified = format!("{}.{}", type_name, method);
+        if let Some(func) = self.functions.get(&qualified).cloned() {
+            // The first parameter is the receiver
+            let expected = func.args.len().saturating_sub(1);
+            if args.len() != expected {
+                return Err(RuntimeError::ArityMismatch {
+                    name: qualified,
+                    expected,
+                    found: args.len(),
+                });
+            }
+            let args = std::iter::once(receiver.clone()).chain(args).collect();
+            return self.call_function(func, args);
+        }
 
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
+        let line = self.frames.last().map_or(self.line, |frame| frame.line);
+        let mut callback = |closure: &Closure, args: Vec<Value>| {
+            self.call_closure(&closure.function.name, closure, args)
+                .map_err(|error| callback_failed(method, line, error))
+        };
+        methods::call_method(receiver, method, &args, &mut callback).unwrap_or_else(|| {
+            Err(RuntimeError::UndefinedMethod {
+                type_name,
+                method: method.to_string(),
+            })
+        })
+    }
 
-        write!(handle, "Variables:\n\n").unwrap();
-        for element in &self.program {
-            if let Some(var_assign) = element.get("VariableAssign") {
-                let var_assign_obj = var_assign.as_object().unwrap();
-                let name = var_assign_obj.get("name").unwrap().as_str().unwrap();
-                let value = var_assign_obj.get("value").unwrap();
-                write!(
-                    handle,
-                    "Variable:\n  Name: \"{}\"\n  Value: {}\n\n",
-                    name,
-                    serde_json::to_string_pretty(value).unwrap()
-                )
-                    .unwrap();
+    /// 🛠️ Runs a named function in a fresh frame and returns its result (null without `return`)
+    fn call_function(&mut self, func: Rc<Function>, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        // Native code has no statements for a hook to see
+        #[cfg(feature = "jit")]
+        if self.hook.is_none() {
+            let result =
+                self.jit.try_call(&func, &args, &self.functions, &self.structs, &self.variables);
+            if let Some(result) = result {
+                return Ok(result);
             }
         }
+
+        let variables = func.args.iter().cloned().zip(args).collect();
+        self.run_function(func, variables, Vec::new())
     }
 
+    /// 🎁 Calls a function value (known as `name` at the call site): its parameters are set
+    /// on top of what it captured
+    fn call_closure(
+        &mut self,
+        name: &str,
+        closure: &Closure,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let func = Rc::clone(&closure.function);
+        if args.len() != func.args.len() {
+            return Err(RuntimeError::ArityMismatch {
+                name: name.to_string(),
+                expected: func.args.len(),
+                found: args.len(),
+            });
+        }
+        let variables = func.args.iter().cloned().zip(args).collect();
+        self.run_function(func, variables, closure.captured.clone())
+    }
+
+    /// 🖼️ Runs a function body in a new frame holding `variables`, on top of the `captured`
+    /// scopes of a lambda
+    fn run_function(
+        &mut self,
+        func: Rc<Function>,
+        variables: HashMap<String, Value>,
+        captured: Vec<Scope>,
+    ) -> Result<Value, RuntimeError> {
+        let (line, file) = match self.frames.last() {
+            Some(frame) => (frame.line, frame.file.clone()),
+            None => (self.line, self.file.clone()),
+        };
+        self.frames.push(Frame {
+            function: Rc::clone(&func),
+            variables: Rc::new(RefCell::new(variables)),
+            captured,
+            line,
+            file,
+        });
+        if let Some(mut hook) = self.hook.take() {
+            hook.call(self, &func);
+            self.hook = Some(hook);
+        }
+        let result = self.execute_block(&func.body);
+        if let Some(mut hook) = self.hook.take() {
+            hook.returned(self, &func);
+            self.hook = Some(hook);
+        }
+        self.frames.pop();
+
+        match result? {
+            Flow::Return(value) => Ok(value),
+            Flow::Normal => Ok(Value::Null),
+        }
+    }
+
+    /// 📊 Returns the JIT statistics report
+    #[cfg(feature = "jit")]
+    pub fn jit_report(&self) -> String {
+        self.jit.report()
+    }
+
+    /// 📂 Loads the program and functions from a JSON string
+    pub fn load_from_json(&mut self, json_str: &str) {
+        let data: Json = from_str(json_str).unwrap();
+        self.program = data["Program"].as_array().unwrap().to_vec();
+
+        // Создаем копию `self.program`, чтобы избежать заимствований
+        let program_copy = self.program.clone();
+        self.extract_functions_recursive(&program_copy);
+    }
 
     // Функция для рекурсивного извлечения функций
-    fn extract_functions_recursive(&mut self, elements: &[Value]) {
+    fn extract_functions_recursive(&mut self, elements: &[Json]) {
         for element in elements {
             if let Some(func_obj) = element.get("Function") {
-                let function = Function {
-                    name: func_obj["name"].as_str().unwrap().to_string(),
-                    args: func_obj["args"]["FunctionArgs"]
-                        .as_array()
-                        .unwrap()
-                        .iter()
-                        .map(|arg| arg["Identifier"].as_str().unwrap().to_string())
-                        .collect(),
-                    body: func_obj["body"].clone(),
-                };
-                self.add_function(function);
+                self.add_function(Function::from_json(func_obj));
+            }
+            if let Some(ty) = element.get("Struct").and_then(StructType::from_json) {
+                self.structs.insert(ty.name.clone(), Rc::new(ty));
+            }
+            // Methods are functions named after their type (`Point.len`)
+            if let Some(methods) = element.get("Impl") {
+                let owner = methods["name"].as_str().unwrap();
+                for method in methods["methods"].as_array().map(Vec::as_slice).unwrap_or(&[]) {
+                    if let Some(("Function", func_obj)) = statement_kind(method) {
+                        let mut func = Function::from_json(func_obj);
+                        func.name = format!("{}.{}", owner, func.name);
+                        self.add_function(func);
+                        self.extract_functions_recursive(std::slice::from_ref(&func_obj["body"]));
+                    }
+                }
+                continue;
             }
 
             // Проверяем вложенные объекты
-            for (_, value) in element.as_object().unwrap().iter() {
-                if value.is_array() {
-                    self.extract_functions_recursive(value.as_array().unwrap());
-                } else if value.is_object() {
-                    self.extract_functions_recursive(&[value.clone()]);
+            if let Some(object) = element.as_object() {
+                for value in object.values() {
+                    if let Some(array) = value.as_array() {
+                        self.extract_functions_recursive(array);
+                    } else if value.is_object() {
+                        self.extract_functions_recursive(std::slice::from_ref(value));
+                    }
                 }
             }
         }
     }
 }
 
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// 🎬 Entry point: Initializes the interpreter and runs the program from a JSON string
 pub fn interpret_from_json(json_str: &str) {
     let mut interpreter = Interpreter::new();
     interpreter.load_from_json(json_str); // 📂 Loads the program from JSON
-    interpreter.interpret(); // 🎬 Interprets and executes the program
-}
\ No newline at end of file
+    if let Err(err) = interpreter.interpret() {
+        // 🚨 Report runtime errors instead of panicking
+        eprintln!("Runtime error: {}", err);
+    }
+}
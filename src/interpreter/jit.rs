@@ -0,0 +1,787 @@
+use colored::Colorize;
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::{
+    types, AbiParam, Block, FuncRef, InstBuilder, MemFlags, Signature, Type, UserFuncName,
+    Value as IrValue,
+};
+use cranelift_codegen::settings::{self, Configurable};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, FuncId, Linkage, Module};
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::interpreter::function::Function;
+use crate::interpreter::interpreter::{node_kind, statement_kind};
+use crate::interpreter::structs::StructType;
+use crate::interpreter::value::Value;
+
+/// 🔥 Number of interpreted calls after which a function is considered hot
+pub const JIT_THRESHOLD: u64 = 50;
+
+/// 🚪 Uniform native entry point: (pointer to integer arguments, pointer to deopt status) -> result
+type EntryFn = extern "C" fn(*const i64, *mut i64) -> i64;
+
+/// 🧩 A compiled call graph: the entry point of every newly compiled function, and the names of
+/// all the functions in the graph
+type Group = (Vec<(String, EntryFn)>, Rc<[String]>);
+
+/// 🏷️ The types the compiled subset of Glint understands
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ty {
+    Int,
+    Bool,
+}
+
+/// 🚦 Where a function is in its JIT lifecycle; a compiled function keeps the names its native
+/// code calls directly (itself included)
+enum JitState {
+    Profiling,
+    Compiled(EntryFn, Rc<[String]>),
+    Rejected(String),
+}
+
+/// 📈 Per-function counters collected while the program runs
+struct FunctionProfile {
+    calls: u64,
+    native_calls: u64,
+    guard_failures: u64,
+    deopts: u64,
+    state: JitState,
+}
+
+impl FunctionProfile {
+    fn new() -> Self {
+        Self {
+            calls: 0,
+            native_calls: 0,
+            guard_failures: 0,
+            deopts: 0,
+            state: JitState::Profiling,
+        }
+    }
+}
+
+/// ⚡ Compiles hot, integer-only functions to native code with Cranelift
+///
+/// A function is compiled once it has been called `JIT_THRESHOLD` times with integer arguments
+/// only (monomorphic at runtime) and its body stays inside the compiled subset: integer and
+/// boolean arithmetic, comparisons, `if`/`else`, local assignments, `return` and calls to other
+/// such functions. Every native call is guarded by an argument type check and by a check that no
+/// global variable holding a function hides one of the functions it calls; calls that fail the
+/// guards, or that bail out at runtime (overflow, division by zero, no `return`), run in the
+/// interpreter instead, which is safe because compiled functions have no side effects.
+pub struct Jit {
+    module: Option<JITModule>,
+    ids: HashMap<String, FuncId>,
+    profiles: HashMap<String, FunctionProfile>,
+    compile_time: Duration,
+}
+
+impl Jit {
+    /// 🆕 Creates a JIT with no compiled functions; the Cranelift module is created lazily
+    pub fn new() -> Self {
+        Self {
+            module: None,
+            ids: HashMap::new(),
+            profiles: HashMap::new(),
+            compile_time: Duration::ZERO,
+        }
+    }
+
+    /// 📞 Runs `func` natively if it is (or just became) compiled and `args` pass the type guard.
+    /// Returns `None` when the call has to be executed by the interpreter, including when one
+    /// of the `globals` is a function hiding a function the native code would call.
+    pub fn try_call(
+        &mut self,
+        func: &Function,
+        args: &[Value],
+        functions: &HashMap<String, Rc<Function>>,
+        structs: &HashMap<String, Rc<StructType>>,
+        globals: &HashMap<String, Value>,
+    ) -> Option<Value> {
+        if !self.profiles.contains_key(&func.name) {
+            self.profiles
+                .insert(func.name.clone(), FunctionProfile::new());
+        }
+        let profile = self.profiles.get_mut(&func.name).unwrap();
+        profile.calls += 1;
+
+        let ints: Option<Vec<i64>> = args
+            .iter()
+            .map(|arg| match arg {
+                Value::Integer(i) => Some(*i),
+                _ => None,
+            })
+            .collect();
+
+        if let JitState::Profiling = profile.state {
+            if ints.is_none() {
+                profile.state = JitState::Rejected("called with non-integer arguments".to_string());
+                return None;
+            }
+            if profile.calls < JIT_THRESHOLD {
+                return None;
+            }
+            self.compile(&func.name, functions, structs);
+        }
+
+        let profile = self.profiles.get_mut(&func.name).unwrap();
+        let JitState::Compiled(entry, group) = &profile.state else {
+            return None;
+        };
+        let entry = *entry;
+        // The interpreter calls a global function value before a function with its name
+        let shadowed = group
+            .iter()
+            .any(|name| matches!(globals.get(name), Some(Value::Function(_))));
+        let Some(ints) = ints.filter(|_| !shadowed) else {
+            profile.guard_failures += 1;
+            return None;
+        };
+
+        let mut status = 0i64;
+        let result = entry(ints.as_ptr(), &mut status);
+        if status != 0 {
+            profile.deopts += 1;
+            return None;
+        }
+        profile.native_calls += 1;
+        Some(Value::Integer(result))
+    }
+
+    /// 🔨 Compiles `name` and every function it can reach, or records why it cannot be compiled
+    fn compile(
+        &mut self,
+        name: &str,
+        functions: &HashMap<String, Rc<Function>>,
+        structs: &HashMap<String, Rc<StructType>>,
+    ) {
+        let started = Instant::now();
+        let result = self.compile_group(name, functions, structs);
+        self.compile_time += started.elapsed();
+
+        match result {
+            Ok((entries, group)) => {
+                for (func_name, entry) in entries {
+                    let profile = self
+                        .profiles
+                        .entry(func_name)
+                        .or_insert_with(FunctionProfile::new);
+                    profile.state = JitState::Compiled(entry, Rc::clone(&group));
+                }
+            }
+            Err(reason) => {
+                self.profiles.get_mut(name).unwrap().state = JitState::Rejected(reason);
+            }
+        }
+    }
+
+    /// 🧩 Type-checks the call graph rooted at `name`, then lowers every new function to Cranelift IR
+    fn compile_group(
+        &mut self,
+        name: &str,
+        functions: &HashMap<String, Rc<Function>>,
+        structs: &HashMap<String, Rc<StructType>>,
+    ) -> Result<Group, String> {
+        // 1. Collect the functions reachable from `name` and make sure they all fit the subset
+        let mut group = vec![name.to_string()];
+        let mut i = 0;
+        while i < group.len() {
+            let func = &functions[&group[i]];
+            let mut checker = Checker {
+                functions,
+                structs,
+                callees: Vec::new(),
+                local_types: HashMap::new(),
+            };
+            checker
+                .check_function(func)
+                .map_err(|reason| format!("`{}` {}", func.name, reason))?;
+            for callee in checker.callees {
+                if !group.contains(&callee) {
+                    group.push(callee);
+                }
+            }
+            i += 1;
+        }
+        let reachable: Rc<[String]> = group.clone().into();
+        group.retain(|func_name| !self.ids.contains_key(func_name));
+
+        if self.module.is_none() {
+            self.module = Some(new_module()?);
+        }
+        let module = self.module.as_mut().unwrap();
+        let pointer_type = module.target_config().pointer_type();
+
+        // 2. Declare everything first so (mutually) recursive calls resolve
+        let mut entry_ids = Vec::new();
+        for func_name in &group {
+            let func = &functions[func_name];
+            let id = module
+                .declare_function(
+                    &format!("glint_{}", func_name),
+                    Linkage::Local,
+                    &body_signature(module, func.args.len(), pointer_type),
+                )
+                .map_err(|err| err.to_string())?;
+            self.ids.insert(func_name.clone(), id);
+
+            let mut entry_sig = module.make_signature();
+            entry_sig.params.push(AbiParam::new(pointer_type));
+            entry_sig.params.push(AbiParam::new(pointer_type));
+            entry_sig.returns.push(AbiParam::new(types::I64));
+            let entry_id = module
+                .declare_function(
+                    &format!("glint_entry_{}", func_name),
+                    Linkage::Local,
+                    &entry_sig,
+                )
+                .map_err(|err| err.to_string())?;
+            entry_ids.push((func_name.clone(), id, entry_id, entry_sig));
+        }
+
+        // 3. Define the bodies and their entry trampolines
+        let mut ctx = module.make_context();
+        let mut func_ctx = FunctionBuilderContext::new();
+        for (func_name, id, entry_id, entry_sig) in &entry_ids {
+            let func = &functions[func_name];
+
+            ctx.func.signature = body_signature(module, func.args.len(), pointer_type);
+            ctx.func.name = UserFuncName::user(0, id.as_u32());
+            Lowering::lower_function(module, &self.ids, &mut ctx, &mut func_ctx, func);
+            module
+                .define_function(*id, &mut ctx)
+                .map_err(|err| err.to_string())?;
+            module.clear_context(&mut ctx);
+
+            ctx.func.signature = entry_sig.clone();
+            ctx.func.name = UserFuncName::user(0, entry_id.as_u32());
+            lower_entry(module, &mut ctx, &mut func_ctx, *id, func.args.len());
+            module
+                .define_function(*entry_id, &mut ctx)
+                .map_err(|err| err.to_string())?;
+            module.clear_context(&mut ctx);
+        }
+        module
+            .finalize_definitions()
+            .map_err(|err| err.to_string())?;
+
+        let entries = entry_ids
+            .into_iter()
+            .map(|(func_name, _, entry_id, _)| {
+                let code = module.get_finalized_function(entry_id);
+                // SAFETY: the entry trampoline was built with exactly the `EntryFn` signature
+                (func_name, unsafe {
+                    mem::transmute::<*const u8, EntryFn>(code)
+                })
+            })
+            .collect();
+        Ok((entries, reachable))
+    }
+
+    /// 📊 Renders the `--jit-stats` report
+    pub fn report(&self) -> String {
+        let mut names: Vec<&String> = self.profiles.keys().collect();
+        names.sort();
+        let compiled = self
+            .profiles
+            .values()
+            .filter(|profile| matches!(profile.state, JitState::Compiled(..)))
+            .count();
+
+        let mut report = format!("{} JIT Stats {}\n", "<=> ".blue(), " <=>".blue());
+        report.push_str(&format!(
+            "{}: {} calls\n",
+            "Threshold".truecolor(41, 176, 255),
+            JIT_THRESHOLD
+        ));
+        report.push_str(&format!(
+            "{}: {} ({:.4} ms)\n",
+            "Compiled functions".truecolor(41, 176, 255),
+            compiled,
+            self.compile_time.as_secs_f64() * 1000.0
+        ));
+        report.push_str(&format!("{}:\n", "Functions".truecolor(0, 76, 120)));
+        for name in names {
+            let profile = &self.profiles[name];
+            let state = match &profile.state {
+                JitState::Profiling => "interpreted".to_string(),
+                JitState::Compiled(..) => "compiled".to_string(),
+                JitState::Rejected(reason) => format!("not compiled: {}", reason),
+            };
+            report.push_str(&format!(
+                "  └─ {}: calls {}, native {}, guard fallbacks {}, deopts {} [{}]\n",
+                name.truecolor(41, 176, 255),
+                profile.calls,
+                profile.native_calls,
+                profile.guard_failures,
+                profile.deopts,
+                state
+            ));
+        }
+        report.push_str(&format!(
+            "{} End JIT Stats {}",
+            "<=> ".blue(),
+            " <=>".blue()
+        ));
+        report
+    }
+}
+
+impl Default for Jit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 🏭 Creates a JIT module for the host machine
+fn new_module() -> Result<JITModule, String> {
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("use_colocated_libcalls", "false")
+        .map_err(|err| err.to_string())?;
+    flag_builder
+        .set("is_pic", "false")
+        .map_err(|err| err.to_string())?;
+    flag_builder
+        .set("opt_level", "speed")
+        .map_err(|err| err.to_string())?;
+    let isa = cranelift_native::builder()
+        .map_err(|msg| format!("host machine is not supported: {}", msg))?
+        .finish(settings::Flags::new(flag_builder))
+        .map_err(|err| err.to_string())?;
+    Ok(JITModule::new(JITBuilder::with_isa(
+        isa,
+        default_libcall_names(),
+    )))
+}
+
+/// ✍️ Signature of a compiled body: one i64 per argument plus the deopt status pointer
+fn body_signature(module: &JITModule, arity: usize, pointer_type: Type) -> Signature {
+    let mut sig = module.make_signature();
+    for _ in 0..arity {
+        sig.params.push(AbiParam::new(types::I64));
+    }
+    sig.params.push(AbiParam::new(pointer_type));
+    sig.returns.push(AbiParam::new(types::I64));
+    sig
+}
+
+/// 🚪 Builds the trampoline that unpacks the argument array and calls the compiled body
+fn lower_entry(
+    module: &mut JITModule,
+    ctx: &mut cranelift_codegen::Context,
+    func_ctx: &mut FunctionBuilderContext,
+    body_id: FuncId,
+    arity: usize,
+) {
+    let mut builder = FunctionBuilder::new(&mut ctx.func, func_ctx);
+    let block = builder.create_block();
+    builder.append_block_params_for_function_params(block);
+    builder.switch_to_block(block);
+
+    let (args_ptr, status) = (
+        builder.block_params(block)[0],
+        builder.block_params(block)[1],
+    );
+    let mut call_args = Vec::with_capacity(arity + 1);
+    for i in 0..arity {
+        let offset = (i * mem::size_of::<i64>()) as i32;
+        call_args.push(
+            builder
+                .ins()
+                .load(types::I64, MemFlags::trusted(), args_ptr, offset),
+        );
+    }
+    call_args.push(status);
+
+    let callee = module.declare_func_in_func(body_id, builder.func);
+    let call = builder.ins().call(callee, &call_args);
+    let result = builder.inst_results(call)[0];
+    builder.ins().return_(&[result]);
+    builder.seal_all_blocks();
+    builder.finalize();
+}
+
+/// 🔎 Checks that a function only uses what the compiler supports
+struct Checker<'a> {
+    functions: &'a HashMap<String, Rc<Function>>,
+    structs: &'a HashMap<String, Rc<StructType>>,
+    callees: Vec<String>,
+    local_types: HashMap<String, Ty>,
+}
+
+impl Checker<'_> {
+    fn check_function(&mut self, func: &Function) -> Result<(), String> {
+        let mut vars: HashMap<String, Ty> =
+            func.args.iter().map(|arg| (arg.clone(), Ty::Int)).collect();
+        self.local_types = vars.clone();
+        if !self.check_block(func.statements(), &mut vars)? {
+            // It would return null, which only the interpreter can represent
+            return Err("can finish without `return`".to_string());
+        }
+        Ok(())
+    }
+
+    /// 🧱 Checks a block and returns whether it always ends in a `return`;
+    /// `vars` ends up holding the variables definitely assigned after it
+    fn check_block(
+        &mut self,
+        statements: &[Json],
+        vars: &mut HashMap<String, Ty>,
+    ) -> Result<bool, String> {
+        for statement in statements {
+            match statement_kind(statement) {
+                Some(("Return", expr)) => {
+                    if self.check_expr(expr, vars)? != Ty::Int {
+                        return Err("returns a non-integer value".to_string());
+                    }
+                    return Ok(true);
+                }
+                Some(("VariableAssign", assign)) => {
+                    let name = assign["name"].as_str().unwrap();
+                    let ty = self.check_expr(&assign["value"], vars)?;
+                    // A local keeps one type in every branch, because it maps to one Cranelift variable
+                    if *self.local_types.entry(name.to_string()).or_insert(ty) != ty {
+                        return Err(format!("changes the type of `{}`", name));
+                    }
+                    vars.insert(name.to_string(), ty);
+                }
+                Some(("IfElse", if_else)) => {
+                    self.check_expr(&if_else["condition"], vars)?;
+                    let mut then_vars = vars.clone();
+                    let then_returns =
+                        self.check_block(block_statements(&if_else["if_block"]), &mut then_vars)?;
+                    let mut else_vars = vars.clone();
+                    let else_returns =
+                        self.check_block(block_statements(&if_else["else_block"]), &mut else_vars)?;
+                    match (then_returns, else_returns) {
+                        (true, true) => return Ok(true),
+                        (true, false) => *vars = else_vars,
+                        (false, true) => *vars = then_vars,
+                        (false, false) => {
+                            for (name, ty) in then_vars {
+                                if else_vars.contains_key(&name) {
+                                    vars.insert(name, ty);
+                                }
+                            }
+                        }
+                    }
+                }
+                Some(("FunctionCall", call)) => self.check_call(call, vars)?,
+                Some(("Write", _)) => return Err("performs I/O (`write`)".to_string()),
+                Some(("Comment", _)) => {}
+                Some((kind, _)) => return Err(format!("uses unsupported statement `{}`", kind)),
+                None => return Err("contains a malformed statement".to_string()),
+            }
+        }
+        Ok(false)
+    }
+
+    fn check_expr(&mut self, expr: &Json, vars: &HashMap<String, Ty>) -> Result<Ty, String> {
+        match node_kind(expr) {
+            Some(("Integer", _)) => Ok(Ty::Int),
+            Some(("Bool", _)) => Ok(Ty::Bool),
+            Some(("Identifier", id)) => {
+                let name = id.as_str().unwrap();
+                vars.get(name)
+                    .copied()
+                    .ok_or_else(|| format!("reads non-local variable `{}`", name))
+            }
+            Some(("BinaryOp", binary_op)) => {
+                let left = self.check_expr(&binary_op["left"], vars)?;
+                let right = self.check_expr(&binary_op["right"], vars)?;
+                match (binary_op["op"].as_str().unwrap(), left, right) {
+                    ("+" | "-" | "*" | "/", Ty::Int, Ty::Int) => Ok(Ty::Int),
+                    ("<" | "<=" | ">" | ">=", Ty::Int, Ty::Int) => Ok(Ty::Bool),
+                    ("=" | "!=", l, r) if l == r => Ok(Ty::Bool),
+                    (op, l, r) => Err(format!("applies `{}` to {:?} and {:?}", op, l, r)),
+                }
+            }
+            Some(("FunctionCall", call)) => {
+                self.check_call(call, vars)?;
+                Ok(Ty::Int)
+            }
+            Some((kind, _)) => Err(format!("uses {} values", kind)),
+            None => Err("contains a malformed expression".to_string()),
+        }
+    }
+
+    fn check_call(&mut self, call: &Json, vars: &HashMap<String, Ty>) -> Result<(), String> {
+        let name = call["name"].as_str().unwrap();
+        // The interpreter calls the function a variable holds before a function with its name
+        if vars.contains_key(name) {
+            return Err(format!("calls the variable `{}`", name));
+        }
+        // ...and builds a struct before calling a function with its name
+        if self.structs.contains_key(name) {
+            return Err(format!("constructs the struct `{}`", name));
+        }
+        let callee = self
+            .functions
+            .get(name)
+            .ok_or_else(|| format!("calls unknown function `{}`", name))?;
+        let args = call["args"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+        if args.len() != callee.args.len() {
+            return Err(format!(
+                "calls `{}` with the wrong number of arguments",
+                name
+            ));
+        }
+        for arg in args {
+            if self.check_expr(arg, vars)? != Ty::Int {
+                return Err(format!("passes a non-integer argument to `{}`", name));
+            }
+        }
+        if !self.callees.iter().any(|callee| callee == name) {
+            self.callees.push(name.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 📋 Returns the statements of an optional `Block` node
+fn block_statements(block: &Json) -> &[Json] {
+    block["Block"].as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// 🏗️ Translates a checked function body to Cranelift IR
+struct Lowering<'a, 'b> {
+    builder: FunctionBuilder<'b>,
+    module: &'a mut JITModule,
+    ids: &'a HashMap<String, FuncId>,
+    func_refs: HashMap<String, FuncRef>,
+    vars: HashMap<String, (Variable, Ty)>,
+    status: IrValue,
+    deopt_block: Block,
+}
+
+impl<'a, 'b> Lowering<'a, 'b> {
+    fn lower_function(
+        module: &'a mut JITModule,
+        ids: &'a HashMap<String, FuncId>,
+        ctx: &'b mut cranelift_codegen::Context,
+        func_ctx: &'b mut FunctionBuilderContext,
+        func: &Function,
+    ) {
+        let mut builder = FunctionBuilder::new(&mut ctx.func, func_ctx);
+        let entry = builder.create_block();
+        builder.append_block_params_for_function_params(entry);
+        let deopt_block = builder.create_block();
+        builder.switch_to_block(entry);
+
+        let params = builder.block_params(entry).to_vec();
+        let status = params[func.args.len()];
+        let mut lowering = Lowering {
+            builder,
+            module,
+            ids,
+            func_refs: HashMap::new(),
+            vars: HashMap::new(),
+            status,
+            deopt_block,
+        };
+        for (arg, param) in func.args.iter().zip(params) {
+            lowering.define(arg, Ty::Int, param);
+        }
+
+        // Falling off the end returns null in the interpreter, so let it handle that call
+        if !lowering.lower_block(func.statements()) {
+            lowering.builder.ins().jump(deopt_block, &[]);
+        }
+
+        // Deopt: flag the status word and unwind to the entry trampoline
+        lowering.builder.switch_to_block(deopt_block);
+        let one = lowering.builder.ins().iconst(types::I64, 1);
+        lowering
+            .builder
+            .ins()
+            .store(MemFlags::trusted(), one, status, 0);
+        let zero = lowering.builder.ins().iconst(types::I64, 0);
+        lowering.builder.ins().return_(&[zero]);
+
+        lowering.builder.seal_all_blocks();
+        lowering.builder.finalize();
+    }
+
+    /// 📝 Assigns a local, declaring the Cranelift variable on first use
+    fn define(&mut self, name: &str, ty: Ty, value: IrValue) {
+        let next_index = self.vars.len() as u32;
+        let (var, _) = *self.vars.entry(name.to_string()).or_insert_with(|| {
+            let var = Variable::from_u32(next_index);
+            self.builder.declare_var(var, ir_type(ty));
+            (var, ty)
+        });
+        self.builder.def_var(var, value);
+    }
+
+    /// 🧱 Lowers statements; returns true if the block always ends in a `return`
+    fn lower_block(&mut self, statements: &[Json]) -> bool {
+        for statement in statements {
+            match statement_kind(statement) {
+                Some(("Return", expr)) => {
+                    let (value, _) = self.lower_expr(expr);
+                    self.builder.ins().return_(&[value]);
+                    // Anything after the return is dead code
+                    return true;
+                }
+                Some(("VariableAssign", assign)) => {
+                    let (value, ty) = self.lower_expr(&assign["value"]);
+                    self.define(assign["name"].as_str().unwrap(), ty, value);
+                }
+                Some(("IfElse", if_else)) => {
+                    let condition = self.lower_condition(&if_else["condition"]);
+                    let then_block = self.builder.create_block();
+                    let else_block = self.builder.create_block();
+                    let merge_block = self.builder.create_block();
+                    self.builder
+                        .ins()
+                        .brif(condition, then_block, &[], else_block, &[]);
+
+                    self.builder.switch_to_block(then_block);
+                    let then_returns = self.lower_block(block_statements(&if_else["if_block"]));
+                    if !then_returns {
+                        self.builder.ins().jump(merge_block, &[]);
+                    }
+
+                    self.builder.switch_to_block(else_block);
+                    let else_returns = self.lower_block(block_statements(&if_else["else_block"]));
+                    if !else_returns {
+                        self.builder.ins().jump(merge_block, &[]);
+                    }
+
+                    self.builder.switch_to_block(merge_block);
+                    if then_returns && else_returns {
+                        return true;
+                    }
+                }
+                Some(("FunctionCall", call)) => {
+                    self.lower_call(call);
+                }
+                Some(("Comment", _)) => {}
+                _ => unreachable!("statement rejected by the checker"),
+            }
+        }
+        false
+    }
+
+    /// ⚖️ Lowers a condition to an i8 truth value (integers are true when non-zero)
+    fn lower_condition(&mut self, expr: &Json) -> IrValue {
+        match self.lower_expr(expr) {
+            (value, Ty::Bool) => value,
+            (value, Ty::Int) => self.builder.ins().icmp_imm(IntCC::NotEqual, value, 0),
+        }
+    }
+
+    fn lower_expr(&mut self, expr: &Json) -> (IrValue, Ty) {
+        match node_kind(expr) {
+            Some(("Integer", n)) => (
+                self.builder.ins().iconst(types::I64, n.as_i64().unwrap()),
+                Ty::Int,
+            ),
+            Some(("Bool", b)) => (
+                self.builder
+                    .ins()
+                    .iconst(types::I8, b.as_bool().unwrap() as i64),
+                Ty::Bool,
+            ),
+            Some(("Identifier", id)) => {
+                let (var, ty) = self.vars[id.as_str().unwrap()];
+                (self.builder.use_var(var), ty)
+            }
+            Some(("BinaryOp", binary_op)) => {
+                let (left, _) = self.lower_expr(&binary_op["left"]);
+                let (right, _) = self.lower_expr(&binary_op["right"]);
+                self.lower_binary_op(binary_op["op"].as_str().unwrap(), left, right)
+            }
+            Some(("FunctionCall", call)) => (self.lower_call(call), Ty::Int),
+            _ => unreachable!("expression rejected by the checker"),
+        }
+    }
+
+    /// ➕ Lowers an operator; arithmetic bails out on overflow and division by zero like the interpreter
+    fn lower_binary_op(&mut self, op: &str, left: IrValue, right: IrValue) -> (IrValue, Ty) {
+        let cc = match op {
+            "+" | "-" | "*" => {
+                let (value, overflow) = match op {
+                    "+" => self.builder.ins().sadd_overflow(left, right),
+                    "-" => self.builder.ins().ssub_overflow(left, right),
+                    _ => self.builder.ins().smul_overflow(left, right),
+                };
+                self.deopt_if(overflow);
+                return (value, Ty::Int);
+            }
+            "/" => {
+                let is_zero = self.builder.ins().icmp_imm(IntCC::Equal, right, 0);
+                self.deopt_if(is_zero);
+                let is_min = self.builder.ins().icmp_imm(IntCC::Equal, left, i64::MIN);
+                let is_minus_one = self.builder.ins().icmp_imm(IntCC::Equal, right, -1);
+                let overflow = self.builder.ins().band(is_min, is_minus_one);
+                self.deopt_if(overflow);
+                return (self.builder.ins().sdiv(left, right), Ty::Int);
+            }
+            "=" => IntCC::Equal,
+            "!=" => IntCC::NotEqual,
+            "<" => IntCC::SignedLessThan,
+            "<=" => IntCC::SignedLessThanOrEqual,
+            ">" => IntCC::SignedGreaterThan,
+            ">=" => IntCC::SignedGreaterThanOrEqual,
+            _ => unreachable!("operator rejected by the checker"),
+        };
+        (self.builder.ins().icmp(cc, left, right), Ty::Bool)
+    }
+
+    /// 📞 Calls another compiled function and propagates its deopt status
+    fn lower_call(&mut self, call: &Json) -> IrValue {
+        let name = call["name"].as_str().unwrap();
+        let mut args: Vec<IrValue> = call["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|arg| self.lower_expr(arg).0)
+            .collect();
+        args.push(self.status);
+
+        let func_ref = match self.func_refs.get(name) {
+            Some(func_ref) => *func_ref,
+            None => {
+                let func_ref = self
+                    .module
+                    .declare_func_in_func(self.ids[name], self.builder.func);
+                self.func_refs.insert(name.to_string(), func_ref);
+                func_ref
+            }
+        };
+        let call = self.builder.ins().call(func_ref, &args);
+        let result = self.builder.inst_results(call)[0];
+
+        let status = self
+            .builder
+            .ins()
+            .load(types::I64, MemFlags::trusted(), self.status, 0);
+        let failed = self.builder.ins().icmp_imm(IntCC::NotEqual, status, 0);
+        self.deopt_if(failed);
+        result
+    }
+
+    /// 🛑 Branches to the deopt block when `condition` is true
+    fn deopt_if(&mut self, condition: IrValue) {
+        let continue_block = self.builder.create_block();
+        self.builder
+            .ins()
+            .brif(condition, self.deopt_block, &[], continue_block, &[]);
+        self.builder.switch_to_block(continue_block);
+    }
+}
+
+/// 🔠 Maps a subset type to its Cranelift representation
+fn ir_type(ty: Ty) -> Type {
+    match ty {
+        Ty::Int => types::I64,
+        Ty::Bool => types::I8,
+    }
+}
@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+
+use crate::error::RuntimeError;
+use crate::interpreter::builtins::{builtin_arity, call_builtin, Callback, BUILTINS};
+use crate::interpreter::value::{position, Value};
+
+/// 🧰 The methods values of the builtin types have (`"abc".upper()`, `arr.push(1)`); the
+/// collection builtins are methods of arrays too (`arr.map(f)` is `map(arr, f)`)
+pub const METHODS: &[(&str, &[&str])] = &[
+    (
+        "string",
+        &[
+            "len",
+            "upper",
+            "lower",
+            "trim",
+            "split",
+            "contains",
+            "starts_with",
+            "ends_with",
+            "replace",
+        ],
+    ),
+    (
+        "array",
+        &[
+            "len",
+            "push",
+            "pop",
+            "insert",
+            "remove",
+            "contains",
+            "index_of",
+            "join",
+            "reverse",
+            "sort",
+            "map",
+            "filter",
+            "reduce",
+            "any",
+            "all",
+            "find",
+            "sort_by",
+            "zip",
+            "enumerate",
+        ],
+    ),
+    ("tuple", &["len", "contains"]),
+    (
+        "dictionary",
+        &["len", "keys", "values", "has", "get", "remove"],
+    ),
+];
+
+/// ✏️ The methods that change the value they are called on rather than returning a new one
+pub const MUTATING: &[&str] = &["push", "pop", "insert", "remove"];
+
+/// 📞 Calls the builtin method `name` on `receiver`, running function arguments with
+/// `callback`; returns `None` if values of the receiver's type have no such method
+pub fn call_method(
+    receiver: &mut Value,
+    name: &str,
+    args: &[Value],
+    callback: &mut Callback,
+) -> Option<Result<Value, RuntimeError>> {
+    let type_name = receiver.type_name();
+    let (min, max) = method_arity(type_name, name)?;
+    if args.len() < min || args.len() > max {
+        return Some(Err(RuntimeError::ArityMismatch {
+            name: format!("{}.{}", type_name, name),
+            expected: if args.len() < min { min } else { max },
+            found: args.len(),
+        }));
+    }
+    if BUILTINS.contains(&name) {
+        let args: Vec<Value> = std::iter::once(receiver.clone())
+            .chain(args.iter().cloned())
+            .collect();
+        return call_builtin(name, &args, callback);
+    }
+
+    let result = match (receiver, name, args) {
+        (Value::String(s), "len", []) => Ok(Value::Integer(s.chars().count() as i64)),
+        (Value::String(s), "upper", []) => Ok(Value::String(s.to_uppercase())),
+        (Value::String(s), "lower", []) => Ok(Value::String(s.to_lowercase())),
+        (Value::String(s), "trim", []) => Ok(Value::String(s.trim().to_string())),
+        (Value::String(s), "split", [Value::String(separator)]) => Ok(Value::Array(
+            s.split(separator.as_str())
+                .map(|part| Value::String(part.to_string()))
+                .collect(),
+        )),
+        (Value::String(s), "contains", [Value::String(part)]) => {
+            Ok(Value::Bool(s.contains(part.as_str())))
+        }
+        (Value::String(s), "starts_with", [Value::String(prefix)]) => {
+            Ok(Value::Bool(s.starts_with(prefix.as_str())))
+        }
+        (Value::String(s), "ends_with", [Value::String(suffix)]) => {
+            Ok(Value::Bool(s.ends_with(suffix.as_str())))
+        }
+        (Value::String(s), "replace", [Value::String(from), Value::String(to)]) => {
+            Ok(Value::String(s.replace(from.as_str(), to)))
+        }
+        (Value::Array(items) | Value::Tuple(items), "len", []) => {
+            Ok(Value::Integer(items.len() as i64))
+        }
+        (Value::Array(items), "push", [item]) => {
+            items.push(item.clone());
+            Ok(Value::Null)
+        }
+        (Value::Array(items), "pop", []) => items.pop().ok_or(RuntimeError::EmptyArray {
+            method: name.to_string(),
+        }),
+        (Value::Array(items), "insert", [index, item]) => {
+            // Inserting at the length appends
+            let at = match index {
+                Value::Integer(i) if *i == items.len() as i64 => Ok(items.len()),
+                index => position("array", index, items.len()),
+            };
+            at.map(|at| {
+                items.insert(at, item.clone());
+                Value::Null
+            })
+        }
+        (Value::Array(items), "remove", [index]) => {
+            position("array", index, items.len()).map(|at| items.remove(at))
+        }
+        (Value::Array(items) | Value::Tuple(items), "contains", [item]) => {
+            Ok(Value::Bool(items.contains(item)))
+        }
+        (Value::Array(items), "index_of", [item]) => Ok(items
+            .iter()
+            .position(|candidate| candidate == item)
+            .map_or(Value::Null, |i| Value::Integer(i as i64))),
+        (Value::Array(items), "join", [Value::String(separator)]) => Ok(Value::String(
+            items
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(separator),
+        )),
+        (Value::Array(items), "reverse", []) => {
+            Ok(Value::Array(items.iter().rev().cloned().collect()))
+        }
+        (Value::Array(items), "sort", []) => sorted(items),
+        (Value::Dictionary(pairs), "len", []) => Ok(Value::Integer(pairs.len() as i64)),
+        (Value::Dictionary(pairs), "keys", []) => Ok(Value::Array(
+            pairs.iter().map(|(key, _)| key.clone()).collect(),
+        )),
+        (Value::Dictionary(pairs), "values", []) => Ok(Value::Array(
+            pairs.iter().map(|(_, value)| value.clone()).collect(),
+        )),
+        (Value::Dictionary(pairs), "has", [key]) => Ok(Value::Bool(
+            pairs.iter().any(|(existing, _)| existing == key),
+        )),
+        (Value::Dictionary(pairs), "get", [key, default @ ..]) => Ok(pairs
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.clone())
+            .or_else(|| default.first().cloned())
+            .unwrap_or(Value::Null)),
+        (Value::Dictionary(pairs), "remove", [key]) => {
+            match pairs.iter().position(|(existing, _)| existing == key) {
+                Some(found) => Ok(pairs.remove(found).1),
+                None => Err(RuntimeError::KeyNotFound { key: key.repr() }),
+            }
+        }
+        (_, name, args) => Err(RuntimeError::TypeError {
+            message: format!(
+                "{}.{}() cannot take ({})",
+                type_name,
+                name,
+                args.iter()
+                    .map(Value::type_name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }),
+    };
+    Some(result)
+}
+
+/// 🔢 The smallest and largest number of arguments (besides the receiver) a builtin method
+/// takes, or `None` if values of type `type_name` have no method `name`
+pub fn method_arity(type_name: &str, name: &str) -> Option<(usize, usize)> {
+    let (_, methods) = METHODS.iter().find(|(owner, _)| *owner == type_name)?;
+    if !methods.contains(&name) {
+        return None;
+    }
+    if BUILTINS.contains(&name) {
+        let (min, max) = builtin_arity(name)?;
+        return Some((min - 1, max - 1));
+    }
+    match name {
+        "len" | "upper" | "lower" | "trim" | "pop" | "reverse" | "sort" | "keys" | "values" => {
+            Some((0, 0))
+        }
+        "replace" | "insert" => Some((2, 2)),
+        "get" => Some((1, 2)),
+        _ => Some((1, 1)),
+    }
+}
+
+/// 🗂️ A sorted copy of the items: numbers or strings, in ascending order
+fn sorted(items: &[Value]) -> Result<Value, RuntimeError> {
+    let mut error = None;
+    let mut items = items.to_vec();
+    items.sort_by(|a, b| {
+        a.sort_order(b).unwrap_or_else(|| {
+            error.get_or_insert_with(|| RuntimeError::TypeError {
+                message: format!(
+                    "sort() cannot order a {} and a {}",
+                    a.type_name(),
+                    b.type_name()
+                ),
+            });
+            Ordering::Equal
+        })
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(Value::Array(items)),
+    }
+}
@@ -1,4 +1,11 @@
+#[allow(clippy::module_inception)]
 pub mod interpreter;
 pub mod function;
 pub mod value;
+pub mod structs;
 pub mod conoperators;
+pub mod builtins;
+pub mod methods;
+pub mod hook;
+#[cfg(feature = "jit")]
+pub mod jit;
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use serde_json::Value as Json;
+
+use crate::error::RuntimeError;
+use crate::interpreter::value::Value;
+
+// 🏗️ A type declared with `struct`: its name and its fields in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructType {
+    // The struct's name (qualified with its module's namespace when it comes from a module)
+    pub name: String,
+    // Each field with the type its values must have, if the declaration names one
+    pub fields: Vec<(String, Option<String>)>,
+}
+
+impl StructType {
+    /// 📦 Reads a struct type from the JSON of a `Struct` node
+    pub fn from_json(json: &Json) -> Option<Self> {
+        let name = json.get("name")?.as_str()?.to_string();
+        let fields = json
+            .get("fields")?
+            .as_array()?
+            .iter()
+            .map(|field| {
+                let field = field.as_array()?;
+                let name = field.first()?.as_str()?.to_string();
+                let ty = field.get(1)?.as_str().map(str::to_string);
+                Some((name, ty))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(StructType { name, fields })
+    }
+
+    /// 🔢 The position of a field among the struct's fields
+    pub fn position(&self, field: &str) -> Result<usize, RuntimeError> {
+        self.fields
+            .iter()
+            .position(|(name, _)| name == field)
+            .ok_or_else(|| RuntimeError::UnknownField {
+                name: self.name.clone(),
+                field: field.to_string(),
+            })
+    }
+
+    /// 🏷️ Checks a value against the type declared for the field at `position`
+    pub fn check(&self, position: usize, value: &Value) -> Result<(), RuntimeError> {
+        let struct_name = match value {
+            Value::Struct(ty, _) => Some(ty.name.as_str()),
+            _ => None,
+        };
+        self.check_type(position, value.type_name(), struct_name)
+    }
+
+    /// 🏷️ Checks the type of a value (and the name of its struct, for a struct) against the
+    /// type declared for the field at `position`
+    pub fn check_type(
+        &self,
+        position: usize,
+        type_name: &str,
+        struct_name: Option<&str>,
+    ) -> Result<(), RuntimeError> {
+        let (field, ty) = &self.fields[position];
+        match ty {
+            Some(ty) if ty != type_name && Some(ty.as_str()) != struct_name => {
+                Err(RuntimeError::TypeError {
+                    message: format!(
+                        "{}.{} must be of type {}, got {}",
+                        self.name,
+                        field,
+                        ty,
+                        struct_name.unwrap_or(type_name)
+                    ),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 🕳️ The error for a construction that leaves out a field
+    pub fn missing(&self, field: &str) -> RuntimeError {
+        RuntimeError::TypeError {
+            message: format!("{}() is missing the field '{}'", self.name, field),
+        }
+    }
+
+    /// 🔁 The error for a construction that gives a field twice
+    pub fn repeated(&self, field: &str) -> RuntimeError {
+        RuntimeError::TypeError {
+            message: format!("{}() was given the field '{}' twice", self.name, field),
+        }
+    }
+
+    /// 🆕 Builds a value from its fields in declaration order (`Point(1, 2)`)
+    pub fn build(self: &Rc<Self>, values: Vec<Value>) -> Result<Value, RuntimeError> {
+        if values.len() != self.fields.len() {
+            return Err(RuntimeError::ArityMismatch {
+                name: self.name.clone(),
+                expected: self.fields.len(),
+                found: values.len(),
+            });
+        }
+        for (position, value) in values.iter().enumerate() {
+            self.check(position, value)?;
+        }
+        Ok(Value::Struct(Rc::clone(self), values))
+    }
+
+    /// 🆕 Builds a value from named fields (`Point(x: 1, y: 2)`), each given exactly once
+    pub fn construct(self: &Rc<Self>, named: Vec<(String, Value)>) -> Result<Value, RuntimeError> {
+        let mut values = vec![None; self.fields.len()];
+        for (field, value) in named {
+            let position = self.position(&field)?;
+            if values[position].is_some() {
+                return Err(self.repeated(&field));
+            }
+            values[position] = Some(value);
+        }
+        let values = values
+            .into_iter()
+            .zip(&self.fields)
+            .map(|(value, (field, _))| value.ok_or_else(|| self.missing(field)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.build(values)
+    }
+}
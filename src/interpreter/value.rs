@@ -1 +1,422 @@
-// later...
\ No newline at end of file
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::error::RuntimeError;
+use crate::interpreter::function::{Closure, ANONYMOUS};
+use crate::interpreter::structs::StructType;
+
+// 💎 A runtime value produced by evaluating an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    // 🔢 An integer value
+    Integer(i64),
+
+    // 🔣 A floating-point value
+    Float(f64),
+
+    // ✅ A boolean value
+    Bool(bool),
+
+    // 📝 A string value
+    String(String),
+
+    // 📚 An array of values
+    Array(Vec<Value>),
+
+    // 📖 A dictionary (key-value pairs, in insertion order)
+    Dictionary(Vec<(Value, Value)>),
+
+    // 🎭 A tuple of values
+    Tuple(Vec<Value>),
+
+    // 🎁 A function (`fn(x) { ... }`, `x => x * 2`, or a named function used as a value)
+    Function(Rc<Closure>),
+
+    // 🏗️ A value of a `struct` type, with its fields in declaration order
+    Struct(Rc<StructType>, Vec<Value>),
+
+    // 🕳️ The absence of a value (e.g. a function without `return`)
+    Null,
+}
+
+impl Value {
+    /// 🏷️ Returns the name of the value's type, used in error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Integer(_) => "int",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Dictionary(_) => "dictionary",
+            Value::Tuple(_) => "tuple",
+            Value::Function(_) => "function",
+            Value::Struct(..) => "struct",
+            Value::Null => "null",
+        }
+    }
+
+    /// 🔎 Formats a value for display in the REPL and inside collections (strings get quotes)
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(s) => format!("\"{}\"", s),
+            other => other.to_string(),
+        }
+    }
+
+    /// ⚖️ Decides whether a value counts as true in a condition
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Integer(i) => *i != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::Bool(b) => *b,
+            Value::String(s) => !s.is_empty(),
+            Value::Array(items) | Value::Tuple(items) => !items.is_empty(),
+            Value::Dictionary(pairs) => !pairs.is_empty(),
+            Value::Function(_) | Value::Struct(..) => true,
+            Value::Null => false,
+        }
+    }
+
+    /// ➕ Applies a binary operator (arithmetic or comparison) to two values
+    pub fn binary_op(&self, op: &str, right: &Value) -> Result<Value, RuntimeError> {
+        match op {
+            "=" => return Ok(Value::Bool(self == right)),
+            "!=" => return Ok(Value::Bool(self != right)),
+            "<" | "<=" | ">" | ">=" => return self.compare(op, right),
+            _ => {}
+        }
+
+        match (self, right) {
+            (Value::Integer(l), Value::Integer(r)) => {
+                let result = match op {
+                    "+" => l.checked_add(*r),
+                    "-" => l.checked_sub(*r),
+                    "*" => l.checked_mul(*r),
+                    "/" => {
+                        if *r == 0 {
+                            return Err(RuntimeError::DivisionByZero);
+                        }
+                        l.checked_div(*r)
+                    }
+                    _ => return Err(RuntimeError::UnknownOperator { op: op.to_string() }),
+                };
+                result
+                    .map(Value::Integer)
+                    .ok_or_else(|| RuntimeError::Overflow { op: op.to_string() })
+            }
+            (Value::Integer(_), Value::Float(_))
+            | (Value::Float(_), Value::Integer(_))
+            | (Value::Float(_), Value::Float(_)) => {
+                let (l, r) = (self.as_f64().unwrap(), right.as_f64().unwrap());
+                match op {
+                    "+" => Ok(Value::Float(l + r)),
+                    "-" => Ok(Value::Float(l - r)),
+                    "*" => Ok(Value::Float(l * r)),
+                    "/" => {
+                        if r == 0.0 {
+                            Err(RuntimeError::DivisionByZero)
+                        } else {
+                            Ok(Value::Float(l / r))
+                        }
+                    }
+                    _ => Err(RuntimeError::UnknownOperator { op: op.to_string() }),
+                }
+            }
+            (Value::String(l), _) if op == "+" => Ok(Value::String(format!("{}{}", l, right))),
+            (_, Value::String(r)) if op == "+" => Ok(Value::String(format!("{}{}", self, r))),
+            (Value::Array(l), Value::Array(r)) if op == "+" => {
+                Ok(Value::Array(l.iter().chain(r.iter()).cloned().collect()))
+            }
+            _ => Err(RuntimeError::TypeError {
+                message: format!(
+                    "unsupported operand types for '{}': {} and {}",
+                    op,
+                    self.type_name(),
+                    right.type_name()
+                ),
+            }),
+        }
+    }
+
+    /// 📏 Evaluates an ordering comparison between two values
+    fn compare(&self, op: &str, right: &Value) -> Result<Value, RuntimeError> {
+        let ordering = match (self, right) {
+            (Value::Integer(l), Value::Integer(r)) => l.partial_cmp(r),
+            (Value::String(l), Value::String(r)) => l.partial_cmp(r),
+            _ => match (self.as_f64(), right.as_f64()) {
+                (Some(l), Some(r)) => l.partial_cmp(&r),
+                _ => {
+                    return Err(RuntimeError::TypeError {
+                        message: format!(
+                            "cannot compare {} and {} with '{}'",
+                            self.type_name(),
+                            right.type_name(),
+                            op
+                        ),
+                    })
+                }
+            },
+        };
+
+        let Some(ordering) = ordering else {
+            return Ok(Value::Bool(false));
+        };
+        Ok(Value::Bool(match op {
+            "<" => ordering.is_lt(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        }))
+    }
+
+    /// 🔍 The element at an int index (`arr[-1]` counts from the end) or the value of a dictionary key
+    pub fn index(&self, index: &Value) -> Result<Value, RuntimeError> {
+        match self {
+            Value::Array(items) | Value::Tuple(items) => {
+                Ok(items[position(self.type_name(), index, items.len())?].clone())
+            }
+            Value::String(s) => {
+                let chars: Vec<char> = s.chars().collect();
+                Ok(Value::String(
+                    chars[position(self.type_name(), index, chars.len())?].to_string(),
+                ))
+            }
+            Value::Dictionary(pairs) => pairs
+                .iter()
+                .find(|(key, _)| key == index)
+                .map(|(_, value)| value.clone())
+                .ok_or_else(|| RuntimeError::KeyNotFound { key: index.repr() }),
+            other => Err(RuntimeError::TypeError {
+                message: format!("cannot index a value of type {}", other.type_name()),
+            }),
+        }
+    }
+
+    /// 🏷️ The value of a struct's field (`point.x`) or of a dictionary's string key
+    pub fn field(&self, name: &str) -> Result<Value, RuntimeError> {
+        match self {
+            Value::Struct(ty, values) => Ok(values[ty.position(name)?].clone()),
+            Value::Dictionary(_) => self.index(&Value::String(name.to_string())),
+            other => Err(RuntimeError::TypeError {
+                message: format!(
+                    "cannot read the field '{}' of a value of type {}",
+                    name,
+                    other.type_name()
+                ),
+            }),
+        }
+    }
+
+    /// 👣 The element or field one step inside the value
+    pub fn get(&self, step: &Step) -> Result<Value, RuntimeError> {
+        match step {
+            Step::Index(index) => self.index(index),
+            Step::Field(name) => self.field(name),
+        }
+    }
+
+    /// ✂️ The elements of an array or string from `start` up to (not including) `end`; negative
+    /// bounds count from the end and bounds past either end are clamped
+    pub fn slice(&self, start: Option<&Value>, end: Option<&Value>) -> Result<Value, RuntimeError> {
+        match self {
+            Value::Array(items) => Ok(Value::Array(
+                items[bounds(start, end, items.len())?].to_vec(),
+            )),
+            Value::String(s) => {
+                let chars: Vec<char> = s.chars().collect();
+                Ok(Value::String(
+                    chars[bounds(start, end, chars.len())?].iter().collect(),
+                ))
+            }
+            other => Err(RuntimeError::TypeError {
+                message: format!("cannot slice a value of type {}", other.type_name()),
+            }),
+        }
+    }
+
+    /// ✏️ Replaces the element reached through `path` (`grid[1][0] is 5` has the path `[1, 0]`);
+    /// the last step may add a new key to a dictionary, and a struct's field keeps its type
+    pub fn set_path(&mut self, path: &[Step], value: Value) -> Result<(), RuntimeError> {
+        let Some((step, rest)) = path.split_first() else {
+            *self = value;
+            return Ok(());
+        };
+        let type_name = self.type_name();
+        let index = match step {
+            Step::Index(index) => index.clone(),
+            Step::Field(name) => {
+                if let Value::Struct(ty, values) = self {
+                    let position = ty.position(name)?;
+                    if rest.is_empty() {
+                        ty.check(position, &value)?;
+                    }
+                    return values[position].set_path(rest, value);
+                }
+                Value::String(name.clone())
+            }
+        };
+        let element = match self {
+            Value::Array(items) => {
+                let position = position(type_name, &index, items.len())?;
+                &mut items[position]
+            }
+            Value::Dictionary(pairs) => match pairs.iter().position(|(key, _)| *key == index) {
+                Some(found) => &mut pairs[found].1,
+                None if rest.is_empty() => {
+                    pairs.push((index, value));
+                    return Ok(());
+                }
+                None => return Err(RuntimeError::KeyNotFound { key: index.repr() }),
+            },
+            _ => {
+                return Err(RuntimeError::TypeError {
+                    message: format!("cannot assign to an element of a {}", type_name),
+                })
+            }
+        };
+        element.set_path(rest, value)
+    }
+
+    /// 🗂️ Orders two numbers or two strings for sorting (NaN sorts after every other number);
+    /// `None` if the values cannot be ordered
+    pub fn sort_order(&self, right: &Value) -> Option<Ordering> {
+        match (self, right) {
+            (Value::Integer(l), Value::Integer(r)) => Some(l.cmp(r)),
+            (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+            _ => Some(self.as_f64()?.total_cmp(&right.as_f64()?)),
+        }
+    }
+
+    /// 🔣 Converts numeric values to f64
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+}
+
+// 👣 One step of the path from a variable to the element or field an assignment changes
+#[derive(Debug, Clone)]
+pub enum Step {
+    // 🔍 `[index]`: an element of an array or the value of a dictionary key
+    Index(Value),
+
+    // 🏷️ `.name`: a field of a struct or a string key of a dictionary
+    Field(String),
+}
+
+/// 🔢 The position an int index refers to among the `len` elements of a `type_name` value
+pub fn position(type_name: &str, index: &Value, len: usize) -> Result<usize, RuntimeError> {
+    let Value::Integer(index) = index else {
+        return Err(RuntimeError::TypeError {
+            message: format!(
+                "{} index must be an int, got {}",
+                type_name,
+                index.type_name()
+            ),
+        });
+    };
+    let position = if *index < 0 {
+        *index + len as i64
+    } else {
+        *index
+    };
+    if (0..len as i64).contains(&position) {
+        Ok(position as usize)
+    } else {
+        Err(RuntimeError::IndexOutOfRange { index: *index, len })
+    }
+}
+
+/// 📐 The positions `start:end` selects among `len` elements
+fn bounds(
+    start: Option<&Value>,
+    end: Option<&Value>,
+    len: usize,
+) -> Result<Range<usize>, RuntimeError> {
+    let bound = |bound: Option<&Value>, default: usize| match bound {
+        None => Ok(default),
+        Some(Value::Integer(i)) => {
+            let i = if *i < 0 { *i + len as i64 } else { *i };
+            Ok(i.clamp(0, len as i64) as usize)
+        }
+        Some(other) => Err(RuntimeError::TypeError {
+            message: format!("slice bounds must be ints, got {}", other.type_name()),
+        }),
+    };
+    let start = bound(start, 0)?;
+    let end = bound(end, len)?;
+    Ok(start..end.max(start))
+}
+
+// 🖨️ Formats a value the way `write` prints it
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                write_list(f, items)?;
+                write!(f, "]")
+            }
+            Value::Tuple(items) => {
+                write!(f, "(")?;
+                write_list(f, items)?;
+                // Like the literal, a tuple with one item has a trailing comma
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Value::Dictionary(pairs) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", key)?;
+                    write_nested(f, value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Struct(ty, values) => {
+                write!(f, "{}(", ty.name)?;
+                for (i, ((field, _), value)) in ty.fields.iter().zip(values).enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", field)?;
+                    write_nested(f, value)?;
+                }
+                write!(f, ")")
+            }
+            Value::Function(closure) if closure.function.name == ANONYMOUS => write!(f, "<fn>"),
+            Value::Function(closure) => write!(f, "<fn {}>", closure.function.name),
+            Value::Null => write!(f, "null"),
+        }
+    }
+}
+
+/// 🧵 Writes comma-separated items, quoting nested strings
+fn write_list(f: &mut fmt::Formatter<'_>, items: &[Value]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write_nested(f, item)?;
+    }
+    Ok(())
+}
+
+/// 🧵 Writes a value nested inside a collection
+fn write_nested(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    write!(f, "{}", value.repr())
+}
@@ -8,3 +8,45 @@ pub mod parser;
 pub mod error;
 
 pub mod interpreter;
+
+// This module declaration includes the interactive REPL.
+pub mod repl;
+
+// This module declaration includes the source formatter.
+pub mod formatter;
+
+// This module declaration includes the static checks behind `glint check`.
+pub mod checker;
+
+// This module declaration includes the rule-based linter behind `glint lint`.
+pub mod linter;
+
+// This module declaration includes the language server behind `glint lsp`.
+pub mod lsp;
+
+// This module declaration includes the breakpoints and stepping shared by the debuggers.
+pub mod debugger;
+
+// This module declaration includes the debug adapter behind `glint dap`.
+pub mod dap;
+
+// This module declaration includes the terminal debugger behind `glint debug`.
+pub mod console;
+
+// This module declaration includes the execution profiler behind `glint run --profile`.
+pub mod profiler;
+
+// This module declaration includes the benchmark runner behind `glint bench`.
+pub mod bench;
+
+// This module declaration includes the golden-file test runner behind `glint test`.
+pub mod golden;
+
+// This module declaration includes the runner for in-language `test` blocks.
+pub mod testing;
+
+// This module declaration includes the linker that resolves `import` statements.
+pub mod modules;
+
+// This module declaration includes the `glint.toml` project manifest.
+pub mod manifest;
@@ -0,0 +1,765 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::ast::{Span, AST};
+use crate::checker::{Diagnostic, Locator, Severity};
+use crate::interpreter::value::Value;
+
+// 🧹 Rule-based linter behind `glint lint`
+
+// 🎚️ What happens when a rule matches: nothing, a warning, or an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Level {
+    /// 🔤 Parses `allow`, `warn` or `deny`
+    pub fn parse(name: &str) -> Option<Level> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+}
+
+// 📏 A lint rule and the level it has unless configured otherwise
+pub struct Rule {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: Level,
+}
+
+/// 📚 Every rule the linter knows
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "unused-variable",
+        description: "a variable or parameter is assigned but never read",
+        default: Level::Warn,
+    },
+    Rule {
+        name: "unused-function",
+        description: "a function is defined but never called",
+        default: Level::Warn,
+    },
+    Rule {
+        name: "shadowed-parameter",
+        description: "a parameter hides a global variable or an earlier parameter",
+        default: Level::Warn,
+    },
+    Rule {
+        name: "unreachable-code",
+        description: "statements after a `return` never run",
+        default: Level::Warn,
+    },
+    Rule {
+        name: "constant-condition",
+        description: "an `if` condition is always true or always false, e.g. `if 1 = 1`",
+        default: Level::Warn,
+    },
+    Rule {
+        name: "duplicate-function",
+        description: "a function is defined again, silently replacing the earlier definition",
+        default: Level::Deny,
+    },
+    Rule {
+        name: "coincide-without-default",
+        description: "a `coincide` has no `default` arm",
+        default: Level::Warn,
+    },
+];
+
+fn find_rule(name: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.name == name)
+}
+
+// ⚙️ Rule levels set by `glint.toml` and the command line
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<String, Level>,
+}
+
+impl LintConfig {
+    /// 📄 Reads the `[lint]` table of a `glint.toml`, e.g. `unused-variable = "allow"`
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        #[derive(Deserialize)]
+        struct Manifest {
+            #[serde(default)]
+            lint: HashMap<String, Level>,
+        }
+
+        let manifest: Manifest = toml::from_str(text).map_err(|err| err.to_string())?;
+        let mut config = LintConfig::default();
+        for (rule, level) in manifest.lint {
+            config.set(&rule, level)?;
+        }
+        Ok(config)
+    }
+
+    /// 📄 Reads a `glint.toml` file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Error reading {}: {}", path.display(), err))?;
+        LintConfig::from_toml(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// 🔎 Uses the `glint.toml` closest to a script (in its directory or a parent), if any
+    pub fn for_script(script: &Path) -> Result<Self, String> {
+        let manifest = script.canonicalize().ok().and_then(|script| {
+            script
+                .ancestors()
+                .skip(1)
+                .map(|dir| dir.join("glint.toml"))
+                .find(|path| path.is_file())
+        });
+        match manifest {
+            Some(path) => LintConfig::from_file(&path),
+            None => Ok(LintConfig::default()),
+        }
+    }
+
+    /// 🎚️ Sets the level of a rule; fails for unknown rule names
+    pub fn set(&mut self, rule: &str, level: Level) -> Result<(), String> {
+        if find_rule(rule).is_none() {
+            return Err(format!("unknown lint rule '{}'", rule));
+        }
+        self.levels.insert(rule.to_string(), level);
+        Ok(())
+    }
+
+    /// 🎚️ The configured level of a rule, or its default
+    pub fn level(&self, rule: &str) -> Level {
+        self.levels
+            .get(rule)
+            .copied()
+            .or_else(|| find_rule(rule).map(|rule| rule.default))
+            .unwrap_or(Level::Allow)
+    }
+}
+
+/// 🧹 Lints a parsed program. Besides `config`, comments can change levels:
+/// `# glint: allow(rule, ...)` applies to the statement on the same line (or the next one),
+/// `# glint-file: deny(rule)` to the whole file
+pub fn lint_program(ast: &AST, source: Option<&str>, config: &LintConfig) -> Vec<Diagnostic> {
+    let statements = match ast {
+        AST::Program(statements) => statements.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    let mut linter = Linter {
+        locator: source.map(Locator::new),
+        findings: Vec::new(),
+        functions: Vec::new(),
+        calls: Vec::new(),
+        global_reads: HashSet::new(),
+    };
+    let mut globals = Scope::default();
+    linter.statements(statements, &mut globals);
+    linter.finish(globals);
+
+    let mut directives = Directives::default();
+    let mut diagnostics = Vec::new();
+    directives.collect(statements, None, &mut diagnostics);
+
+    for finding in linter.findings {
+        let level = directives.level(finding.rule, finding.span.line, config);
+        let severity = match level {
+            Level::Allow => continue,
+            Level::Warn => Severity::Warning,
+            Level::Deny => Severity::Error,
+        };
+        diagnostics.push(Diagnostic {
+            severity,
+            span: finding.span,
+            message: finding.message,
+            rule: Some(finding.rule),
+        });
+    }
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.line, diagnostic.span.column));
+    diagnostics
+}
+
+struct Finding {
+    rule: &'static str,
+    span: Span,
+    message: String,
+}
+
+// 🛠️ A function definition and its parameters, with where they were written
+struct FunctionInfo {
+    name: String,
+    span: Span,
+    params: Vec<(String, Span)>,
+    // Methods are called through values, so calls to them cannot be told apart by name
+    method: bool,
+}
+
+// 📦 Names assigned and read in the program body or in one function
+#[derive(Default)]
+struct Scope {
+    function: Option<String>,
+    params: Vec<(String, Span)>,
+    assigned: Vec<(String, Span)>,
+    reads: HashSet<String>,
+}
+
+struct Linter {
+    locator: Option<Locator>,
+    findings: Vec<Finding>,
+    functions: Vec<FunctionInfo>,
+    // Every call and the function it was made from (`None` for the program body)
+    calls: Vec<(String, Option<String>)>,
+    // Reads of names that are not local to the function reading them
+    global_reads: HashSet<String>,
+}
+
+impl Linter {
+    /// 🧱 Lints statements in order; returns true if they always end in a `return`
+    fn statements(&mut self, statements: &[AST], scope: &mut Scope) -> bool {
+        let mut returns = false;
+        let mut reported = false;
+        for statement in statements {
+            let (span, node) = unlocated(statement);
+            // Function, struct and method definitions are hoisted and test blocks run on their own, so they are reachable anywhere
+            if returns
+                && !reported
+                && !matches!(
+                    node,
+                    AST::Comment(_)
+                        | AST::Function { .. }
+                        | AST::Struct { .. }
+                        | AST::Impl { .. }
+                        | AST::Test { .. }
+                )
+            {
+                self.report(
+                    "unreachable-code",
+                    span,
+                    "unreachable code after `return`".to_string(),
+                );
+                reported = true;
+            }
+            returns |= self.statement(node, span, scope);
+        }
+        returns
+    }
+
+    /// ▶️ Lints one statement; returns true if it always ends in a `return`
+    fn statement(&mut self, node: &AST, span: Span, scope: &mut Scope) -> bool {
+        match node {
+            AST::Located { span, node, .. } => self.statement(node, *span, scope),
+            AST::Return(value) => {
+                self.expression(value, scope);
+                true
+            }
+            AST::Write(items) => {
+                for item in items {
+                    self.expression(item, scope);
+                }
+                false
+            }
+            AST::VariableAssign { name, value } => {
+                self.expression(value, scope);
+                if !scope.assigned.iter().any(|(assigned, _)| assigned == name) {
+                    scope.assigned.push((name.clone(), name_span(span, name)));
+                }
+                false
+            }
+            AST::Destructure { names, value } => {
+                self.expression(value, scope);
+                for name in names {
+                    if !scope.assigned.iter().any(|(assigned, _)| assigned == name) {
+                        scope.assigned.push((name.clone(), name_span(span, name)));
+                    }
+                }
+                false
+            }
+            AST::Assign { target, value } => {
+                self.expression(target, scope);
+                self.expression(value, scope);
+                false
+            }
+            AST::Function { name, args, body } => {
+                self.function(name, args, body, span, None);
+                false
+            }
+            AST::Impl { name, methods } => {
+                for method in methods {
+                    if let AST::Located { span, node, .. } = method {
+                        if let AST::Function {
+                            name: method,
+                            args,
+                            body,
+                        } = node.as_ref()
+                        {
+                            self.function(method, args, body, *span, Some(name));
+                        }
+                    }
+                }
+                false
+            }
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => {
+                if let Some(value) = constant(condition) {
+                    let always = if value.is_truthy() { "true" } else { "false" };
+                    self.report(
+                        "constant-condition",
+                        span,
+                        format!("this condition is always {}", always),
+                    );
+                }
+                self.expression(condition, scope);
+                let then_returns = self.block(if_block, scope);
+                let else_returns = match else_block {
+                    Some(else_block) => self.block(else_block, scope),
+                    None => false,
+                };
+                then_returns && else_returns
+            }
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                ..
+            } => {
+                self.expression(expr, scope);
+                for (value, action) in cases {
+                    self.expression(value, scope);
+                    self.statement(action, span, scope);
+                }
+                match default {
+                    Some(action) => {
+                        self.statement(action, span, scope);
+                    }
+                    None => self.report(
+                        "coincide-without-default",
+                        span,
+                        "`coincide` has no `default` arm".to_string(),
+                    ),
+                }
+                false
+            }
+            AST::Test { body, .. } => {
+                // A test runs on its own, so its variables are not globals
+                self.block(body, &mut Scope::default());
+                false
+            }
+            AST::Assert { condition, message } => {
+                self.expression(condition, scope);
+                if let Some(message) = message {
+                    self.expression(message, scope);
+                }
+                false
+            }
+            AST::Block(statements) => self.statements(statements, scope),
+            AST::Comment(_) => false,
+            expr => {
+                self.expression(expr, scope);
+                false
+            }
+        }
+    }
+
+    fn block(&mut self, block: &AST, scope: &mut Scope) -> bool {
+        match block {
+            AST::Block(statements) => self.statements(statements, scope),
+            other => self.statements(std::slice::from_ref(other), scope),
+        }
+    }
+
+    /// 🛠️ Lints a function body in its own scope and reports its unused locals; `owner` is the
+    /// type a method of an `impl` block belongs to
+    fn function(&mut self, name: &str, args: &AST, body: &AST, span: Span, owner: Option<&str>) {
+        let params = args.param_names();
+
+        // The function name comes first, then the parameters in order
+        let mut occurrences: HashMap<&str, usize> = HashMap::from([(name, 1)]);
+        let params = params
+            .iter()
+            .map(|param| {
+                let nth = occurrences.entry(param).or_insert(0);
+                let param_span = self.locate(span, param, *nth);
+                *nth += 1;
+                (param.clone(), param_span)
+            })
+            .collect::<Vec<_>>();
+
+        let qualified = match owner {
+            Some(owner) => format!("{}.{}", owner, name),
+            None => name.to_string(),
+        };
+        let mut scope = Scope {
+            function: Some(qualified.clone()),
+            params: params.clone(),
+            ..Scope::default()
+        };
+        self.block(body, &mut scope);
+
+        let is_local = |name: &String| {
+            scope.params.iter().any(|(param, _)| param == name)
+                || scope.assigned.iter().any(|(local, _)| local == name)
+        };
+        for read in &scope.reads {
+            if !is_local(read) {
+                self.global_reads.insert(read.clone());
+            }
+        }
+        // A method need not read the value it is called on
+        let receivers = usize::from(owner.is_some());
+        let unused: Vec<(&str, String, Span)> = scope
+            .params
+            .iter()
+            .skip(receivers)
+            .map(|(param, span)| ("parameter", param, span))
+            .chain(
+                scope
+                    .assigned
+                    .iter()
+                    .filter(|(local, _)| !scope.params.iter().any(|(param, _)| param == local))
+                    .map(|(local, span)| ("variable", local, span)),
+            )
+            .filter(|(_, local, _)| !scope.reads.contains(*local) && !local.starts_with('_'))
+            .map(|(kind, local, span)| (kind, local.clone(), *span))
+            .collect();
+        for (kind, local, local_span) in unused {
+            self.report(
+                "unused-variable",
+                local_span,
+                format!("{} '{}' is never read", kind, local),
+            );
+        }
+
+        self.functions.push(FunctionInfo {
+            name: qualified,
+            span: name_span(span, name),
+            params,
+            method: owner.is_some(),
+        });
+    }
+
+    /// 🧮 Records the names an expression reads and the functions it calls
+    fn expression(&mut self, node: &AST, scope: &mut Scope) {
+        match node {
+            AST::Identifier(name) => {
+                // A function passed around by name counts as called
+                self.calls.push((name.clone(), scope.function.clone()));
+                scope.reads.insert(name.clone());
+                // `point.x` reads `point`
+                if let Some((variable, _)) = name.split_once('.') {
+                    scope.reads.insert(variable.to_string());
+                }
+            }
+            AST::FunctionCall { name, args } => {
+                // The name may be a parameter or variable holding a function
+                self.calls.push((name.clone(), scope.function.clone()));
+                scope.reads.insert(name.clone());
+                // `p.len()` reads `p`
+                if let Some((variable, _)) = name.split_once('.') {
+                    scope.reads.insert(variable.to_string());
+                }
+                for arg in args {
+                    self.expression(arg, scope);
+                }
+            }
+            AST::Construct { fields, .. } => {
+                for (_, value) in fields {
+                    self.expression(value, scope);
+                }
+            }
+            AST::BinaryOp { left, right, .. } => {
+                self.expression(left, scope);
+                self.expression(right, scope);
+            }
+            AST::Array(items) | AST::Tuple(items) | AST::Alternatives(items) => {
+                for item in items {
+                    self.expression(item, scope);
+                }
+            }
+            AST::Range { start, end } => {
+                self.expression(start, scope);
+                self.expression(end, scope);
+            }
+            AST::Dictionary(pairs) => {
+                for (key, value) in pairs {
+                    // Bare identifiers used as keys are string keys, not reads
+                    if !matches!(key, AST::Identifier(_)) {
+                        self.expression(key, scope);
+                    }
+                    self.expression(value, scope);
+                }
+            }
+            AST::Lambda { args, body } => {
+                // The names a lambda reads from around it (rather than its own locals) are read there
+                let params = args.param_names();
+                let mut inner = Scope {
+                    function: scope.function.clone(),
+                    ..Scope::default()
+                };
+                self.block(body, &mut inner);
+                let is_local = |name: &String| {
+                    params.contains(name) || inner.assigned.iter().any(|(local, _)| local == name)
+                };
+                scope
+                    .reads
+                    .extend(inner.reads.iter().filter(|read| !is_local(read)).cloned());
+            }
+            AST::Index { object, index } => {
+                self.expression(object, scope);
+                self.expression(index, scope);
+            }
+            AST::Slice { object, start, end } => {
+                self.expression(object, scope);
+                for bound in [start, end].into_iter().flatten() {
+                    self.expression(bound, scope);
+                }
+            }
+            AST::Field { object, .. } => self.expression(object, scope),
+            AST::Call { callee, args } => {
+                self.expression(callee, scope);
+                for arg in args {
+                    self.expression(arg, scope);
+                }
+            }
+            AST::Located { node, .. } => self.expression(node, scope),
+            _ => {}
+        }
+    }
+
+    /// 🏁 Reports what can only be decided once the whole program has been seen
+    fn finish(&mut self, globals: Scope) {
+        self.global_reads.extend(globals.reads);
+        for (name, span) in &globals.assigned {
+            if !self.global_reads.contains(name) && !name.starts_with('_') {
+                self.report(
+                    "unused-variable",
+                    *span,
+                    format!("variable '{}' is never read", name),
+                );
+            }
+        }
+
+        let mut first_definitions: HashMap<&str, &FunctionInfo> = HashMap::new();
+        let mut findings = Vec::new();
+        for function in &self.functions {
+            match first_definitions.get(function.name.as_str()) {
+                Some(first) => findings.push((
+                    "duplicate-function",
+                    function.span,
+                    format!(
+                        "function '{}' is already defined on line {}",
+                        function.name, first.span.line
+                    ),
+                )),
+                None => {
+                    first_definitions.insert(&function.name, function);
+                }
+            }
+
+            let mut seen = HashSet::new();
+            for (param, span) in &function.params {
+                if !seen.insert(param) {
+                    findings.push((
+                        "shadowed-parameter",
+                        *span,
+                        format!("parameter '{}' shadows an earlier parameter", param),
+                    ));
+                } else if globals.assigned.iter().any(|(global, _)| global == param) {
+                    findings.push((
+                        "shadowed-parameter",
+                        *span,
+                        format!(
+                            "parameter '{}' shadows the global variable '{}'",
+                            param, param
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for (name, function) in first_definitions {
+            if function.method {
+                continue;
+            }
+            // Recursive calls do not make a function used
+            let called = self
+                .calls
+                .iter()
+                .any(|(callee, caller)| callee == name && caller.as_deref() != Some(name));
+            if !called {
+                findings.push((
+                    "unused-function",
+                    function.span,
+                    format!("function '{}' is never called", name),
+                ));
+            }
+        }
+
+        for (rule, span, message) in findings {
+            self.report(rule, span, message);
+        }
+    }
+
+    /// 📍 Where the `nth` occurrence of `name` inside a statement is
+    fn locate(&self, span: Span, name: &str, nth: usize) -> Span {
+        self.locator
+            .as_ref()
+            .and_then(|locator| locator.find(span, name, nth))
+            .unwrap_or(span)
+    }
+
+    fn report(&mut self, rule: &'static str, span: Span, message: String) {
+        self.findings.push(Finding {
+            rule,
+            span,
+            message,
+        });
+    }
+}
+
+// 💬 Levels changed by `# glint:` comments
+#[derive(Default)]
+struct Directives {
+    file: HashMap<String, Level>,
+    // (first line, last line, rule, level) for statement-level comments
+    ranges: Vec<(usize, usize, String, Level)>,
+}
+
+impl Directives {
+    /// 🔎 Finds directive comments in a statement list and its nested blocks
+    /// `parent` is the statement owning the block, which a comment right after its `{` applies to
+    fn collect(
+        &mut self,
+        statements: &[AST],
+        parent: Option<Span>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let mut previous: Option<Span> = None;
+        for (i, statement) in statements.iter().enumerate() {
+            let (span, node) = unlocated(statement);
+            match node {
+                AST::Comment(text) => {
+                    if let Some((file, level, rules)) = parse_directive(text) {
+                        // Trailing comments apply to their line's statement, others to the next one
+                        let target = match previous {
+                            Some(previous) if previous.end_line == span.line => Some(previous),
+                            None if parent.is_some_and(|parent| parent.line == span.line) => parent,
+                            _ => statements[i + 1..]
+                                .iter()
+                                .map(unlocated)
+                                .find(|(_, node)| !matches!(node, AST::Comment(_)))
+                                .map(|(span, _)| span),
+                        };
+                        for rule in rules {
+                            if find_rule(&rule).is_none() {
+                                diagnostics.push(Diagnostic {
+                                    severity: Severity::Warning,
+                                    span,
+                                    message: format!("unknown lint rule '{}'", rule),
+                                    rule: None,
+                                });
+                            } else if file {
+                                self.file.insert(rule, level);
+                            } else if let Some(target) = target {
+                                self.ranges
+                                    .push((target.line, target.end_line, rule, level));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                AST::Function { body, .. } | AST::Test { body, .. } => {
+                    self.collect_block(body, span, diagnostics)
+                }
+                AST::IfElse {
+                    if_block,
+                    else_block,
+                    ..
+                } => {
+                    self.collect_block(if_block, span, diagnostics);
+                    if let Some(else_block) = else_block {
+                        self.collect_block(else_block, span, diagnostics);
+                    }
+                }
+                _ => {}
+            }
+            previous = Some(span);
+        }
+    }
+
+    fn collect_block(&mut self, block: &AST, parent: Span, diagnostics: &mut Vec<Diagnostic>) {
+        if let AST::Block(statements) = block {
+            self.collect(statements, Some(parent), diagnostics);
+        }
+    }
+
+    /// 🎚️ The level of `rule` on `line`: the closest statement comment wins over
+    /// file comments, which win over the configuration
+    fn level(&self, rule: &str, line: usize, config: &LintConfig) -> Level {
+        self.ranges
+            .iter()
+            .filter(|(first, last, name, _)| name == rule && (*first..=*last).contains(&line))
+            .min_by_key(|(first, last, _, _)| last - first)
+            .map(|(_, _, _, level)| *level)
+            .or_else(|| self.file.get(rule).copied())
+            .unwrap_or_else(|| config.level(rule))
+    }
+}
+
+/// 💬 Parses `glint: allow(a, b)` / `glint-file: deny(a)` into (file-wide, level, rules)
+fn parse_directive(text: &str) -> Option<(bool, Level, Vec<String>)> {
+    let text = text.trim();
+    let (file, rest) = if let Some(rest) = text.strip_prefix("glint-file:") {
+        (true, rest)
+    } else {
+        (false, text.strip_prefix("glint:")?)
+    };
+    let (level, rules) = rest.trim().split_once('(')?;
+    let level = Level::parse(level.trim())?;
+    let rules = rules
+        .trim_end()
+        .strip_suffix(')')?
+        .split(',')
+        .map(|rule| rule.trim().to_string())
+        .filter(|rule| !rule.is_empty())
+        .collect();
+    Some((file, level, rules))
+}
+
+/// 📍 Splits a `Located` statement into its span and node
+fn unlocated(statement: &AST) -> (Span, &AST) {
+    match statement {
+        AST::Located { span, node, .. } => (*span, node),
+        other => (Span::default(), other),
+    }
+}
+
+/// 📍 The span of a name written at the start of a statement
+pub(crate) fn name_span(statement: Span, name: &str) -> Span {
+    Span {
+        end_line: statement.line,
+        end_column: statement.column + name.chars().count(),
+        ..statement
+    }
+}
+
+/// 🧊 Evaluates an expression made only of literals, or returns `None`
+fn constant(node: &AST) -> Option<Value> {
+    match node {
+        AST::Integer(n) => Some(Value::Integer(*n as i64)),
+        AST::Float(f) => Some(Value::Float(*f)),
+        AST::Bool(b) => Some(Value::Bool(*b)),
+        AST::String(s) => Some(Value::String(s.clone())),
+        AST::BinaryOp { left, op, right } => constant(left)?.binary_op(op, &constant(right)?).ok(),
+        _ => None,
+    }
+}
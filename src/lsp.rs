@@ -0,0 +1,645 @@
+use serde_json::{json, Value as Json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::ast::{Span, AST};
+use crate::checker::{check_program, Diagnostic, Locator, Severity};
+use crate::error::ParseError;
+use crate::formatter::format_source;
+use crate::interpreter::builtins::BUILTINS;
+use crate::linter::{lint_program, name_span, LintConfig};
+use crate::parser::parser::parse_program;
+
+// 🧭 Language server for editors, spoken over stdio by `glint lsp`
+
+const KEYWORDS: &[&str] = &[
+    "write", "return", "if", "else", "coincide", "then", "default", "is", "true", "false", "test",
+    "assert", "import", "fn", "struct", "impl",
+];
+
+// LSP enum values used below
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SYMBOL_METHOD: u8 = 6;
+const SYMBOL_FUNCTION: u8 = 12;
+const SYMBOL_STRUCT: u8 = 23;
+const COMPLETION_FUNCTION: u8 = 3;
+const COMPLETION_VARIABLE: u8 = 6;
+const COMPLETION_KEYWORD: u8 = 14;
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// 🚀 Serves requests from stdin until the client sends `exit`
+pub fn run_stdio() -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    serve(stdin.lock(), stdout.lock())
+}
+
+/// 🔁 Reads framed JSON-RPC messages from `reader` and writes responses and notifications to `writer`
+pub fn serve<R: BufRead, W: Write>(mut reader: R, writer: W) -> io::Result<()> {
+    let mut server = Server {
+        writer,
+        documents: HashMap::new(),
+    };
+    while let Some(message) = read_message(&mut reader)? {
+        if !server.handle(&message)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// 📥 Reads one `Content-Length`-framed message; `None` at end of input
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// 📤 Writes one message with its `Content-Length` header
+pub fn write_message<W: Write>(writer: &mut W, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+// 📄 An open document and the last version of it that parsed
+struct Document {
+    text: String,
+    ast: Option<AST>,
+}
+
+struct Server<W: Write> {
+    writer: W,
+    documents: HashMap<String, Document>,
+}
+
+impl<W: Write> Server<W> {
+    /// 📨 Handles one message; returns false once the client asked the server to exit
+    fn handle(&mut self, message: &Json) -> io::Result<bool> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": {},
+                    "documentSymbolProvider": true,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": { "name": "glint", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => Json::Null,
+            "exit" => return Ok(false),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                self.update(
+                    document["uri"].as_str().unwrap_or_default(),
+                    document["text"].as_str().unwrap_or_default(),
+                )?;
+                return Ok(true);
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text
+                if let Some(change) = params["contentChanges"].as_array().and_then(|c| c.last()) {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                    self.update(uri, change["text"].as_str().unwrap_or_default())?;
+                }
+                return Ok(true);
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                self.notify(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                )?;
+                return Ok(true);
+            }
+            "textDocument/definition" => self.with_document(params, definition),
+            "textDocument/hover" => self.with_document(params, hover),
+            "textDocument/completion" => self.with_document(params, completion),
+            "textDocument/documentSymbol" => self.with_document(params, document_symbols),
+            "textDocument/formatting" => self.with_document(params, formatting),
+            _ => {
+                // Unknown notifications are ignored, unknown requests get an error
+                if let Some(id) = id {
+                    let error = json!({ "code": METHOD_NOT_FOUND, "message": format!("unknown method '{}'", method) });
+                    write_message(
+                        &mut self.writer,
+                        &json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+                    )?;
+                }
+                return Ok(true);
+            }
+        };
+
+        if let Some(id) = id {
+            write_message(
+                &mut self.writer,
+                &json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// 📝 Stores new text for a document and publishes its diagnostics
+    fn update(&mut self, uri: &str, text: &str) -> io::Result<()> {
+        let previous = self.documents.remove(uri).and_then(|document| document.ast);
+        let (ast, diagnostics) = match parse_program(text) {
+            Ok(ast) => {
+                let config = uri_path(uri)
+                    .and_then(|path| LintConfig::for_script(&path).ok())
+                    .unwrap_or_default();
+                let mut found = check_program(&ast, Some(text));
+                found.extend(lint_program(&ast, Some(text), &config));
+                let diagnostics = found.iter().map(|d| lsp_diagnostic(text, d)).collect();
+                (Some(ast), diagnostics)
+            }
+            // Keep the last good tree so navigation still works while the user is typing
+            Err(err) => (previous, vec![parse_error_diagnostic(text, &err)]),
+        };
+        self.documents.insert(
+            uri.to_string(),
+            Document {
+                text: text.to_string(),
+                ast,
+            },
+        );
+        self.notify(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    /// 📄 Runs a request handler against the document named in `params`
+    fn with_document(&self, params: &Json, handler: fn(&Document, &Json) -> Json) -> Json {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match self.documents.get(uri) {
+            Some(document) => handler(document, params),
+            None => Json::Null,
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Json) -> io::Result<()> {
+        write_message(
+            &mut self.writer,
+            &json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+        )
+    }
+}
+
+/// 🎯 `textDocument/definition`: where the function or variable under the cursor is defined
+fn definition(document: &Document, params: &Json) -> Json {
+    let (Some(ast), Some((word, line))) =
+        (&document.ast, word_at(&document.text, &params["position"]))
+    else {
+        return Json::Null;
+    };
+    let locator = Locator::new(&document.text);
+    let uri = &params["textDocument"]["uri"];
+
+    if let Some(function) = functions(ast).into_iter().find(|f| f.name == word) {
+        return json!({ "uri": uri, "range": lsp_range(&document.text, function.name_span) });
+    }
+
+    // Inside a function a name refers to a parameter or local first, then to a global
+    let enclosing = functions(ast)
+        .into_iter()
+        .filter(|f| (f.span.line..=f.span.end_line).contains(&line))
+        .min_by_key(|f| f.span.end_line - f.span.line);
+    let target = match enclosing {
+        Some(function) => function
+            .params
+            .iter()
+            .any(|param| param == &word)
+            .then(|| locator.find(function.span, &word, usize::from(function.name == word)))
+            .flatten()
+            .or_else(|| first_assignment(&function.body, &word)),
+        None => None,
+    }
+    .or_else(|| first_assignment(program_statements(ast), &word));
+
+    match target {
+        Some(span) => json!({ "uri": uri, "range": lsp_range(&document.text, span) }),
+        None => Json::Null,
+    }
+}
+
+/// 💡 `textDocument/hover`: the argument list of the function under the cursor
+fn hover(document: &Document, params: &Json) -> Json {
+    let Some((word, _)) = word_at(&document.text, &params["position"]) else {
+        return Json::Null;
+    };
+    let signature = document
+        .ast
+        .as_ref()
+        .and_then(|ast| functions(ast).into_iter().find(|f| f.name == word))
+        .map(|function| format!("{}({})", function.name, function.params.join(", ")))
+        .or_else(|| builtin_signature(&word).map(str::to_string));
+    match signature {
+        Some(signature) => json!({
+            "contents": { "kind": "markdown", "value": format!("```glint\n{}\n```", signature) },
+        }),
+        None => Json::Null,
+    }
+}
+
+/// ✍️ `textDocument/completion`: keywords, builtins, functions and variables
+fn completion(document: &Document, _params: &Json) -> Json {
+    let mut items: Vec<Json> = KEYWORDS
+        .iter()
+        .map(|keyword| json!({ "label": keyword, "kind": COMPLETION_KEYWORD }))
+        .collect();
+    for builtin in BUILTINS {
+        items.push(json!({
+            "label": builtin,
+            "kind": COMPLETION_FUNCTION,
+            "detail": builtin_signature(builtin),
+        }));
+    }
+    if let Some(ast) = &document.ast {
+        let mut seen: Vec<String> = Vec::new();
+        for function in functions(ast) {
+            if !seen.contains(&function.name) {
+                items.push(json!({
+                    "label": function.name,
+                    "kind": COMPLETION_FUNCTION,
+                    "detail": format!("{}({})", function.name, function.params.join(", ")),
+                }));
+                seen.push(function.name);
+            }
+            for param in function.params {
+                if !seen.contains(&param) {
+                    items.push(json!({ "label": param, "kind": COMPLETION_VARIABLE }));
+                    seen.push(param);
+                }
+            }
+        }
+        let mut variables = vec!["args".to_string()];
+        collect_assignments(program_statements(ast), &mut variables);
+        for variable in variables {
+            if !seen.contains(&variable) {
+                items.push(json!({ "label": variable, "kind": COMPLETION_VARIABLE }));
+                seen.push(variable);
+            }
+        }
+    }
+    Json::Array(items)
+}
+
+/// 🗂️ `textDocument/documentSymbol`: every struct, method and function, nested ones as children
+fn document_symbols(document: &Document, _params: &Json) -> Json {
+    match &document.ast {
+        Some(ast) => Json::Array(symbols(&document.text, program_statements(ast))),
+        None => Json::Array(Vec::new()),
+    }
+}
+
+fn symbols(text: &str, statements: &[AST]) -> Vec<Json> {
+    let mut found = Vec::new();
+    for statement in statements {
+        let AST::Located { span, node, .. } = statement else {
+            continue;
+        };
+        match node.as_ref() {
+            AST::Function { name, args, body } => found.push(json!({
+                "name": name,
+                "detail": format!("({})", args.param_names().join(", ")),
+                "kind": SYMBOL_FUNCTION,
+                "range": lsp_range(text, *span),
+                "selectionRange": lsp_range(text, name_span(*span, name)),
+                "children": symbols(text, block_statements(body)),
+            })),
+            AST::Impl { name, methods } => {
+                for method in methods {
+                    let AST::Located { span, node, .. } = method else {
+                        continue;
+                    };
+                    if let AST::Function {
+                        name: method,
+                        args,
+                        body,
+                    } = node.as_ref()
+                    {
+                        found.push(json!({
+                            "name": format!("{}.{}", name, method),
+                            "detail": format!("({})", args.param_names().join(", ")),
+                            "kind": SYMBOL_METHOD,
+                            "range": lsp_range(text, *span),
+                            "selectionRange": lsp_range(text, name_span(*span, method)),
+                            "children": symbols(text, block_statements(body)),
+                        }));
+                    }
+                }
+            }
+            AST::Struct { name, fields } => {
+                // The name comes after `struct `
+                let start = Span {
+                    column: span.column + "struct ".len(),
+                    ..*span
+                };
+                let fields: Vec<&str> = fields.iter().map(|(field, _)| field.as_str()).collect();
+                found.push(json!({
+                    "name": name,
+                    "detail": format!("{{ {} }}", fields.join(", ")),
+                    "kind": SYMBOL_STRUCT,
+                    "range": lsp_range(text, *span),
+                    "selectionRange": lsp_range(text, name_span(start, name)),
+                }));
+            }
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                found.extend(symbols_in(text, if_block));
+                if let Some(else_block) = else_block {
+                    found.extend(symbols_in(text, else_block));
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+fn symbols_in(text: &str, block: &AST) -> Vec<Json> {
+    symbols(text, block_statements(block))
+}
+
+/// 🎨 `textDocument/formatting`: replaces the whole document with its formatted form
+fn formatting(document: &Document, _params: &Json) -> Json {
+    match format_source(&document.text) {
+        Ok(formatted) if formatted != document.text => {
+            let end = end_position(&document.text);
+            json!([{
+                "range": { "start": { "line": 0, "character": 0 }, "end": end },
+                "newText": formatted,
+            }])
+        }
+        Ok(_) => json!([]),
+        // Nothing to format until the document parses again
+        Err(_) => Json::Null,
+    }
+}
+
+// 🛠️ A function definition found in the tree
+struct FunctionInfo {
+    name: String,
+    params: Vec<String>,
+    span: Span,
+    name_span: Span,
+    body: Vec<AST>,
+}
+
+/// 🔎 Every function definition in the program, including nested ones
+fn functions(ast: &AST) -> Vec<FunctionInfo> {
+    let mut found = Vec::new();
+    collect_functions(program_statements(ast), &mut found);
+    found
+}
+
+fn collect_functions(statements: &[AST], found: &mut Vec<FunctionInfo>) {
+    for statement in statements {
+        let (span, node) = match statement {
+            AST::Located { span, node, .. } => (*span, node.as_ref()),
+            other => (Span::default(), other),
+        };
+        match node {
+            AST::Function { name, args, body } => {
+                found.push(FunctionInfo {
+                    name: name.clone(),
+                    params: args.param_names(),
+                    span,
+                    name_span: name_span(span, name),
+                    body: block_statements(body).to_vec(),
+                });
+                collect_functions(block_statements(body), found);
+            }
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                collect_functions(block_statements(if_block), found);
+                if let Some(else_block) = else_block {
+                    collect_functions(block_statements(else_block), found);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 📝 The span of the first assignment to `name` in these statements (not inside functions)
+fn first_assignment(statements: &[AST], name: &str) -> Option<Span> {
+    for statement in statements {
+        let AST::Located { span, node, .. } = statement else {
+            continue;
+        };
+        let found = match node.as_ref() {
+            AST::VariableAssign { name: assigned, .. } if assigned == name => {
+                Some(name_span(*span, name))
+            }
+            AST::Destructure { names, .. } if names.iter().any(|assigned| assigned == name) => {
+                Some(name_span(*span, name))
+            }
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => first_assignment(block_statements(if_block), name).or_else(|| {
+                else_block
+                    .as_deref()
+                    .and_then(|block| first_assignment(block_statements(block), name))
+            }),
+            _ => None,
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+/// 📋 Names assigned in these statements (not inside functions), in order
+fn collect_assignments(statements: &[AST], names: &mut Vec<String>) {
+    for statement in statements {
+        match statement {
+            AST::Located { node, .. } => collect_assignments(std::slice::from_ref(node), names),
+            AST::VariableAssign { name, .. } if !names.contains(name) => names.push(name.clone()),
+            AST::Destructure {
+                names: assigned, ..
+            } => {
+                for name in assigned {
+                    if !names.contains(name) {
+                        names.push(name.clone());
+                    }
+                }
+            }
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                collect_assignments(block_statements(if_block), names);
+                if let Some(else_block) = else_block {
+                    collect_assignments(block_statements(else_block), names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn program_statements(ast: &AST) -> &[AST] {
+    match ast {
+        AST::Program(statements) => statements,
+        other => std::slice::from_ref(other),
+    }
+}
+
+fn block_statements(block: &AST) -> &[AST] {
+    match block {
+        AST::Block(statements) => statements,
+        other => std::slice::from_ref(other),
+    }
+}
+
+fn builtin_signature(name: &str) -> Option<&'static str> {
+    match name {
+        "env" => Some("env(name, default)"),
+        "exit" => Some("exit(code)"),
+        "map" => Some("map(array, f)"),
+        "filter" => Some("filter(array, f)"),
+        "reduce" => Some("reduce(array, init, f)"),
+        "any" => Some("any(array, f)"),
+        "all" => Some("all(array, f)"),
+        "find" => Some("find(array, f)"),
+        "sort_by" => Some("sort_by(array, key)"),
+        "zip" => Some("zip(a, b)"),
+        "enumerate" => Some("enumerate(array)"),
+        _ => None,
+    }
+}
+
+/// 🔤 The identifier at an LSP position and its 1-based line
+fn word_at(text: &str, position: &Json) -> Option<(String, usize)> {
+    let line = position["line"].as_u64()? as usize;
+    let line_text = text.lines().nth(line)?;
+    let chars: Vec<char> = line_text.chars().collect();
+    let index = utf16_to_char(line_text, position["character"].as_u64()? as usize);
+
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let start = chars[..index.min(chars.len())]
+        .iter()
+        .rposition(|c| !is_word(c))
+        .map_or(0, |i| i + 1);
+    let end = chars[start..]
+        .iter()
+        .position(|c| !is_word(c))
+        .map_or(chars.len(), |i| start + i);
+    (start < end).then(|| (chars[start..end].iter().collect(), line + 1))
+}
+
+/// 📏 Converts a UTF-16 offset within a line (what LSP uses) to a character index
+fn utf16_to_char(line: &str, offset: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in line.chars().enumerate() {
+        if units >= offset {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.chars().count()
+}
+
+/// 📏 Converts a 1-based line and character column to an LSP position
+fn lsp_position(text: &str, line: usize, column: usize) -> Json {
+    let line_text = text.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+    let character: usize = line_text
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum();
+    json!({ "line": line.saturating_sub(1), "character": character })
+}
+
+fn lsp_range(text: &str, span: Span) -> Json {
+    json!({
+        "start": lsp_position(text, span.line, span.column),
+        "end": lsp_position(text, span.end_line, span.end_column),
+    })
+}
+
+fn end_position(text: &str) -> Json {
+    let line = text.split('\n').count();
+    let last = text.rsplit('\n').next().unwrap_or_default();
+    lsp_position(text, line, last.chars().count() + 1)
+}
+
+fn lsp_diagnostic(text: &str, diagnostic: &Diagnostic) -> Json {
+    let severity = match diagnostic.severity {
+        Severity::Error => SEVERITY_ERROR,
+        Severity::Warning => SEVERITY_WARNING,
+    };
+    let mut lsp = json!({
+        "range": lsp_range(text, diagnostic.span),
+        "severity": severity,
+        "source": "glint",
+        "message": diagnostic.message,
+    });
+    if let Some(rule) = diagnostic.rule {
+        lsp["code"] = json!(rule);
+    }
+    lsp
+}
+
+/// 🚨 A parse error as a diagnostic running to the end of its line
+fn parse_error_diagnostic(text: &str, err: &ParseError) -> Json {
+    let (line, column) = err.location();
+    let line_length = text
+        .lines()
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default()
+        .chars()
+        .count();
+    json!({
+        "range": {
+            "start": lsp_position(text, line, column),
+            "end": lsp_position(text, line, line_length + 1),
+        },
+        "severity": SEVERITY_ERROR,
+        "source": "glint",
+        "message": err.to_string(),
+    })
+}
+
+/// 📂 The path of a `file://` URI
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    uri.strip_prefix("file://").map(PathBuf::from)
+}
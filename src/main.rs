@@ -1,39 +1,76 @@
+use clap::CommandFactory;
 use colored::Colorize;
 use peak_alloc::PeakAlloc;
-// use serde_cbor;
-use serde_json;
-use std::env;
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::time::Instant;
-use sysinfo::System;
-// use Glint::ast::AST;
-use glint::error::ParseError;
-use glint::interpreter::interpreter::interpret_from_json;
+use glint::ast::AST;
+use glint::bench::{bench_program, collect_programs, millis};
+use glint::golden::run_golden;
+use glint::manifest::{self, Manifest};
+use glint::modules::link_program;
+use glint::checker::{check_program, Severity};
+use glint::error::{ParseError, RuntimeError};
+use glint::formatter::format_source;
+use glint::linter::{lint_program, Level, LintConfig, RULES};
+use glint::interpreter::interpreter::Interpreter;
 use glint::parser::parser::parse_program;
-use os_info;
+use glint::profiler::Profile;
+use glint::repl::Repl;
+use glint::testing::{run_tests, TestResult};
+
+mod cli;
+mod dev;
+
+use cli::{Cli, Command, LintArgs, RunArgs};
 
 #[global_allocator]
 static PEAK_ALLOC: PeakAlloc = PeakAlloc; // 🚀 Custom global allocator for memory tracking
 
-const INFO: &str = r#"
-                 ✧Glint v0.0.1✧
-       Usage: Glint [command] [options]
+const HEADER: &str = concat!("✧Glint v", env!("CARGO_PKG_VERSION"), "✧");
+
+const INFO: &str = concat!(
+    r#"
+                 ✧Glint v"#,
+    env!("CARGO_PKG_VERSION"),
+    r#"✧
+       Usage: glint [command] [options]
        Commands:
-        run <filename>.glt    Run the script
+        run [<filename>.glt]  Run the script (or the project's entry point)
+        new <name>            Create a project with a glint.toml
+        check <files>         Check scripts without running them
+        lint <files>          Report likely mistakes (configurable rules)
+        fmt <files>           Format scripts
+        ast <filename>.glt    Print the AST as JSON
+        repl                  Start an interactive session
+        lsp                   Start a language server for editors
+        dap                   Start a debug adapter for editors
+        debug <filename>.glt  Run a script under the terminal debugger
+        test [paths]          Run the test suite
+        build <filename>.glt  Compile the script to a .gltc file
+        bench [paths]         Run the benchmark suite
         info                  Display info
+        completions <shell>   Generate shell completions
        flags:
-        -dev                  Display dev info
-"#;
+        --dev                 Display dev info
+        --no-color            Disable colored output
+        --jit-stats           Display JIT statistics (run, requires the `jit` feature)
+        --help                Display help for any command
+        --version             Display the version
+"#
+);
 
 fn print_version_info() {
     // 🌐 Prints basic version info and usage instructions
-    let header = "✧Glint v0.0.1✧".bright_blue();
+    let header = HEADER.bright_blue();
     let usage = "Usage:".cyan();
     let commands = "Commands:".cyan();
     let flags = "flags:".cyan();
 
     let info_colored = INFO
-        .replace("✧Glint v0.0.1✧", &header.to_string())
+        .replace(HEADER, &header.to_string())
         .replace("Usage:", &usage.to_string())
         .replace("Commands:", &commands.to_string())
         .replace("flags:", &flags.to_string());
@@ -41,121 +78,697 @@ fn print_version_info() {
     println!("{}", info_colored);
 }
 
-fn print_dev_info(start_time: Instant) {
-    // 📊 Prints developer information like elapsed time and memory usage
-    let elapsed = start_time.elapsed();
-    let elapsed_secs = elapsed.as_secs_f64();
-    let peak_mem_gb = PEAK_ALLOC.peak_usage_as_gb();
-    let current_mem_mb = PEAK_ALLOC.current_usage_as_mb();
-    let os_info = os_info::get();
+fn main() -> ExitCode {
+    let start_time = Instant::now(); // ⏱️ Track start time for measuring performance
+    let cli = cli::parse(); // 📥 Parse command-line arguments
 
-    println!("{} Dev Info {}", "<=> ".blue(), " <=>".blue());
-    println!(
-        "{}: {:.4}s",
-        "Elapsed time".truecolor(41, 176, 255),
-        elapsed_secs
-    );
-    println!("{}:", "Resource consumption".truecolor(0, 76, 120));
-    println!(
-        "  └─ {}: {:.4} MB",
-        "RAM Usage".truecolor(41, 176, 255),
-        current_mem_mb
-    );
-    println!(
-        "  └─ {}: {:.4} GB",
-        "Peak RAM Usage".truecolor(41, 176, 255),
-        peak_mem_gb
-    );
-    println!(
-        "  └─ {}: {:?} {:?}",
-        "OS".truecolor(41, 176, 255),
-        os_info.os_type(),
-        System::os_version()
-    );
-    println!("{} End Dev Info {}", "<=> ".blue(), " <=>".blue());
+    if cli.global.no_color {
+        colored::control::set_override(false);
+    }
+
+    // 📊 With --dev, time and measure each phase and report once the command has finished
+    let dev_json = cli.global.dev_json;
+    let dev = cli.global.dev || dev_json;
+    if dev {
+        dev::enable();
+    }
+    let code = run_command(cli);
+    if dev {
+        dev::print(start_time, dev_json);
+    }
+    code
 }
 
-fn main() {
-    let start_time = Instant::now(); // ⏱️ Track start time for measuring performance
-    let args: Vec<String> = env::args().collect(); // 📥 Collect command-line arguments
+/// 🧭 Runs the command selected on the command line
+fn run_command(cli: Cli) -> ExitCode {
+    // ⚡ `glint -e CODE` evaluates inline code
+    if let Some(code) = &cli.eval {
+        return match parse_source("<eval>", code).and_then(|ast| link(Path::new("-"), ast)) {
+            Ok(ast) => execute(&ast, &cli.script_args, false, false, None),
+            Err(code) => code,
+        };
+    }
 
-    match args.len() {
-        1 => {
-            // ⚙️ No command provided, print version info
+    match cli.command {
+        // ⚙️ No command provided, or "info": print version info
+        None | Some(Command::Info) => {
             print_version_info();
+            ExitCode::SUCCESS
+        }
+        Some(Command::Run(args)) => run(&args),
+        Some(Command::New { path }) => new_project(&path),
+        Some(Command::Check { files }) => check(&files),
+        Some(Command::Ast { file }) => match load_program(&file) {
+            Ok(ast) => {
+                let ast_json = serde_json::to_string_pretty(&ast).expect("Failed to serialize AST");
+                println!("{}", ast_json);
+                ExitCode::SUCCESS
+            }
+            Err(code) => code,
+        },
+        Some(Command::Repl) => {
+            // 💬 Start an interactive session
+            ExitCode::from(Repl::new().run())
         }
-        2 => {
-            if args[1] == "info" {
-                // ℹ️ Print version info if "info" command is provided
-                print_version_info();
-            } else {
-                // 🚨 Invalid command usage
-                eprintln!("Usage: Glint [command] [options]");
+        Some(Command::Lsp) => match glint::lsp::run_stdio() {
+            // 🧭 Serve editor requests until the client disconnects
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Language server error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Some(Command::Dap) => match glint::dap::run_stdio() {
+            // 🐞 Serve the debug session until the client disconnects
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Debug adapter error: {}", err);
+                ExitCode::FAILURE
+            }
+        },
+        Some(Command::Debug { file, script_args }) => debug(&file, &script_args),
+        Some(Command::Build { file, output }) => build(&file, output),
+        Some(Command::Lint(args)) => lint(&args),
+        Some(Command::Fmt { files, check }) => fmt(&files, check),
+        Some(Command::Test { paths, bless }) => test(&paths, bless),
+        Some(Command::Bench {
+            paths,
+            warmup,
+            runs,
+        }) => bench(&paths, warmup, runs as usize),
+        Some(Command::Script(args)) => {
+            // 📜 `glint script.glt a b` (what a `#!/usr/bin/env glint` line expands to)
+            let (file, script_args) = args.split_first().expect("external subcommand name");
+            if !Path::new(file).is_file() {
+                eprintln!("error: '{}' is neither a command nor a script", file);
+                eprintln!("For more information, try '--help'.");
+                return ExitCode::from(2);
             }
+            run(&RunArgs {
+                file: Some(PathBuf::from(file)),
+                jit_stats: false,
+                profile: false,
+                profile_out: None,
+                script_args: script_args.to_vec(),
+            })
         }
-        3 | 4 => {
-            if args[1] == "run" {
-                let filename = &args[2]; // 📝 Get filename to run the script
-                let mut dev_flag = false;
+        Some(Command::Completions { shell }) => {
+            // 🐚 Print a completion script for the requested shell
+            clap_complete::generate(shell, &mut Cli::command(), "glint", &mut io::stdout());
+            ExitCode::SUCCESS
+        }
+    }
+}
 
-                if args.len() == 4 && args[3] == "-dev" {
-                    dev_flag = true; // 🛠️ Developer flag detected
-                }
+/// ▶️ `glint run`: parses and interprets a script
+fn run(args: &RunArgs) -> ExitCode {
+    if args.jit_stats && cfg!(not(feature = "jit")) {
+        eprintln!("--jit-stats requires Glint to be built with `--features jit`");
+        return ExitCode::FAILURE;
+    }
 
-                // 📊 Print developer info before processing the file if -dev is present
-                if dev_flag {
-                    print_dev_info(start_time);
+    // 🎬 Without a script, run the entry point of the project we are in
+    let file = match &args.file {
+        Some(file) => file.clone(),
+        None => match Manifest::find(Path::new(".")) {
+            Ok(Some(manifest)) => manifest.entry_path(),
+            Ok(None) => {
+                eprintln!(
+                    "No script given and no {} found in this directory or its parents",
+                    manifest::MANIFEST_FILE
+                );
+                return ExitCode::FAILURE;
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    match load_program(&file).and_then(|ast| link(&file, ast)) {
+        Ok(ast) => execute(
+            &ast,
+            &args.script_args,
+            args.jit_stats,
+            args.profile,
+            args.profile_out.as_deref(),
+        ),
+        Err(code) => code,
+    }
+}
+
+/// 🧠 Interprets a parsed program and turns the outcome into the process exit status
+#[cfg_attr(not(feature = "jit"), allow(unused_variables))]
+fn execute(
+    ast: &AST,
+    script_args: &[String],
+    jit_stats: bool,
+    profile: bool,
+    profile_out: Option<&Path>,
+) -> ExitCode {
+    // 🧩 Serialize the AST to a JSON string and load it into the interpreter
+    let mut interpreter = Interpreter::new();
+    interpreter.set_args(script_args);
+    dev::phase("serialize", || {
+        let ast_json = serde_json::to_string(&ast).expect("Failed to serialize AST");
+        interpreter.load_from_json(&ast_json);
+    });
+    let profiler = (profile || profile_out.is_some()).then(|| Profile::attach(&mut interpreter));
+    let result = dev::phase("interpret", || interpreter.interpret());
+
+    // ⚡ Report what the JIT compiled
+    #[cfg(feature = "jit")]
+    if jit_stats {
+        eprintln!("{}", interpreter.jit_report());
+    }
+
+    // ⏱️ Report where the time went
+    if let Some(profiler) = profiler {
+        let mut profiler = profiler.borrow_mut();
+        profiler.finish();
+        if profile {
+            eprint!("{}", profiler.table());
+        }
+        if let Some(path) = profile_out {
+            if let Err(err) = fs::write(path, profiler.folded()) {
+                eprintln!("Error writing {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    exit_status(result)
+}
+
+/// 🚦 Turns the outcome of a program into the process exit status, reporting runtime errors
+fn exit_status(result: Result<(), RuntimeError>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // 🚪 The script chose its own exit status (exit() only accepts 0-255)
+        Err(RuntimeError::Exit { code }) => ExitCode::from(code),
+        Err(err) => {
+            eprintln!("Runtime error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 🐞 `glint debug`: runs a script paused before its first statement, taking commands from stdin
+fn debug(file: &Path, script_args: &[String]) -> ExitCode {
+    let (name, input) = match read_source(file) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+    let ast = match parse_source(&name, &input).and_then(|ast| link(file, ast)) {
+        Ok(ast) => ast,
+        Err(code) => return code,
+    };
+    println!("Debugging {}. Type help for commands.", name);
+    exit_status(glint::console::debug(
+        &input,
+        &ast,
+        script_args,
+        io::stdin().lock(),
+        io::stdout(),
+    ))
+}
+
+/// 🔍 `glint check`: parses every file and runs the static checks, reporting each finding
+fn check(files: &[PathBuf]) -> ExitCode {
+    let mut failed = false;
+    for file in files {
+        // A built .gltc has no source text, so findings point at whole statements
+        let (name, source) = if file.extension().is_some_and(|ext| ext == "gltc") {
+            (file.display().to_string(), None)
+        } else {
+            match read_source(file) {
+                Ok((name, source)) => (name, Some(source)),
+                Err(_) => {
+                    failed = true;
+                    continue;
+                }
+            }
+        };
+        let (ast, source) = if let Some(source) = &source {
+            match parse_program(source) {
+                Ok(ast) => (ast, Some(source.as_str())),
+                Err(err) => {
+                    let (line, column) = err.location();
+                    let message = match err {
+                        ParseError::UnknownToken { token, .. } => {
+                            format!("Unknown token '{}'", token)
+                        }
+                        ParseError::SyntaxError { message, .. } => {
+                            format!("Syntax error: {}", message)
+                        }
+                        err => err.to_string(),
+                    };
+                    print_finding(&name, line, column, Severity::Error, &message);
+                    failed = true;
+                    continue;
+                }
+            }
+        } else {
+            match load_program(file) {
+                Ok(ast) => (ast, None),
+                Err(_) => {
+                    failed = true;
+                    continue;
                 }
+            }
+        };
 
-                // 📂 Read the script file
-                let input = match fs::read_to_string(filename) {
-                    Ok(contents) => contents,
-                    Err(err) => {
-                        // 🚨 Handle file read errors
-                        eprintln!("Error reading file: {}", err);
-                        return;
-                    }
-                };
-
-                // 🔍 Parse the script
-                match parse_program(&input) {
-                    Ok(ast) => {
-                        // 🧩 Serialize the AST to a JSON string
-                        let ast_json =
-                            serde_json::to_string_pretty(&ast).expect("Failed to serialize AST");
-
-                        // 🧩 Serialize the AST to CBOR format
-                        // let ast_cbor =
-                        //     serde_cbor::to_vec(&ast).expect("Failed to serialize AST to CBOR");
-                        // let deserialized_ast: AST =
-                        //     serde_cbor::from_slice(&ast_cbor).expect("Failed to deserialize CBOR");
-
-                        println!("{}", ast_json);
-                        // 🧠 Call the interpreter function with the JSON string
-                        interpret_from_json(&ast_json);
-                    }
-                    // 🚨 Handle parsing errors
-                    Err(ParseError::UnknownToken { token, line }) => {
-                        eprintln!("Unknown token '{}' on line {}", token, line);
-                    }
-                    Err(ParseError::IoError(err)) => {
-                        eprintln!("IO Error: {}", err);
-                    }
-                    Err(ParseError::SyntaxError { message, line }) => {
-                        eprintln!("Syntax error on line {}: {}", line, message);
-                    }
-                    Err(ParseError::NomError(_)) => {
-                        eprintln!("Parsing error occurred.");
+        let diagnostics = check_program(&ast, source);
+        for diagnostic in &diagnostics {
+            let span = diagnostic.span;
+            print_finding(
+                &name,
+                span.line,
+                span.column,
+                diagnostic.severity,
+                &diagnostic.message,
+            );
+        }
+        if diagnostics.is_empty() {
+            println!("{}: {}", name, "ok".green());
+        }
+        failed |= diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 🧹 `glint lint`: runs the lint rules on every file and prints their findings
+fn lint(args: &LintArgs) -> ExitCode {
+    if args.rules {
+        for rule in RULES {
+            let level = format!("{:?}", rule.default).to_lowercase();
+            println!("{:<26} {:<6} {}", rule.name, level, rule.description);
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut failed = false;
+    for file in &args.files {
+        let config = match lint_config(args, file) {
+            Ok(config) => config,
+            Err(message) => {
+                eprintln!("{}", message);
+                return ExitCode::FAILURE;
+            }
+        };
+        let (name, source) = match read_source(file) {
+            Ok(source) => source,
+            Err(_) => {
+                failed = true;
+                continue;
+            }
+        };
+        let Ok(ast) = parse_source(&name, &source) else {
+            failed = true;
+            continue;
+        };
+
+        for diagnostic in lint_program(&ast, Some(&source), &config) {
+            let span = diagnostic.span;
+            let message = match diagnostic.rule {
+                Some(rule) => format!("{} [{}]", diagnostic.message, rule),
+                None => diagnostic.message,
+            };
+            print_finding(&name, span.line, span.column, diagnostic.severity, &message);
+            failed |= diagnostic.severity == Severity::Error;
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// ⚙️ Builds the lint configuration: `--config` (or the nearest glint.toml), then `-A`/`-W`/`-D`
+fn lint_config(args: &LintArgs, file: &Path) -> Result<LintConfig, String> {
+    let mut config = match &args.config {
+        Some(path) => LintConfig::from_file(path)?,
+        None => LintConfig::for_script(file)?,
+    };
+    for (rules, level) in [
+        (&args.allow, Level::Allow),
+        (&args.warn, Level::Warn),
+        (&args.deny, Level::Deny),
+    ] {
+        for rule in rules {
+            config.set(rule, level)?;
+        }
+    }
+    Ok(config)
+}
+
+/// 🚩 Prints a finding as `file:line:col: severity: message`
+fn print_finding(name: &str, line: usize, column: usize, severity: Severity, message: &str) {
+    let severity = match severity {
+        Severity::Error => "error".red().bold(),
+        Severity::Warning => "warning".yellow().bold(),
+    };
+    println!("{}:{}:{}: {}: {}", name, line, column, severity, message);
+}
+
+/// 🎨 `glint fmt`: rewrites scripts in canonical form, or with `--check` lists the ones that differ
+fn fmt(files: &[PathBuf], check: bool) -> ExitCode {
+    let mut failed = false;
+    for file in files {
+        let input = match fs::read_to_string(file) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("Error reading {}: {}", file.display(), err);
+                failed = true;
+                continue;
+            }
+        };
+        // Parse errors are reported the same way as by `run`
+        if parse_source(&file.display().to_string(), &input).is_err() {
+            failed = true;
+            continue;
+        }
+        let formatted = format_source(&input).expect("source parsed above");
+        if formatted == input {
+            continue;
+        }
+
+        if check {
+            println!("{}: {}", file.display(), "not formatted".yellow());
+            failed = true;
+        } else if let Err(err) = fs::write(file, formatted) {
+            eprintln!("Error writing {}: {}", file.display(), err);
+            failed = true;
+        } else {
+            println!("Formatted {}", file.display());
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 📦 `glint build`: stores the parsed AST as CBOR so it can be run without reparsing
+fn build(file: &Path, output: Option<PathBuf>) -> ExitCode {
+    let ast = match load_program(file).and_then(|ast| link(file, ast)) {
+        Ok(ast) => ast,
+        Err(code) => return code,
+    };
+    let output = output.unwrap_or_else(|| file.with_extension("gltc"));
+    let ast_cbor = serde_cbor::to_vec(&ast).expect("Failed to serialize AST to CBOR");
+    match fs::write(&output, ast_cbor) {
+        Ok(()) => {
+            println!("Built {}", output.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Error writing {}: {}", output.display(), err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 🥇 `glint test`: runs the `test` blocks of scripts that have them, and every other script
+/// as a golden test whose output is diffed against the expected output
+fn test(paths: &[PathBuf], bless: bool) -> ExitCode {
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from("tests")]
+    } else {
+        paths.to_vec()
+    };
+    let tests = match collect_programs(&paths) {
+        Ok(tests) => tests,
+        Err(err) => {
+            eprintln!("Error reading tests: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let glint = match std::env::current_exe() {
+        Ok(glint) => glint,
+        Err(err) => {
+            eprintln!("Cannot find the glint executable: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut passed, mut failed) = (0, 0);
+    for test in &tests {
+        let blocks = fs::read_to_string(test)
+            .ok()
+            .and_then(|source| parse_program(&source).ok())
+            .and_then(|ast| {
+                let search_path = manifest::search_path_for(Some(test)).ok()?;
+                link_program(ast, Some(test), &search_path).ok()
+            })
+            .map(|ast| run_tests(&ast))
+            .unwrap_or_default();
+        if !blocks.is_empty() {
+            // Test blocks have no expected output to bless
+            if !bless {
+                for result in blocks {
+                    if report_test_block(test, result) {
+                        passed += 1;
+                    } else {
+                        failed += 1;
                     }
                 }
-            } else {
-                // 🚨 Invalid usage for the "run" command
-                eprintln!("Usage: Glint run <filename>.glt [-dev]");
             }
+            continue;
         }
-        _ => {
-            // 🚨 Handle incorrect number of arguments
-            eprintln!("Usage: Glint [command] [options]");
+
+        match run_golden(&glint, test, bless) {
+            Ok(_) if bless => {
+                println!("{} {}", "BLESSED".yellow(), test.display());
+                passed += 1;
+            }
+            Ok(outcome) if outcome.passed => {
+                println!("{} {}", "PASS".green(), test.display());
+                passed += 1;
+            }
+            Ok(outcome) => {
+                println!("{} {}", "FAIL".red(), test.display());
+                for diff in outcome.diffs {
+                    print!("{}", diff);
+                }
+                failed += 1;
+            }
+            Err(err) => {
+                println!("{} {}: {}", "FAIL".red(), test.display(), err);
+                failed += 1;
+            }
         }
     }
+
+    if bless {
+        println!("\n{} blessed, {} failed", passed, failed);
+    } else {
+        println!("\n{} passed, {} failed", passed, failed);
+    }
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 🧪 Prints the result of one `test` block (with where it failed and what it wrote); returns whether it passed
+fn report_test_block(file: &Path, result: TestResult) -> bool {
+    let Some(failure) = result.failure else {
+        println!("{} {}: {}", "PASS".green(), file.display(), result.name);
+        return true;
+    };
+    println!("{} {}: {}", "FAIL".red(), file.display(), result.name);
+    // A failure inside an imported module is located in that module's file
+    let location = failure.file.unwrap_or_else(|| file.display().to_string());
+    match failure.column {
+        Some(column) => println!(
+            "  {}:{}:{}: {}",
+            location, failure.line, column, failure.message
+        ),
+        None => println!("  {}:{}: {}", location, failure.line, failure.message),
+    }
+    for line in result.output.lines() {
+        println!("  | {}", line);
+    }
+    false
+}
+
+/// 🏎️ `glint bench`: times every benchmark program and prints a table of the results
+fn bench(paths: &[PathBuf], warmup: usize, runs: usize) -> ExitCode {
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from("benches")]
+    } else {
+        paths.to_vec()
+    };
+    let programs = match collect_programs(&paths) {
+        Ok(programs) if !programs.is_empty() => programs,
+        Ok(_) => {
+            eprintln!("No .glt benchmarks found");
+            return ExitCode::FAILURE;
+        }
+        Err(err) => {
+            eprintln!("Error reading benchmarks: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if cfg!(debug_assertions) {
+        eprintln!("note: this glint is a debug build; use `cargo run --release` for meaningful numbers");
+    }
+
+    let width = programs
+        .iter()
+        .map(|path| bench_name(path).chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Benchmark".len());
+    println!(
+        "{:<width$}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "Benchmark", "Runs", "Mean", "± Std", "Min", "Median", "Max"
+    );
+
+    let mut failed = false;
+    for path in &programs {
+        let name = bench_name(path);
+        let result = read_source(path)
+            .and_then(|(source_name, input)| parse_source(&source_name, &input))
+            .and_then(|ast| link(path, ast))
+            .and_then(|ast| {
+                bench_program(&ast, warmup, runs).map_err(|err| {
+                    eprintln!("{}: Runtime error: {}", name, err);
+                    ExitCode::FAILURE
+                })
+            });
+        match result {
+            Ok(stats) => println!(
+                "{:<width$}  {:>5}  {:>12}  {:>12}  {:>12}  {:>12}  {:>12}",
+                name,
+                stats.runs,
+                millis(stats.mean),
+                millis(stats.std_dev),
+                millis(stats.min),
+                millis(stats.median),
+                millis(stats.max)
+            ),
+            Err(_) => failed = true,
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 🏷️ A benchmark's name: its file name without the extension
+fn bench_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// 📂 Reads and parses a script (or loads a .gltc built by `glint build`), reporting errors
+fn load_program(path: &Path) -> Result<AST, ExitCode> {
+    if path.extension().is_some_and(|ext| ext == "gltc") {
+        let bytes = dev::phase("read", || fs::read(path)).map_err(|err| {
+            eprintln!("Error reading file: {}", err);
+            ExitCode::FAILURE
+        })?;
+        let ast: AST = dev::phase("parse", || serde_cbor::from_slice(&bytes)).map_err(|err| {
+            eprintln!("{}: invalid .gltc file: {}", path.display(), err);
+            ExitCode::FAILURE
+        })?;
+        dev::record_ast(&ast);
+        return Ok(ast);
+    }
+
+    let (name, input) = read_source(path)?;
+    parse_source(&name, &input)
+}
+
+/// 📦 Resolves the imports of a program read from `path` (`-` for stdin), reporting errors
+fn link(path: &Path, ast: AST) -> Result<AST, ExitCode> {
+    let file = (path != Path::new("-")).then_some(path);
+    dev::phase("link", || {
+        let search_path = manifest::search_path_for(file)?;
+        link_program(ast, file, &search_path).map_err(|err| err.to_string())
+    })
+    .map_err(|err| {
+        eprintln!("{}", err);
+        ExitCode::FAILURE
+    })
+}
+
+/// 🏗️ `glint new`: scaffolds a project
+fn new_project(path: &Path) -> ExitCode {
+    match manifest::scaffold(path) {
+        Ok(()) => {
+            println!("Created project {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 📂 Reads a script (`-` reads the program from stdin); returns its display name and contents
+fn read_source(path: &Path) -> Result<(String, String), ExitCode> {
+    let input = dev::phase("read", || {
+        if path == Path::new("-") {
+            io::read_to_string(io::stdin())
+        } else {
+            fs::read_to_string(path)
+        }
+    })
+    .map_err(|err| {
+        // 🚨 Handle file read errors
+        eprintln!("Error reading file: {}", err);
+        ExitCode::FAILURE
+    })?;
+
+    let name = if path == Path::new("-") {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    };
+    Ok((name, input))
+}
+
+/// 🔍 Parses source code, reporting errors prefixed with `name`
+fn parse_source(name: &str, input: &str) -> Result<AST, ExitCode> {
+    let ast = dev::phase("parse", || parse_program(input));
+    if let Ok(ast) = &ast {
+        dev::record_ast(ast);
+    }
+    ast.map_err(|err| {
+        eprint!("{}: ", name);
+        match err {
+            // 🚨 Handle parsing errors
+            err @ ParseError::UnknownToken { .. } => {
+                eprintln!("{}", err);
+            }
+            ParseError::IoError(err) => {
+                eprintln!("IO Error: {}", err);
+            }
+            ParseError::SyntaxError { message, line } => {
+                eprintln!("Syntax error on line {}: {}", line, message);
+            }
+            ParseError::NomError(_) => {
+                eprintln!("Parsing error occurred.");
+            }
+        }
+        ExitCode::FAILURE
+    })
 }
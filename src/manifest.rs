@@ -0,0 +1,219 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::modules::search_path_from_env;
+
+// 📜 Project manifests (`glint.toml`): name, version, entry point, sources and dependencies
+
+pub const MANIFEST_FILE: &str = "glint.toml";
+
+// 📜 A parsed `glint.toml` that describes a project (a `[package]` table)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    // The directory holding the manifest; every path in it is relative to this
+    pub root: PathBuf,
+    pub name: String,
+    pub version: String,
+    // The script `glint run` runs when no file is given
+    pub entry: PathBuf,
+    // Directories searched for imported modules
+    pub sources: Vec<PathBuf>,
+    // Other projects (or plain module directories) whose modules can be imported, by name
+    pub dependencies: BTreeMap<String, PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct File {
+    package: Option<Package>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, Dependency>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Package {
+    name: String,
+    version: String,
+    #[serde(default = "default_entry")]
+    entry: PathBuf,
+    #[serde(default = "default_sources")]
+    sources: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Dependency {
+    path: PathBuf,
+}
+
+fn default_entry() -> PathBuf {
+    PathBuf::from("src/main.glt")
+}
+
+fn default_sources() -> Vec<PathBuf> {
+    vec![PathBuf::from("src")]
+}
+
+impl Manifest {
+    /// 📄 Parses the text of a `glint.toml` found in `root`; `None` if it has no `[package]`
+    /// (a manifest may only configure the linter)
+    pub fn from_toml(text: &str, root: &Path) -> Result<Option<Manifest>, String> {
+        let file: File = toml::from_str(text).map_err(|err| err.to_string())?;
+        let Some(package) = file.package else {
+            return Ok(None);
+        };
+        Ok(Some(Manifest {
+            root: root.to_path_buf(),
+            name: package.name,
+            version: package.version,
+            entry: package.entry,
+            sources: package.sources,
+            dependencies: file
+                .dependencies
+                .into_iter()
+                .map(|(name, dependency)| (name, dependency.path))
+                .collect(),
+        }))
+    }
+
+    /// 📄 Reads the `glint.toml` in `root`
+    pub fn from_dir(root: &Path) -> Result<Option<Manifest>, String> {
+        let path = root.join(MANIFEST_FILE);
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("Error reading {}: {}", path.display(), err))?;
+        Manifest::from_toml(&text, root).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// 🔎 The project `dir` belongs to: the closest `glint.toml` with a `[package]` in it or a parent
+    pub fn find(dir: &Path) -> Result<Option<Manifest>, String> {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        for root in dir.ancestors() {
+            if root.join(MANIFEST_FILE).is_file() {
+                if let Some(manifest) = Manifest::from_dir(root)? {
+                    return Ok(Some(manifest));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// 🎬 The entry point's path
+    pub fn entry_path(&self) -> PathBuf {
+        self.root.join(&self.entry)
+    }
+
+    /// 🗺️ Where the project's imports are looked up: its source directories, then those of
+    /// its dependencies (a dependency without a manifest is a directory of modules itself)
+    pub fn search_path(&self) -> Result<Vec<PathBuf>, String> {
+        let mut path = Vec::new();
+        let mut visited = vec![self.root.clone()];
+        self.collect_search_path(&mut path, &mut visited)?;
+        Ok(path)
+    }
+
+    fn collect_search_path(
+        &self,
+        path: &mut Vec<PathBuf>,
+        visited: &mut Vec<PathBuf>,
+    ) -> Result<(), String> {
+        path.extend(self.sources.iter().map(|dir| self.root.join(dir)));
+        for (name, dependency) in &self.dependencies {
+            let root = self.root.join(dependency);
+            let root = root.canonicalize().map_err(|_| {
+                format!(
+                    "{}: dependency '{}' not found at {}",
+                    self.root.join(MANIFEST_FILE).display(),
+                    name,
+                    root.display()
+                )
+            })?;
+            // Dependencies may share dependencies, or depend on each other
+            if visited.contains(&root) {
+                continue;
+            }
+            visited.push(root.clone());
+            match root
+                .join(MANIFEST_FILE)
+                .is_file()
+                .then(|| Manifest::from_dir(&root))
+                .transpose()?
+                .flatten()
+            {
+                Some(manifest) => manifest.collect_search_path(path, visited)?,
+                None => path.push(root),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 🗺️ The module search path for a script (`None` for code without a file): the search path
+/// of the project it belongs to (found from the current directory for code without a file),
+/// then `GLINT_PATH`
+pub fn search_path_for(script: Option<&Path>) -> Result<Vec<PathBuf>, String> {
+    let dir = match script.and_then(Path::parent) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let mut path = match Manifest::find(&dir)? {
+        Some(manifest) => manifest.search_path()?,
+        None => Vec::new(),
+    };
+    path.extend(search_path_from_env());
+    Ok(path)
+}
+
+/// 🏗️ Creates a new project in `dir`, named after it: a manifest, an entry point that imports
+/// a module, and tests; fails if `dir` already exists
+pub fn scaffold(dir: &Path) -> Result<(), String> {
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "'{}' is not a valid project name (use letters, digits, '_' and '-')",
+            name
+        ));
+    }
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    let files = [
+        (
+            MANIFEST_FILE,
+            format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nentry = \"src/main.glt\"\nsources = [\"src\"]\n\n[dependencies]\n",
+                name
+            ),
+        ),
+        (
+            "src/main.glt",
+            "import greeting\n\nwrite greeting.hello(\"world\")\n".to_string(),
+        ),
+        (
+            "src/greeting.glt",
+            "hello(name) {\n    return \"Hello, \" + name + \"!\"\n}\n".to_string(),
+        ),
+        (
+            "tests/greeting_test.glt",
+            "import greeting\n\ntest \"hello greets by name\" {\n    assert greeting.hello(\"Glint\") = \"Hello, Glint!\"\n}\n".to_string(),
+        ),
+        (".gitignore", "*.gltc\n".to_string()),
+    ];
+    for (file, contents) in files {
+        let path = dir.join(file);
+        let parent = path.parent().unwrap_or(dir);
+        fs::create_dir_all(parent)
+            .and_then(|()| fs::write(&path, contents))
+            .map_err(|err| format!("Error writing {}: {}", path.display(), err))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,563 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::ast::{Span, AST};
+use crate::error::ImportError;
+use crate::parser::parser::parse_program;
+
+// 📦 Multi-file programs: links the modules a program imports into a single program
+
+// Extra directories to look for modules in, separated like `PATH`
+pub const SEARCH_PATH_VAR: &str = "GLINT_PATH";
+
+/// 🗺️ The directories listed in `GLINT_PATH`
+pub fn search_path_from_env() -> Vec<PathBuf> {
+    std::env::var_os(SEARCH_PATH_VAR)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+/// 📦 Resolves the imports of a program parsed from `file` (`None` for code without a file,
+/// whose imports are resolved from the current directory). A module is looked up next to the
+/// file importing it, then in each directory of `search_path`. Every module is parsed once and
+/// its top-level code is put where it is first imported, as a `Module` node whose names are
+/// qualified with the module's namespace (`utils.helper`)
+pub fn link_program(
+    ast: AST,
+    file: Option<&Path>,
+    search_path: &[PathBuf],
+) -> Result<AST, ImportError> {
+    let statements = match ast {
+        AST::Program(statements) => statements,
+        other => vec![other],
+    };
+    let mut linker = Linker {
+        search_path: search_path.to_vec(),
+        modules: HashMap::new(),
+        chain: Vec::new(),
+    };
+    let name = match file {
+        Some(file) => {
+            let name = file.display().to_string();
+            linker.chain.push((canonical(file), name.clone()));
+            name
+        }
+        None => "<program>".to_string(),
+    };
+    let dir = file.and_then(Path::parent).unwrap_or(Path::new(""));
+    Ok(AST::Program(linker.link(statements, &name, dir, None)?))
+}
+
+/// 🏷️ The name an `import` makes a module known by: `utils` for `import utils` and `import "lib/utils.glt"`
+pub fn import_name(module: &AST) -> Option<String> {
+    match module {
+        AST::Identifier(name) => Some(name.clone()),
+        AST::String(path) => Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+struct Linker {
+    search_path: Vec<PathBuf>,
+    // Every module loaded so far (by canonical path) and its namespace
+    modules: HashMap<PathBuf, String>,
+    // The files being linked, outermost first, as canonical paths and display names
+    chain: Vec<(PathBuf, String)>,
+}
+
+// 🧩 A top-level statement of a file being linked
+enum Item {
+    // An import, replaced by the module's code the first time the module is imported
+    Import(Option<AST>),
+    Code(AST),
+}
+
+impl Linker {
+    /// 🔗 Links the statements of one file: loads what it imports, then qualifies its names
+    fn link(
+        &mut self,
+        statements: Vec<AST>,
+        file: &str,
+        dir: &Path,
+        namespace: Option<&str>,
+    ) -> Result<Vec<AST>, ImportError> {
+        // Imports come first, since hoisted functions may use a module imported below them
+        let mut aliases = HashMap::new();
+        let mut items = Vec::new();
+        for statement in statements {
+            let (span, node) = unlocated(&statement);
+            match node {
+                AST::Import(module) => {
+                    let (alias, module_namespace, code) = self.import(module, file, dir, span)?;
+                    aliases.insert(alias, module_namespace);
+                    items.push(Item::Import(code));
+                }
+                // A module's tests belong to the module, not to the programs importing it
+                AST::Test { .. } if namespace.is_some() => {}
+                _ => {
+                    if let Some(line) = nested_import(node, span) {
+                        return Err(ImportError::NotTopLevel {
+                            file: file.to_string(),
+                            line,
+                        });
+                    }
+                    items.push(Item::Code(statement));
+                }
+            }
+        }
+
+        let code: Vec<&AST> = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Code(statement) => Some(statement),
+                Item::Import(_) => None,
+            })
+            .collect();
+        let names = Names {
+            namespace,
+            file: namespace.map(|_| file),
+            aliases: &aliases,
+            functions: namespace.map(|_| functions(&code)).unwrap_or_default(),
+            globals: namespace.map(|_| globals(&code)).unwrap_or_default(),
+        };
+        let no_locals = HashSet::new();
+        Ok(items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Import(code) => code,
+                Item::Code(statement) => Some(names.rename(statement, &no_locals)),
+            })
+            .collect())
+    }
+
+    /// 📥 Finds and loads an imported module; returns the name the importer knows it by, its
+    /// namespace and, if this is its first import, its linked code
+    fn import(
+        &mut self,
+        module: &AST,
+        importer: &str,
+        dir: &Path,
+        span: Span,
+    ) -> Result<(String, String, Option<AST>), ImportError> {
+        let alias = import_name(module).unwrap_or_default();
+        let (module, relative) = match module {
+            AST::String(path) => (path.clone(), PathBuf::from(path)),
+            AST::Identifier(name) => (name.clone(), PathBuf::from(format!("{}.glt", name))),
+            other => (format!("{:?}", other), PathBuf::new()),
+        };
+
+        let searched: Vec<PathBuf> = std::iter::once(dir.to_path_buf())
+            .chain(self.search_path.iter().cloned())
+            .collect();
+        let Some(path) = searched
+            .iter()
+            .map(|dir| dir.join(&relative))
+            .find(|path| path.is_file())
+        else {
+            let searched = searched
+                .into_iter()
+                .map(|dir| {
+                    if dir.as_os_str().is_empty() {
+                        PathBuf::from(".")
+                    } else {
+                        dir
+                    }
+                })
+                .collect();
+            return Err(ImportError::NotFound {
+                module,
+                importer: importer.to_string(),
+                searched,
+            });
+        };
+
+        let canonical = canonical(&path);
+        let name = path.display().to_string();
+        if self.chain.iter().any(|(file, _)| *file == canonical) {
+            let mut chain: Vec<String> = self.chain.iter().map(|(_, name)| name.clone()).collect();
+            chain.push(name);
+            return Err(ImportError::Cycle { chain });
+        }
+        if let Some(namespace) = self.modules.get(&canonical) {
+            return Ok((alias, namespace.clone(), None));
+        }
+
+        let source = fs::read_to_string(&path).map_err(|error| ImportError::IoError {
+            file: name.clone(),
+            error,
+        })?;
+        let statements = match parse_program(&source) {
+            Ok(AST::Program(statements)) => statements,
+            Ok(other) => vec![other],
+            Err(error) => {
+                return Err(ImportError::ParseError { file: name, error });
+            }
+        };
+
+        let namespace = self.namespace(&alias);
+        self.modules.insert(canonical.clone(), namespace.clone());
+        self.chain.push((canonical, name.clone()));
+        let module_dir = path.parent().unwrap_or(Path::new(""));
+        let body = self.link(statements, &name, module_dir, Some(&namespace))?;
+        self.chain.pop();
+
+        let code = AST::Located {
+            span,
+            file: None,
+            node: Box::new(AST::Module {
+                name: namespace.clone(),
+                body: Box::new(AST::Block(body)),
+            }),
+        };
+        Ok((alias, namespace, Some(code)))
+    }
+
+    /// 🏷️ A namespace for a new module: its name, numbered if another module already has it
+    fn namespace(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.modules.values().any(|used| used == candidate);
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}{}", name, n))
+            .find(|candidate| !taken(candidate))
+            .expect("an unused namespace")
+    }
+}
+
+// 🏷️ How the names of one file are rewritten when it is linked
+struct Names<'a> {
+    // The file's own namespace (`None` for the program being run)
+    namespace: Option<&'a str>,
+    // The name of the file its statements are located in (only for modules)
+    file: Option<&'a str>,
+    // The modules the file imported and their namespaces
+    aliases: &'a HashMap<String, String>,
+    // The functions (and structs) and globals the file defines (only for modules)
+    functions: HashSet<String>,
+    globals: HashSet<String>,
+}
+
+impl Names<'_> {
+    /// ✏️ Rewrites the names in a node; `locals` holds the parameters of the function it is in
+    fn rename(&self, node: AST, locals: &HashSet<String>) -> AST {
+        let rename = |node: AST| self.rename(node, locals);
+        let rename_box = |node: Box<AST>| Box::new(self.rename(*node, locals));
+        let rename_all = |nodes: Vec<AST>| nodes.into_iter().map(rename).collect();
+        match node {
+            AST::Located { span, file, node } => AST::Located {
+                span,
+                file: file.or_else(|| self.file.map(str::to_string)),
+                node: rename_box(node),
+            },
+            AST::Program(nodes) => AST::Program(rename_all(nodes)),
+            AST::Block(nodes) => AST::Block(rename_all(nodes)),
+            AST::Write(nodes) => AST::Write(rename_all(nodes)),
+            AST::Array(nodes) => AST::Array(rename_all(nodes)),
+            AST::Tuple(nodes) => AST::Tuple(rename_all(nodes)),
+            AST::Alternatives(nodes) => AST::Alternatives(rename_all(nodes)),
+            AST::Identifier(name) => AST::Identifier(self.name(&name, locals)),
+            AST::VariableAssign { name, value } => AST::VariableAssign {
+                name: self.name(&name, locals),
+                value: rename_box(value),
+            },
+            AST::Destructure { names, value } => AST::Destructure {
+                names: names.iter().map(|name| self.name(name, locals)).collect(),
+                value: rename_box(value),
+            },
+            AST::FunctionCall { name, args } => AST::FunctionCall {
+                name: self.name(&name, locals),
+                args: rename_all(args),
+            },
+            AST::Function { name, args, body } => AST::Function {
+                name: self.name(&name, &HashSet::new()),
+                body: Box::new(self.rename(*body, &args.param_names().into_iter().collect())),
+                args,
+            },
+            AST::Struct { name, fields } => AST::Struct {
+                name: self.name(&name, &HashSet::new()),
+                // A field's type may be a struct of this module or an imported one
+                fields: fields
+                    .into_iter()
+                    .map(|(field, ty)| (field, ty.map(|ty| self.name(&ty, &HashSet::new()))))
+                    .collect(),
+            },
+            AST::Impl { name, methods } => AST::Impl {
+                name: self.name(&name, &HashSet::new()),
+                methods: methods
+                    .into_iter()
+                    .map(|method| self.method(method))
+                    .collect(),
+            },
+            AST::Construct { name, fields } => AST::Construct {
+                name: self.name(&name, locals),
+                fields: fields
+                    .into_iter()
+                    .map(|(field, value)| (field, rename(value)))
+                    .collect(),
+            },
+            AST::Lambda { args, body } => {
+                // A lambda sees the locals around it as well as its parameters
+                let mut locals = locals.clone();
+                locals.extend(args.param_names());
+                AST::Lambda {
+                    body: Box::new(self.rename(*body, &locals)),
+                    args,
+                }
+            }
+            AST::Call { callee, args } => AST::Call {
+                callee: rename_box(callee),
+                args: rename_all(args),
+            },
+            AST::Index { object, index } => AST::Index {
+                object: rename_box(object),
+                index: rename_box(index),
+            },
+            AST::Slice { object, start, end } => AST::Slice {
+                object: rename_box(object),
+                start: start.map(rename_box),
+                end: end.map(rename_box),
+            },
+            AST::Field { object, name } => AST::Field {
+                object: rename_box(object),
+                name,
+            },
+            AST::Assign { target, value } => AST::Assign {
+                target: rename_box(target),
+                value: rename_box(value),
+            },
+            AST::Range { start, end } => AST::Range {
+                start: rename_box(start),
+                end: rename_box(end),
+            },
+            AST::Return(value) => AST::Return(rename_box(value)),
+            AST::BinaryOp { left, op, right } => AST::BinaryOp {
+                left: rename_box(left),
+                op,
+                right: rename_box(right),
+            },
+            AST::Dictionary(pairs) => AST::Dictionary(
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| {
+                        // Bare identifiers used as keys are string keys, not names
+                        let key = match key {
+                            AST::Identifier(_) => key,
+                            key => rename(key),
+                        };
+                        (key, rename(value))
+                    })
+                    .collect(),
+            ),
+            AST::Coincide {
+                expr,
+                cases,
+                default,
+                comments,
+            } => AST::Coincide {
+                expr: rename_box(expr),
+                cases: cases
+                    .into_iter()
+                    .map(|(case, action)| (rename(case), rename(action)))
+                    .collect(),
+                default: default.map(rename_box),
+                comments,
+            },
+            AST::IfElse {
+                condition,
+                if_block,
+                else_block,
+            } => AST::IfElse {
+                condition: rename_box(condition),
+                if_block: rename_box(if_block),
+                else_block: else_block.map(rename_box),
+            },
+            AST::Test { name, body } => AST::Test {
+                name,
+                body: rename_box(body),
+            },
+            AST::Assert { condition, message } => AST::Assert {
+                condition: rename_box(condition),
+                message: message.map(rename_box),
+            },
+            node @ (AST::Import(_)
+            | AST::Module { .. }
+            | AST::FunctionArgs(_)
+            | AST::Integer(_)
+            | AST::Float(_)
+            | AST::Bool(_)
+            | AST::String(_)
+            | AST::TypePattern(_)
+            | AST::Comment(_)) => node,
+        }
+    }
+
+    /// 🔤 The linked name of a variable or function: the module's functions and globals get the
+    /// namespace, unless a parameter hides them
+    fn name(&self, name: &str, locals: &HashSet<String>) -> String {
+        if let Some(qualified) = self.qualified(name) {
+            return qualified;
+        }
+        // In `point.x` only `point` is a name; the rest are its fields
+        if let Some((head, fields)) = name.split_once('.') {
+            return format!("{}.{}", self.name(head, locals), fields);
+        }
+        match self.namespace {
+            Some(namespace)
+                if (self.functions.contains(name) || self.globals.contains(name))
+                    && !locals.contains(name) =>
+            {
+                format!("{}.{}", namespace, name)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// ✏️ Rewrites the names in a method of an `impl` block; unlike a function's, its own name
+    /// is left alone (it is only called through its type)
+    fn method(&self, method: AST) -> AST {
+        match method {
+            AST::Located { span, file, node } => AST::Located {
+                span,
+                file: file.or_else(|| self.file.map(str::to_string)),
+                node: Box::new(self.method(*node)),
+            },
+            AST::Function { name, args, body } => AST::Function {
+                name,
+                body: Box::new(self.rename(*body, &args.param_names().into_iter().collect())),
+                args,
+            },
+            other => other,
+        }
+    }
+
+    /// 🧭 `alias.name` with the alias replaced by the namespace of the module it imported
+    fn qualified(&self, name: &str) -> Option<String> {
+        let (alias, rest) = name.split_once('.')?;
+        let namespace = self.aliases.get(alias)?;
+        Some(format!("{}.{}", namespace, rest))
+    }
+}
+
+/// 🛠️ The functions and structs defined anywhere in these statements
+fn functions(statements: &[&AST]) -> HashSet<String> {
+    fn collect(node: &AST, found: &mut HashSet<String>) {
+        match node {
+            AST::Located { node, .. } => collect(node, found),
+            AST::Function { name, body, .. } => {
+                found.insert(name.clone());
+                collect(body, found);
+            }
+            AST::Struct { name, .. } => {
+                found.insert(name.clone());
+            }
+            AST::Block(statements) => statements.iter().for_each(|node| collect(node, found)),
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                collect(if_block, found);
+                if let Some(else_block) = else_block {
+                    collect(else_block, found);
+                }
+            }
+            AST::Coincide { cases, default, .. } => {
+                cases.iter().for_each(|(_, action)| collect(action, found));
+                if let Some(action) = default {
+                    collect(action, found);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut found = HashSet::new();
+    statements
+        .iter()
+        .for_each(|statement| collect(statement, &mut found));
+    found
+}
+
+/// 📋 The variables assigned at the top level of these statements (not inside functions)
+fn globals(statements: &[&AST]) -> HashSet<String> {
+    fn collect(node: &AST, found: &mut HashSet<String>) {
+        match node {
+            AST::Located { node, .. } => collect(node, found),
+            AST::VariableAssign { name, .. } => {
+                found.insert(name.clone());
+            }
+            AST::Destructure { names, .. } => found.extend(names.iter().cloned()),
+            AST::Block(statements) => statements.iter().for_each(|node| collect(node, found)),
+            AST::IfElse {
+                if_block,
+                else_block,
+                ..
+            } => {
+                collect(if_block, found);
+                if let Some(else_block) = else_block {
+                    collect(else_block, found);
+                }
+            }
+            AST::Coincide { cases, default, .. } => {
+                cases.iter().for_each(|(_, action)| collect(action, found));
+                if let Some(action) = default {
+                    collect(action, found);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut found = HashSet::new();
+    statements
+        .iter()
+        .for_each(|statement| collect(statement, &mut found));
+    found
+}
+
+/// 🚫 The line of an `import` nested inside a statement, if there is one
+fn nested_import(node: &AST, span: Span) -> Option<usize> {
+    let in_block = |block: &AST| match block {
+        AST::Block(statements) => statements.iter().find_map(|statement| {
+            let (span, node) = unlocated(statement);
+            match node {
+                AST::Import(_) => Some(span.line),
+                node => nested_import(node, span),
+            }
+        }),
+        _ => None,
+    };
+    match node {
+        AST::Function { body, .. } | AST::Test { body, .. } => in_block(body),
+        AST::IfElse {
+            if_block,
+            else_block,
+            ..
+        } => in_block(if_block).or_else(|| else_block.as_deref().and_then(in_block)),
+        AST::Coincide { cases, default, .. } => cases
+            .iter()
+            .map(|(_, action)| action)
+            .chain(default.as_deref())
+            .find_map(|action| {
+                let (action_span, action) = unlocated(action);
+                match action {
+                    AST::Import(_) => Some(action_span.line.max(span.line)),
+                    action => nested_import(action, action_span),
+                }
+            }),
+        _ => None,
+    }
+}
+
+fn unlocated(statement: &AST) -> (Span, &AST) {
+    match statement {
+        AST::Located { span, node, .. } => (*span, node.as_ref()),
+        other => (Span::default(), other),
+    }
+}
+
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
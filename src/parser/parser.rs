@@ -2,14 +2,14 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take_while, take_while1},
     character::complete::{char, digit1, multispace0, multispace1},
-    combinator::{map, map_res, opt, recognize},
-    multi::{many0, separated_list0},
+    combinator::{fail, map, map_res, opt, peek, recognize, verify},
+    multi::{many0, separated_list0, separated_list1},
     sequence::{delimited, preceded, separated_pair, tuple},
     IResult,
 };
 use std::str::FromStr;
 
-use crate::ast::AST;
+use crate::ast::{ArmComment, Span, AST};
 use crate::error::ParseError;
 
 // Parsing a string literal.
@@ -31,14 +31,27 @@ pub fn identifier(input: &str) -> IResult<&str, AST> {
     )(input)
 }
 
-// Parsing an integer literal.
+// Parsing a name that may be qualified by a module (`helper` or `utils.helper`).
+pub fn qualified_identifier(input: &str) -> IResult<&str, AST> {
+    let name = recognize(tuple((
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+        many0(preceded(
+            char('.'),
+            take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+        )),
+    )));
+    map(name, |id: &str| AST::Identifier(id.to_string()))(input)
+}
+
+// Parsing an integer literal (`5` or `-1`).
 pub fn integer(input: &str) -> IResult<&str, AST> {
-    map(map_res(digit1, |s: &str| i32::from_str(s)), AST::Integer)(input)
+    let integer_parser = recognize(tuple((opt(char('-')), digit1)));
+    map(map_res(integer_parser, |s: &str| i32::from_str(s)), AST::Integer)(input)
 }
 
 // Parsing a float literal.
 pub fn float(input: &str) -> IResult<&str, AST> {
-    let float_parser = recognize(tuple((digit1, tag("."), digit1)));
+    let float_parser = recognize(tuple((opt(char('-')), digit1, tag("."), digit1)));
     map(
         map_res(float_parser, |s: &str| f64::from_str(s)),
         AST::Float,
@@ -53,9 +66,35 @@ pub fn boolean(input: &str) -> IResult<&str, AST> {
     ))(input)
 }
 
-// Parsing a parenthesized expression.
+// Parsing a parenthesized expression, or a tuple literal (`(a, b)`, or `(a,)` with one item).
 pub fn parenthesized_expression(input: &str) -> IResult<&str, AST> {
-    delimited(tag("("), math_expression, tag(")"))(input)
+    let (input, _) = char('(')(input)?;
+    let (input, mut items) = separated_list1(
+        preceded(multispace0, char(',')),
+        preceded(multispace0, math_expression),
+    )(input)?;
+    let (input, trailing_comma) = opt(preceded(multispace0, char(',')))(input)?;
+    let (input, _) = preceded(multispace0, char(')'))(input)?;
+    if items.len() == 1 && trailing_comma.is_none() {
+        return Ok((input, items.remove(0)));
+    }
+    Ok((input, AST::Tuple(items)))
+}
+
+// Parsing the value of an assignment or `return`: one expression, or several separated by
+// commas, which make a tuple (`return q, r`).
+fn values(input: &str) -> IResult<&str, AST> {
+    let (input, mut items) = separated_list1(
+        preceded(multispace0, char(',')),
+        preceded(
+            multispace0,
+            alt((math_expression, string_literal, array_literal, dictionary_literal)),
+        ),
+    )(input)?;
+    if items.len() == 1 {
+        return Ok((input, items.remove(0)));
+    }
+    Ok((input, AST::Tuple(items)))
 }
 
 // Parsing an array literal.
@@ -89,18 +128,123 @@ pub fn dictionary_literal(input: &str) -> IResult<&str, AST> {
     Ok((input, AST::Dictionary(pairs)))
 }
 
-// Parsing a factor (a basic unit in an expression).
+// What can follow a factor: a call, an index, a slice or a field.
+enum Postfix {
+    Call(Vec<AST>),
+    Index(AST),
+    Slice(Option<AST>, Option<AST>),
+    Field(String),
+}
+
+impl Postfix {
+    // Applies the postfix to the expression before it.
+    fn apply(self, object: AST) -> AST {
+        let object = Box::new(object);
+        match self {
+            Postfix::Call(args) => AST::Call { callee: object, args },
+            Postfix::Index(index) => AST::Index { object, index: Box::new(index) },
+            Postfix::Slice(start, end) => AST::Slice {
+                object,
+                start: start.map(Box::new),
+                end: end.map(Box::new),
+            },
+            Postfix::Field(name) => AST::Field { object, name },
+        }
+    }
+}
+
+// Parsing `[index]` or `[start:end]` (either bound of a slice may be left out).
+fn subscript(input: &str) -> IResult<&str, Postfix> {
+    let (input, _) = char('[')(input)?;
+    let (input, start) = opt(preceded(multispace0, math_expression))(input)?;
+    let (input, colon) = opt(preceded(multispace0, char(':')))(input)?;
+    let (input, postfix) = match (start, colon) {
+        (start, Some(_)) => {
+            let (input, end) = opt(preceded(multispace0, math_expression))(input)?;
+            (input, Postfix::Slice(start, end))
+        }
+        (Some(index), None) => (input, Postfix::Index(index)),
+        (None, None) => return fail(input),
+    };
+    let (input, _) = preceded(multispace0, char(']'))(input)?;
+    Ok((input, postfix))
+}
+
+// Parsing `.name` after an expression (`rows[0].name`); `point.x` is a qualified identifier.
+fn field(input: &str) -> IResult<&str, Postfix> {
+    map(preceded(char('.'), identifier), |name| match name {
+        AST::Identifier(name) => Postfix::Field(name),
+        _ => unreachable!(),
+    })(input)
+}
+
+fn postfix(input: &str) -> IResult<&str, Postfix> {
+    alt((map(parse_arguments, Postfix::Call), subscript, field))(input)
+}
+
+// Parsing a factor (a basic unit in an expression), possibly followed by calls (`f(1)(2)`),
+// indexes, slices and fields (`rows[0].name`).
 pub fn factor(input: &str) -> IResult<&str, AST> {
-    alt((
+    let (input, factor) = alt((
         float,
         integer,
         boolean,
-        identifier,
+        lambda,
+        construct,
+        function_call,
+        qualified_identifier,
         string_literal,
         array_literal,
         dictionary_literal,
         parenthesized_expression,
-    ))(input)
+    ))(input)?;
+    let (input, postfixes) = many0(postfix)(input)?;
+    Ok((input, postfixes.into_iter().fold(factor, |object, postfix| postfix.apply(object))))
+}
+
+// Parsing an anonymous function (`fn(x) { ... }`, `x => x * 2` or `(a, b) => a + b`).
+pub fn lambda(input: &str) -> IResult<&str, AST> {
+    alt((block_lambda, arrow_lambda))(input)
+}
+
+fn block_lambda(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("fn")(input)?;
+    let (input, args) = preceded(multispace0, parameters)(input)?;
+    let (input, _) = preceded(multispace0, char('{'))(input)?;
+    let (input, body) = many0(preceded(multispace0, statement))(input)?;
+    let (input, _) = preceded(multispace0, char('}'))(input)?;
+    Ok((
+        input,
+        AST::Lambda {
+            args: Box::new(AST::FunctionArgs(args)),
+            body: Box::new(AST::Block(body)),
+        },
+    ))
+}
+
+fn arrow_lambda(input: &str) -> IResult<&str, AST> {
+    let (input, args) = alt((parameters, map(identifier, |param| vec![param])))(input)?;
+    let (input, _) = preceded(multispace0, tag("=>"))(input)?;
+    let (input, body) = preceded(multispace0, comparison_expression)(input)?;
+    Ok((
+        input,
+        AST::Lambda {
+            args: Box::new(AST::FunctionArgs(args)),
+            body: Box::new(body),
+        },
+    ))
+}
+
+// Parsing a parameter list (`(a, b)`).
+fn parameters(input: &str) -> IResult<&str, Vec<AST>> {
+    delimited(
+        char('('),
+        separated_list0(
+            preceded(multispace0, char(',')),
+            preceded(multispace0, identifier),
+        ),
+        preceded(multispace0, char(')')),
+    )(input)
 }
 
 // Parsing a term (a factor possibly followed by * or / operations).
@@ -139,7 +283,7 @@ pub fn math_expression(input: &str) -> IResult<&str, AST> {
 pub fn return_stmt(input: &str) -> IResult<&str, AST> {
     let (input, _) = tag("return")(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, expr) = math_expression(input)?;
+    let (input, expr) = values(input)?;
     Ok((input, AST::Return(Box::new(expr))))
 }
 
@@ -151,8 +295,7 @@ pub fn write_stmt(input: &str) -> IResult<&str, AST> {
     let (input, expr_list) = separated_list0(
         preceded(multispace0, tag(",")),
         preceded(multispace0, alt((
-            function_call,  // Вызов функции
-            math_expression,
+            math_expression,  // Включает вызовы функций, строки и идентификаторы
             string_literal,
             identifier,  // Поддержка идентификаторов (например, переменных)
         )))
@@ -197,9 +340,9 @@ fn parse_arguments(input: &str) -> IResult<&str, Vec<AST>> {
         char('('),
         separated_list0(
             preceded(multispace0, char(',')),
-            preceded(multispace0, factor),  // Заменяем identifier на factor, чтобы поддерживались все типы данных
+            preceded(multispace0, math_expression),  // Аргументы могут быть выражениями, например fib(n - 1)
         ),
-        char(')'),
+        preceded(multispace0, char(')')),
     )(input)?;
 
     Ok((input, args))
@@ -234,7 +377,7 @@ pub fn function(input: &str) -> IResult<&str, AST> {
 }
 
 pub fn function_call(input: &str) -> IResult<&str, AST> {
-    let (input, name) = identifier(input)?;
+    let (input, name) = qualified_identifier(input)?;
     let (input, args) = parse_arguments(input)?;
     Ok((
         input,
@@ -248,31 +391,144 @@ pub fn function_call(input: &str) -> IResult<&str, AST> {
     ))
 }
 
+// Parsing a struct declaration (`struct Point { x, y }`); a field may name its type (`x: int`),
+// which can be another struct (`start: Point`, `p: geometry.Point`).
+pub fn struct_decl(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("struct")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, name) = identifier(input)?;
+    let (input, _) = preceded(multispace0, char('{'))(input)?;
+    let field = tuple((
+        preceded(multispace0, identifier),
+        opt(preceded(tuple((multispace0, char(':'), multispace0)), qualified_identifier)),
+    ));
+    let (input, fields) = separated_list0(preceded(multispace0, char(',')), field)(input)?;
+    let (input, _) = opt(preceded(multispace0, char(',')))(input)?;
+    let (input, _) = preceded(multispace0, char('}'))(input)?;
+    let name_of = |node: AST| match node {
+        AST::Identifier(name) => name,
+        _ => unreachable!(),
+    };
+    Ok((
+        input,
+        AST::Struct {
+            name: name_of(name),
+            fields: fields
+                .into_iter()
+                .map(|(field, ty)| (name_of(field), ty.map(name_of)))
+                .collect(),
+        },
+    ))
+}
+
+// Parsing a block of methods for a struct or a builtin type (`impl Point { len(self) { ... } }`);
+// the first parameter of a method is the value it is called on.
+pub fn impl_block(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("impl")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, name) = qualified_identifier(input)?;
+    let (input, _) = preceded(multispace0, char('{'))(input)?;
+    let method = verify(statement, |node| match node {
+        AST::Located { node, .. } => matches!(**node, AST::Function { .. } | AST::Comment(_)),
+        _ => false,
+    });
+    let (input, methods) = many0(preceded(multispace0, method))(input)?;
+    let (input, _) = preceded(multispace0, char('}'))(input)?;
+    Ok((
+        input,
+        AST::Impl {
+            name: match name {
+                AST::Identifier(name) => name,
+                _ => unreachable!(),
+            },
+            methods,
+        },
+    ))
+}
+
+// Parsing a call on the result of an expression, used as a statement (`rows[0].push(x)`).
+fn call_statement(input: &str) -> IResult<&str, AST> {
+    verify(factor, |node| matches!(node, AST::Call { .. }))(input)
+}
+
+// Parsing the construction of a struct value with named fields (`Point(x: 1, y: 2)`).
+pub fn construct(input: &str) -> IResult<&str, AST> {
+    let (input, name) = qualified_identifier(input)?;
+    let field = separated_pair(
+        preceded(multispace0, identifier),
+        preceded(multispace0, char(':')),
+        preceded(multispace0, math_expression),
+    );
+    let (input, fields) = delimited(
+        char('('),
+        separated_list1(preceded(multispace0, char(',')), field),
+        preceded(multispace0, char(')')),
+    )(input)?;
+    let name_of = |node: AST| match node {
+        AST::Identifier(name) => name,
+        _ => unreachable!(),
+    };
+    Ok((
+        input,
+        AST::Construct {
+            name: name_of(name),
+            fields: fields
+                .into_iter()
+                .map(|(field, value)| (name_of(field), value))
+                .collect(),
+        },
+    ))
+}
+
 // Parsing a coincide statement.
 pub fn coincide(input: &str) -> IResult<&str, AST> {
+    let source = input;
     let (input, _) = tag("coincide")(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, expr) = identifier(input)?;
-    let (input, _) = tag(":")(input)?;
-    let (input, _) = multispace1(input)?;
+    let (input, expr) = comparison_expression(input)?;
+    let (input, _) = preceded(multispace0, tag(":"))(input)?;
+    let (mut input, _) = peek(multispace1)(input)?;
 
-    let (input, cases) = many0(tuple((
-        preceded(multispace0, math_expression),
+    let mut case = tuple((
+        preceded(multispace0, arm_patterns),
         preceded(multispace1, tag("then")),
-        preceded(multispace1, statement),
-    )))(input)?;
-
-    let (input, default) = opt(preceded(
+        preceded(multispace1, arm_body),
+    ));
+    let mut default_arm = preceded(
         multispace0,
-        tuple((tag("default"), preceded(multispace1, statement))),
-    ))(input)?;
-
-    let cases = cases
-        .into_iter()
-        .map(|(condition, _, action)| (condition, action))
-        .collect();
+        preceded(tag("default"), preceded(multispace1, arm_body)),
+    );
+
+    let mut cases = Vec::new();
+    let mut default = None;
+    let mut comments = Vec::new();
+    while default.is_none() {
+        // Comments belong to the coincide only when an arm follows them
+        let mut notes = Vec::new();
+        let mut rest = input;
+        while let Ok((after, AST::Comment(text))) = preceded(multispace0, comment)(rest) {
+            let (at, _) = multispace0(rest)?;
+            // Arms can swallow the line break after them, so look back at the source
+            let before = source[..source.len() - at.len()].trim_end_matches([' ', '\t']);
+            notes.push(ArmComment {
+                arm: cases.len(),
+                trailing: !before.ends_with('\n'),
+                text,
+            });
+            rest = after;
+        }
 
-    let default = default.map(|(_, action)| Box::new(action));
+        if let Ok((rest, (condition, _, action))) = case(rest) {
+            cases.push((condition, action));
+            input = rest;
+        } else if let Ok((rest, action)) = default_arm(rest) {
+            default = Some(Box::new(action));
+            input = rest;
+        } else {
+            break;
+        }
+        comments.extend(notes);
+    }
 
     Ok((
         input,
@@ -280,50 +536,201 @@ pub fn coincide(input: &str) -> IResult<&str, AST> {
             expr: Box::new(expr),
             cases,
             default,
+            comments,
         },
     ))
 }
 
+// The type names a `coincide` arm can match on (`int then ...`) and a struct field can declare.
+pub const TYPE_PATTERNS: &[&str] = &[
+    "int", "float", "bool", "string", "array", "dictionary", "tuple", "function", "struct", "null",
+];
+
+// Parsing the patterns of a coincide arm: one, or several separated by commas (`1, 2 then`).
+fn arm_patterns(input: &str) -> IResult<&str, AST> {
+    let (input, mut patterns) =
+        separated_list1(preceded(multispace0, char(',')), preceded(multispace0, pattern))(input)?;
+    if patterns.len() == 1 {
+        return Ok((input, patterns.remove(0)));
+    }
+    Ok((input, AST::Alternatives(patterns)))
+}
 
+// Parsing a single pattern: a range (`1..5`), a type name, or a value; names inside an array
+// or dictionary pattern are bound to what they match.
+fn pattern(input: &str) -> IResult<&str, AST> {
+    let (input, start) = math_expression(input)?;
+    if let Ok((input, end)) = preceded(delimited(multispace0, tag(".."), multispace0), math_expression)(input) {
+        return Ok((
+            input,
+            AST::Range {
+                start: Box::new(start),
+                end: Box::new(end),
+            },
+        ));
+    }
+    match start {
+        AST::Identifier(name) if TYPE_PATTERNS.contains(&name.as_str()) => Ok((input, AST::TypePattern(name))),
+        start => Ok((input, start)),
+    }
+}
+
+// Parsing the body of a coincide arm: a statement or a `{ ... }` block.
+fn arm_body(input: &str) -> IResult<&str, AST> {
+    let block = delimited(
+        char('{'),
+        many0(preceded(multispace0, statement)),
+        preceded(multispace0, char('}')),
+    );
+    alt((map(block, AST::Block), statement))(input)
+}
 
-// Parsing a variable assignment.
+// Parsing an import (`import "utils.glt"` or `import utils`).
+pub fn import_stmt(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("import")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, module) = alt((string_literal, identifier))(input)?;
+    Ok((input, AST::Import(Box::new(module))))
+}
+
+// Parsing a test block (`test "name" { ... }`).
+pub fn test_block(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("test")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, name) = delimited(tag("\""), take_while(|c| c != '"'), tag("\""))(input)?;
+    let (input, _) = multispace0(input)?;
+    let (input, _) = char('{')(input)?;
+    let (input, body) = many0(preceded(multispace0, statement))(input)?;
+    let (input, _) = preceded(multispace0, char('}'))(input)?;
+    Ok((
+        input,
+        AST::Test {
+            name: name.to_string(),
+            body: Box::new(AST::Block(body)),
+        },
+    ))
+}
+
+// Parsing an assertion (`assert condition` or `assert condition, "message"`).
+pub fn assert_stmt(input: &str) -> IResult<&str, AST> {
+    let (input, _) = tag("assert")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, condition) = comparison_expression(input)?;
+    let (input, message) = opt(preceded(
+        tuple((multispace0, char(','), multispace0)),
+        math_expression,
+    ))(input)?;
+    Ok((
+        input,
+        AST::Assert {
+            condition: Box::new(condition),
+            message: message.map(Box::new),
+        },
+    ))
+}
+
+
+// Parsing a variable assignment, or an assignment to an element or field (`arr[0] is 5`, `point.x is 1`).
 pub fn variable_assign(input: &str) -> IResult<&str, AST> {
-    let (input, name) = identifier(input)?;
+    let (input, name) = qualified_identifier(input)?;
+    let (input, postfixes) = many0(alt((subscript, field)))(input)?;
+    // A slice is a copy, so it cannot be assigned to
+    if postfixes.iter().any(|postfix| matches!(postfix, Postfix::Slice(..))) {
+        return fail(input);
+    }
+    let target = postfixes.into_iter().fold(name, |object, postfix| postfix.apply(object));
     let (input, _) = multispace1(input)?;
     let (input, _) = tag("is")(input)?;
     let (input, _) = multispace1(input)?;
-    let (input, value) = alt((
-        math_expression,
-        string_literal,
-        array_literal,
-        dictionary_literal,
+    let (input, value) = values(input)?;
+    let value = Box::new(value);
+    match target {
+        AST::Identifier(name) if !name.contains('.') => Ok((input, AST::VariableAssign { name, value })),
+        target => Ok((input, AST::Assign { target: Box::new(target), value })),
+    }
+}
+
+// Parsing a destructuring assignment (`x, y is pair` or `(q, r) is divmod(a, b)`).
+pub fn destructure(input: &str) -> IResult<&str, AST> {
+    let names = |input| {
+        separated_list1(
+            preceded(multispace0, char(',')),
+            preceded(multispace0, identifier),
+        )(input)
+    };
+    let (input, names) = alt((
+        delimited(char('('), names, preceded(multispace0, char(')'))),
+        names,
     ))(input)?;
+    if names.len() < 2 {
+        return fail(input);
+    }
+    let (input, _) = multispace1(input)?;
+    let (input, _) = tag("is")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, value) = values(input)?;
+    let names = names
+        .into_iter()
+        .map(|name| match name {
+            AST::Identifier(name) => name,
+            _ => unreachable!(),
+        })
+        .collect();
     Ok((
         input,
-        AST::VariableAssign {
-            name: match name {
-                AST::Identifier(id) => id,
-                _ => unreachable!(),
-            },
+        AST::Destructure {
+            names,
             value: Box::new(value),
         },
     ))
 }
 
-// Parsing a statement (includes all possible statements).
+// Parsing a comment (`#` up to the end of the line).
+pub fn comment(input: &str) -> IResult<&str, AST> {
+    map(preceded(char('#'), take_while(|c| c != '\n')), |text: &str| {
+        AST::Comment(text.trim_end().to_string())
+    })(input)
+}
+
+// Parsing a statement (includes all possible statements), wrapped with its position.
+// A parser only sees the input left to parse, so the position is kept as how many bytes were
+// left where the statement starts (`column`) and ends (`end_column`); `parse_program` turns
+// those into lines and columns once it has parsed the whole source.
 pub fn statement(input: &str) -> IResult<&str, AST> {
-    preceded(
-        multispace0,
-        alt((
-            return_stmt,
-            write_stmt,
-            variable_assign,
-            function,
-            if_else_stmt,
-            function_call,
-            coincide,
-        )),
-    )(input)
+    let (start, _) = multispace0(input)?;
+    let (rest, node) = alt((
+        comment,
+        return_stmt,
+        write_stmt,
+        import_stmt,
+        test_block,
+        struct_decl,
+        impl_block,
+        assert_stmt,
+        destructure,
+        variable_assign,
+        function,
+        if_else_stmt,
+        call_statement,
+        function_call,
+        coincide,
+    ))(start)?;
+
+    // Some statements swallow the whitespace after them; it is not part of the span
+    let consumed = &start[..start.len() - rest.len()];
+    let span = Span {
+        column: start.len(),
+        end_column: start.len() - consumed.trim_end().len(),
+        ..Span::default()
+    };
+    Ok((
+        rest,
+        AST::Located {
+            span,
+            file: None,
+            node: Box::new(node),
+        },
+    ))
 }
 
 
@@ -359,7 +766,7 @@ pub fn if_else_stmt(input: &str) -> IResult<&str, AST> {
     };
 
     // Преобразуем else_block в AST
-    let else_ast = else_block.map(|block| AST::Block(block));
+    let else_ast = else_block.map(AST::Block);
 
     Ok((
         input,
@@ -380,14 +787,14 @@ pub fn program(input: &str) -> IResult<&str, Vec<AST>> {
 
 // Parsing the program and returning the result or a parse error.
 pub fn parse_program(input: &str) -> Result<AST, ParseError> {
-    match program(input) {
-        Ok((remaining, ast)) => {
+    let positions = Positions::new(input);
+    match program(skip_shebang(input)) {
+        Ok((remaining, mut ast)) => {
             if !remaining.trim().is_empty() {
-                let line = input.lines().take_while(|l| !remaining.contains(l)).count() + 1;
-                return Err(ParseError::UnknownToken {
-                    token: remaining.trim().to_string(),
-                    line,
-                });
+                return Err(positions.unknown_token(remaining));
+            }
+            for statement in &mut ast {
+                positions.locate(statement);
             }
             Ok(AST::Program(ast))
         }
@@ -405,6 +812,152 @@ pub fn parse_program(input: &str) -> Result<AST, ParseError> {
     }
 }
 
+// Parsing a single expression that must span the whole input (used by the REPL).
+pub fn parse_expression(input: &str) -> Result<AST, ParseError> {
+    let positions = Positions::new(input);
+    match delimited(multispace0, comparison_expression, multispace0)(input) {
+        Ok(("", mut expr)) => {
+            // A lambda's body holds statements
+            positions.locate(&mut expr);
+            Ok(expr)
+        }
+        Ok((remaining, _)) => Err(positions.unknown_token(remaining)),
+        Err(err) => Err(ParseError::SyntaxError {
+            message: format!("Failed to parse expression: {:?}", err),
+            line: input.lines().count(),
+        }),
+    }
+}
+
+// Where the lines of the source being parsed start, to turn the positions parsers keep (how many
+// bytes of the source were left) into 1-based lines and columns (in characters).
+struct Positions<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Positions<'a> {
+    fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Positions {
+            source,
+            line_starts,
+        }
+    }
+
+    // The line and column where the last `left` bytes of the source start.
+    fn line_column(&self, left: usize) -> (usize, usize) {
+        let offset = self.source.len() - left;
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let column = self.source[self.line_starts[line - 1]..offset].chars().count() + 1;
+        (line, column)
+    }
+
+    // Reporting the first line of input nothing could parse.
+    fn unknown_token(&self, remaining: &str) -> ParseError {
+        let remaining = remaining.trim_start();
+        let (line, column) = self.line_column(remaining.len());
+        ParseError::UnknownToken {
+            token: remaining.lines().next().unwrap_or_default().trim_end().to_string(),
+            line,
+            column,
+        }
+    }
+
+    // Giving every statement in `node` its line and column, from the bytes left it was parsed with.
+    fn locate(&self, node: &mut AST) {
+        if let AST::Located { span, .. } = node {
+            let (line, column) = self.line_column(span.column);
+            let (end_line, end_column) = self.line_column(span.end_column);
+            *span = Span {
+                line,
+                column,
+                end_line,
+                end_column,
+            };
+        }
+        for child in children(node) {
+            self.locate(child);
+        }
+    }
+}
+
+// The nodes directly inside a node, where statements can be found.
+fn children(node: &mut AST) -> Vec<&mut AST> {
+    match node {
+        AST::Program(nodes)
+        | AST::Block(nodes)
+        | AST::Write(nodes)
+        | AST::Array(nodes)
+        | AST::Tuple(nodes)
+        | AST::Alternatives(nodes)
+        | AST::FunctionArgs(nodes)
+        | AST::FunctionCall { args: nodes, .. }
+        | AST::Impl { methods: nodes, .. } => nodes.iter_mut().collect(),
+        AST::Function { args, body, .. } | AST::Lambda { args, body } => vec![args, body],
+        AST::Call { callee, args } => std::iter::once(&mut **callee).chain(args).collect(),
+        AST::Index { object, index } => vec![object, index],
+        AST::Slice { object, start, end } => std::iter::once(&mut **object)
+            .chain(start.as_deref_mut())
+            .chain(end.as_deref_mut())
+            .collect(),
+        AST::Construct { fields, .. } => fields.iter_mut().map(|(_, value)| value).collect(),
+        AST::Dictionary(pairs) => pairs.iter_mut().flat_map(|(k, v)| [k, v]).collect(),
+        AST::Coincide {
+            expr,
+            cases,
+            default,
+            ..
+        } => std::iter::once(&mut **expr)
+            .chain(cases.iter_mut().flat_map(|(pattern, action)| [pattern, action]))
+            .chain(default.as_deref_mut())
+            .collect(),
+        AST::BinaryOp { left, right, .. } | AST::Range { start: left, end: right } => {
+            vec![left, right]
+        }
+        AST::Assign { target, value } => vec![target, value],
+        AST::IfElse {
+            condition,
+            if_block,
+            else_block,
+        } => [&mut **condition, &mut **if_block]
+            .into_iter()
+            .chain(else_block.as_deref_mut())
+            .collect(),
+        AST::Assert { condition, message } => std::iter::once(&mut **condition)
+            .chain(message.as_deref_mut())
+            .collect(),
+        AST::Return(node)
+        | AST::Import(node)
+        | AST::Field { object: node, .. }
+        | AST::VariableAssign { value: node, .. }
+        | AST::Destructure { value: node, .. }
+        | AST::Module { body: node, .. }
+        | AST::Test { body: node, .. }
+        | AST::Located { node, .. } => vec![node],
+        AST::Struct { .. }
+        | AST::Identifier(_)
+        | AST::Integer(_)
+        | AST::Float(_)
+        | AST::Bool(_)
+        | AST::String(_)
+        | AST::TypePattern(_)
+        | AST::Comment(_) => Vec::new(),
+    }
+}
+
+// Skipping a leading shebang line (`#!/usr/bin/env glint`) so scripts can be executable.
+// The newline is kept so line numbers in errors stay the same.
+fn skip_shebang(input: &str) -> &str {
+    if input.starts_with("#!") {
+        &input[input.find('\n').unwrap_or(input.len())..]
+    } else {
+        input
+    }
+}
+
 // Helper function for case-insensitive tag matching.
 fn tag_no_case(tag: &'static str) -> impl Fn(&str) -> IResult<&str, &str> {
     move |input: &str| {
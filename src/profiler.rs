@@ -0,0 +1,245 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::ast::Span;
+use crate::bench::millis;
+use crate::debugger::PROGRAM_FRAME;
+use crate::error::RuntimeError;
+use crate::interpreter::function::Function;
+use crate::interpreter::hook::Hook;
+use crate::interpreter::interpreter::Interpreter;
+
+// ⏱️ Execution profiler behind `glint run --profile`
+
+// How many lines the table lists under "Hot lines"
+const HOT_LINES: usize = 10;
+
+// 📊 Totals for one function
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FunctionStats {
+    pub calls: usize,
+    // Time from entering to leaving the function, counted once for recursive calls
+    pub inclusive: Duration,
+    // Time spent in the function's own statements, without the functions it called
+    pub exclusive: Duration,
+}
+
+// 📍 A source line: the module file it is in (`None` for the program itself) and its number
+pub type Location = (Option<Rc<str>>, usize);
+
+// 📊 Totals for one source line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineStats {
+    pub hits: usize,
+    // Time until the next statement started, without time spent in calls
+    pub time: Duration,
+}
+
+// 🖼️ A call that has not returned yet
+struct Call {
+    name: String,
+    started: Instant,
+    // Time spent in the calls it made
+    children: Duration,
+    // The line it is executing and when that line started
+    line: Option<(Location, Instant)>,
+}
+
+// ⏱️ What a profiled run recorded
+pub struct Profile {
+    stack: Vec<Call>,
+    total: Duration,
+    pub functions: HashMap<String, FunctionStats>,
+    pub lines: HashMap<Location, LineStats>,
+    // Exclusive time per call stack (`<program>;main;helper`)
+    pub stacks: HashMap<String, Duration>,
+}
+
+impl Profile {
+    /// ⏱️ Installs a profiler on `interpreter`; read the returned profile once the program has run
+    pub fn attach(interpreter: &mut Interpreter) -> Rc<RefCell<Profile>> {
+        let now = Instant::now();
+        let profile = Rc::new(RefCell::new(Profile {
+            stack: vec![Call {
+                name: PROGRAM_FRAME.to_string(),
+                started: now,
+                children: Duration::ZERO,
+                line: None,
+            }],
+            total: Duration::ZERO,
+            functions: HashMap::new(),
+            lines: HashMap::new(),
+            stacks: HashMap::new(),
+        }));
+        interpreter.set_hook(Box::new(Recorder(profile.clone())));
+        profile
+    }
+
+    /// 🏁 Closes the program's own frame; call after the program finished (or failed)
+    pub fn finish(&mut self) {
+        // A failure unwinds through `returned`, so only the program's frame can be left
+        while !self.stack.is_empty() {
+            self.leave();
+        }
+    }
+
+    /// 📋 The report as a table: functions by exclusive time, then the hottest lines
+    pub fn table(&self) -> String {
+        let mut functions: Vec<(&String, &FunctionStats)> = self.functions.iter().collect();
+        functions.sort_by(|a, b| b.1.exclusive.cmp(&a.1.exclusive).then(a.0.cmp(b.0)));
+        let width = functions
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Function".len());
+
+        let mut table = format!("Profile ({} total)\n\n", millis(self.total));
+        table.push_str(&format!(
+            "{:<width$}  {:>8}  {:>12}  {:>12}\n",
+            "Function", "Calls", "Inclusive", "Exclusive"
+        ));
+        for (name, stats) in functions {
+            table.push_str(&format!(
+                "{:<width$}  {:>8}  {:>12}  {:>12}\n",
+                name,
+                stats.calls,
+                millis(stats.inclusive),
+                millis(stats.exclusive)
+            ));
+        }
+
+        let mut lines: Vec<(String, &LineStats)> = self
+            .lines
+            .iter()
+            .map(|((file, line), stats)| match file {
+                Some(file) => (format!("{}:{}", file, line), stats),
+                None => (line.to_string(), stats),
+            })
+            .collect();
+        lines.sort_by(|a, b| b.1.time.cmp(&a.1.time).then(a.0.cmp(&b.0)));
+        lines.truncate(HOT_LINES);
+        let width = lines
+            .iter()
+            .map(|(line, _)| line.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        table.push_str(&format!(
+            "\nHot lines\n{:>width$}  {:>8}  {:>12}\n",
+            "Line", "Hits", "Time"
+        ));
+        for (line, stats) in lines {
+            table.push_str(&format!(
+                "{:>width$}  {:>8}  {:>12}\n",
+                line,
+                stats.hits,
+                millis(stats.time)
+            ));
+        }
+        table
+    }
+
+    /// 🔥 The report as folded stacks (`<program>;fib;fib 1234`, in microseconds),
+    /// the input format of flame graph tools
+    pub fn folded(&self) -> String {
+        let mut stacks: Vec<(&String, &Duration)> = self.stacks.iter().collect();
+        stacks.sort();
+        stacks
+            .into_iter()
+            .map(|(stack, time)| format!("{} {}\n", stack, time.as_micros()))
+            .collect()
+    }
+
+    /// 📍 Starts timing `line` in the innermost call, ending the line it was on
+    fn step(&mut self, line: Location) {
+        let now = Instant::now();
+        self.end_line(now);
+        self.lines.entry(line.clone()).or_default().hits += 1;
+        if let Some(call) = self.stack.last_mut() {
+            call.line = Some((line, now));
+        }
+    }
+
+    /// ⏹️ Charges the time since the current line started to that line
+    fn end_line(&mut self, now: Instant) {
+        if let Some((line, started)) = self.stack.last_mut().and_then(|call| call.line.take()) {
+            self.lines.entry(line).or_default().time += now - started;
+        }
+    }
+
+    fn enter(&mut self, name: &str) {
+        let now = Instant::now();
+        // The caller's line is paused while the callee runs and resumes in `leave`
+        if let Some(call) = self.stack.last_mut() {
+            if let Some((line, started)) = &call.line {
+                self.lines.entry(line.clone()).or_default().time += now - *started;
+            }
+        }
+        self.stack.push(Call {
+            name: name.to_string(),
+            started: now,
+            children: Duration::ZERO,
+            line: None,
+        });
+    }
+
+    fn leave(&mut self) {
+        let now = Instant::now();
+        self.end_line(now);
+        let path = self
+            .stack
+            .iter()
+            .map(|call| call.name.as_str())
+            .collect::<Vec<_>>()
+            .join(";");
+        let Some(call) = self.stack.pop() else {
+            return;
+        };
+        let elapsed = now - call.started;
+        let exclusive = elapsed.saturating_sub(call.children);
+        *self.stacks.entry(path).or_default() += exclusive;
+
+        // A recursive call's time is already part of the outermost call of the same function
+        let outermost = !self.stack.iter().any(|caller| caller.name == call.name);
+        let stats = self.functions.entry(call.name).or_default();
+        stats.calls += 1;
+        stats.exclusive += exclusive;
+        if outermost {
+            stats.inclusive += elapsed;
+        }
+
+        match self.stack.last_mut() {
+            Some(caller) => {
+                caller.children += elapsed;
+                caller.line = caller.line.take().map(|(line, _)| (line, now));
+            }
+            None => self.total = elapsed,
+        }
+    }
+}
+
+// 🪝 Feeds interpreter events into a shared profile
+struct Recorder(Rc<RefCell<Profile>>);
+
+impl Hook for Recorder {
+    fn statement(
+        &mut self,
+        _interpreter: &mut Interpreter,
+        span: Span,
+        file: Option<Rc<str>>,
+    ) -> Result<(), RuntimeError> {
+        self.0.borrow_mut().step((file, span.line));
+        Ok(())
+    }
+
+    fn call(&mut self, _interpreter: &Interpreter, function: &Function) {
+        self.0.borrow_mut().enter(&function.name);
+    }
+
+    fn returned(&mut self, _interpreter: &Interpreter, _function: &Function) {
+        self.0.borrow_mut().leave();
+    }
+}
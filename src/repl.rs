@@ -0,0 +1,238 @@
+use colored::Colorize;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::env;
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+
+use crate::ast::AST;
+use crate::error::RuntimeError;
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::value::Value;
+use crate::parser::parser::{parse_expression, parse_program};
+
+const HELP: &str = r#"
+  Meta-commands:
+    :ast <code>      Show the AST of an expression or statements
+    :vars            List global variables
+    :funcs           List defined functions
+    :load <file>     Run a .glt file in this session
+    :reset           Forget all variables and functions
+    :help            Show this help
+    :quit            Leave the REPL (or press Ctrl-D)
+"#;
+
+/// 💬 An interactive session that keeps its interpreter state between inputs
+pub struct Repl {
+    interpreter: Interpreter,
+}
+
+impl Repl {
+    /// 🆕 Starts a session with a fresh interpreter
+    pub fn new() -> Self {
+        Self {
+            interpreter: Interpreter::new(),
+        }
+    }
+
+    /// ⌨️ Reads, evaluates and prints until the user quits; returns the exit code of the session
+    /// (the code passed to `exit`, if the session ended with it)
+    pub fn run(&mut self) -> u8 {
+        let mut editor = match DefaultEditor::new() {
+            Ok(editor) => editor,
+            Err(err) => {
+                eprintln!("Failed to start the REPL: {}", err);
+                return 1;
+            }
+        };
+        let history = history_path();
+        if let Some(path) = &history {
+            let _ = editor.load_history(path);
+        }
+
+        println!(
+            "{} {}",
+            format!("✧Glint v{}✧ REPL", env!("CARGO_PKG_VERSION")).bright_blue(),
+            "Type :help for commands, Ctrl-D to exit.".cyan()
+        );
+
+        let mut buffer = String::new();
+        let mut code = 0;
+        loop {
+            let prompt = if buffer.is_empty() {
+                "glint> "
+            } else {
+                "  ...> "
+            };
+            match editor.readline(prompt) {
+                Ok(line) => {
+                    buffer.push_str(&line);
+                    buffer.push('\n');
+
+                    // ⏳ Keep reading until every brace, bracket and parenthesis is closed
+                    if open_delimiters(&buffer) > 0 {
+                        continue;
+                    }
+
+                    let input = std::mem::take(&mut buffer);
+                    let input = input.trim();
+                    if input.is_empty() {
+                        continue;
+                    }
+                    let _ = editor.add_history_entry(input);
+                    if let ControlFlow::Break(exit) = self.eval(input) {
+                        code = exit;
+                        break;
+                    }
+                }
+                Err(ReadlineError::Interrupted) => {
+                    // Ctrl-C drops the pending (possibly multi-line) input
+                    buffer.clear();
+                }
+                Err(ReadlineError::Eof) => break,
+                Err(err) => {
+                    eprintln!("Error reading input: {}", err);
+                    code = 1;
+                    break;
+                }
+            }
+        }
+
+        if let Some(path) = &history {
+            let _ = editor.save_history(path);
+        }
+        code
+    }
+
+    /// 🎯 Evaluates one complete input; breaks with the exit code when the session should end
+    pub fn eval(&mut self, input: &str) -> ControlFlow<u8> {
+        if let Some(command) = input.strip_prefix(':') {
+            return self.meta_command(command);
+        }
+
+        // A bare expression prints its value, anything else runs as statements
+        if let Ok(expr) = parse_expression(input) {
+            let expr_json = serde_json::to_value(&expr).expect("Failed to serialize AST");
+            return match self.interpreter.evaluate(&expr_json) {
+                Ok(Value::Null) => ControlFlow::Continue(()),
+                Ok(value) => {
+                    println!("{}", value.repr());
+                    ControlFlow::Continue(())
+                }
+                Err(err) => report_error(err),
+            };
+        }
+
+        match parse_program(input) {
+            Ok(ast) => self.execute(&ast),
+            Err(err) => {
+                eprintln!("{}", err);
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    /// ▶️ Runs a parsed program inside the session; breaks with the code it passed to `exit`
+    fn execute(&mut self, ast: &AST) -> ControlFlow<u8> {
+        let ast_json = serde_json::to_string(ast).expect("Failed to serialize AST");
+        self.interpreter.load_from_json(&ast_json);
+        match self.interpreter.interpret() {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(err) => report_error(err),
+        }
+    }
+
+    /// 🧰 Handles `:command args`
+    fn meta_command(&mut self, command: &str) -> ControlFlow<u8> {
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, argument)| (name, argument.trim()))
+            .unwrap_or((command, ""));
+
+        match name {
+            "ast" => {
+                let ast = parse_expression(argument).or_else(|_| parse_program(argument));
+                match ast {
+                    Ok(ast) => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&ast).expect("Failed to serialize AST")
+                    ),
+                    Err(err) => eprintln!("{}", err),
+                }
+            }
+            "vars" => {
+                let mut variables: Vec<_> = self.interpreter.variables().iter().collect();
+                variables.sort_by(|a, b| a.0.cmp(b.0));
+                for (name, value) in variables {
+                    println!("{} = {}", name.truecolor(41, 176, 255), value.repr());
+                }
+            }
+            "funcs" => {
+                let mut functions: Vec<_> = self.interpreter.functions().values().collect();
+                functions.sort_by(|a, b| a.name.cmp(&b.name));
+                for func in functions {
+                    println!(
+                        "{}({})",
+                        func.name.truecolor(41, 176, 255),
+                        func.args.join(", ")
+                    );
+                }
+            }
+            "load" => match fs::read_to_string(argument) {
+                Ok(contents) => match parse_program(&contents) {
+                    Ok(ast) => return self.execute(&ast),
+                    Err(err) => eprintln!("{}", err),
+                },
+                Err(err) => eprintln!("Error reading file: {}", err),
+            },
+            "reset" => {
+                self.interpreter = Interpreter::new();
+                println!("Session reset.");
+            }
+            "help" => println!("{}", HELP),
+            "quit" | "q" | "exit" => return ControlFlow::Break(0),
+            _ => eprintln!("Unknown command ':{}'. Type :help for a list.", name),
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 🚨 Prints a runtime error; a call to `exit` instead ends the session with its code
+fn report_error(err: RuntimeError) -> ControlFlow<u8> {
+    if let RuntimeError::Exit { code } = err {
+        return ControlFlow::Break(code);
+    }
+    eprintln!("Runtime error: {}", err);
+    ControlFlow::Continue(())
+}
+
+/// 🧮 Counts unclosed `{`, `[` and `(` outside of string literals and `#` comments
+pub fn open_delimiters(input: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut in_comment = false;
+    for c in input.chars() {
+        match c {
+            '\n' => in_comment = false,
+            _ if in_comment => {}
+            '"' => in_string = !in_string,
+            '#' if !in_string => in_comment = true,
+            '{' | '[' | '(' if !in_string => depth += 1,
+            '}' | ']' | ')' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// 📜 Where the REPL keeps its history between sessions
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".glint_history"))
+}
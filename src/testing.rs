@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::ast::AST;
+use crate::error::RuntimeError;
+use crate::interpreter::interpreter::Interpreter;
+
+// 🧪 In-language `test` blocks behind `glint test`
+
+// 🧾 The result of one `test` block
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    // The line the block starts on
+    pub line: usize,
+    pub failure: Option<Failure>,
+    // What the block wrote
+    pub output: String,
+}
+
+// ❌ Why a test block failed and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    // The module file the failure happened in, `None` for the test's own file
+    pub file: Option<String>,
+    pub line: usize,
+    // Only known for failed assertions
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+/// 🧪 Runs every top-level `test` block of a program, each in a fresh interpreter that only
+/// knows the program's functions (the rest of the program does not run)
+pub fn run_tests(ast: &AST) -> Vec<TestResult> {
+    let ast_json = serde_json::to_string(ast).expect("Failed to serialize AST");
+    let mut interpreter = Interpreter::new();
+    interpreter.load_from_json(&ast_json);
+    interpreter
+        .tests()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, line))| {
+            let (failure, output) = run_test(&ast_json, index);
+            TestResult {
+                name,
+                line,
+                failure,
+                output,
+            }
+        })
+        .collect()
+}
+
+/// ▶️ Runs one test block, collecting what it writes
+fn run_test(ast_json: &str, index: usize) -> (Option<Failure>, String) {
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(Captured(output.clone())));
+    interpreter.set_args(&[]);
+    interpreter.load_from_json(ast_json);
+
+    let failure = match interpreter.run_test(index) {
+        // `exit(0)` ends a test early without failing it
+        Ok(()) | Err(RuntimeError::Exit { code: 0 }) => None,
+        Err(RuntimeError::AssertionFailed {
+            message,
+            file,
+            line,
+            column,
+        }) => Some(Failure {
+            file,
+            line,
+            column: Some(column),
+            message: format!("assertion failed: {}", message),
+        }),
+        Err(err) => Some(Failure {
+            file: interpreter.file().map(str::to_string),
+            line: interpreter.line(),
+            column: None,
+            message: err.to_string(),
+        }),
+    };
+    drop(interpreter);
+    let output = String::from_utf8_lossy(&output.borrow()).into_owned();
+    (failure, output)
+}
+
+// 📥 A writer that keeps what a test writes
+struct Captured(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::process::Command;
+use std::time::Duration;
+
+use glint::bench::{bench_program, collect_programs, Stats};
+use glint::parser::parser::parse_program;
+
+mod common;
+use common::temp_dir;
+
+#[test]
+fn summarizes_samples() {
+    let ms = Duration::from_millis;
+    let stats = Stats::from_samples(&[ms(4), ms(1), ms(3), ms(2)]);
+    assert_eq!(stats.runs, 4);
+    assert_eq!(stats.mean, ms(2) + Duration::from_micros(500));
+    assert_eq!(
+        (stats.min, stats.median, stats.max),
+        (ms(1), Duration::from_micros(2500), ms(4))
+    );
+    // Sample standard deviation of 1, 2, 3 and 4 ms
+    assert!((stats.std_dev.as_secs_f64() * 1000.0 - 1.290_994).abs() < 1e-5);
+
+    let single = Stats::from_samples(&[ms(7)]);
+    assert_eq!((single.median, single.std_dev), (ms(7), Duration::ZERO));
+}
+
+#[test]
+fn times_programs_and_finds_them_in_directories() {
+    let ast = parse_program("write 1 + 2\nexit(0)\n").unwrap();
+    assert_eq!(bench_program(&ast, 2, 3).unwrap().runs, 3);
+    let failing = parse_program("write 1 / 0\n").unwrap();
+    assert!(bench_program(&failing, 0, 1).is_err());
+
+    let dir = temp_dir("collect");
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    for file in ["b.glt", "a.glt", "notes.txt", "nested/c.glt"] {
+        std::fs::write(dir.join(file), "write 1\n").unwrap();
+    }
+    let programs = collect_programs(std::slice::from_ref(&dir)).unwrap();
+    let names: Vec<_> = programs
+        .iter()
+        .map(|path| path.strip_prefix(&dir).unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["a.glt", "b.glt", "nested/c.glt"]);
+}
+
+#[test]
+fn bench_prints_a_row_per_program_and_fails_on_errors() {
+    let dir = temp_dir("cli");
+    std::fs::write(dir.join("quick.glt"), "write 2 * 21\n").unwrap();
+    let run = |runs: &str| {
+        Command::new(env!("CARGO_BIN_EXE_glint"))
+            .args(["bench", "--warmup", "0", "--runs", runs])
+            .arg(&dir)
+            .output()
+            .unwrap()
+    };
+
+    let output = run("2");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("Benchmark"));
+    let row: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(&row[..2], ["quick", "2"]);
+    assert!(row[2].parse::<f64>().is_ok() && row[3] == "ms");
+
+    std::fs::write(dir.join("broken.glt"), "write missing\n").unwrap();
+    let output = run("2");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("broken: Runtime error: Undefined variable 'missing'"));
+    // The other benchmark still ran
+    assert!(String::from_utf8(output.stdout).unwrap().contains("quick"));
+
+    let output = run("0");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--runs"));
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use glint::checker::{check_program, Severity};
+use glint::parser::parser::parse_program;
+
+mod common;
+use common::script;
+
+/// 🩺 Checks `source` and returns `(line, column, message)` for every finding
+fn findings(source: &str) -> Vec<(usize, usize, String)> {
+    let ast = parse_program(source).unwrap();
+    check_program(&ast, Some(source))
+        .into_iter()
+        .map(|diagnostic| {
+            assert_eq!(diagnostic.severity, Severity::Error);
+            (
+                diagnostic.span.line,
+                diagnostic.span.column,
+                diagnostic.message,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn reports_undefined_variables_where_they_are_read() {
+    let source = "x is 1\nx is x + y\nwrite \"y\", y\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (2, 10, "Undefined variable 'y'".to_string()),
+            (3, 12, "Undefined variable 'y'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn functions_see_parameters_locals_and_globals() {
+    let source = "f(a) {\n    b is a + limit\n    return b + c\n}\nlimit is 10\nwrite f(1)\n";
+    assert_eq!(
+        findings(source),
+        vec![(3, 16, "Undefined variable 'c'".to_string())]
+    );
+}
+
+#[test]
+fn test_blocks_only_see_functions_and_their_own_variables() {
+    let source = "limit is 3\ndouble(n) {\n    return n * 2\n}\ntest \"double\" {\n    x is double(limit)\n    assert x = 6, missing\n}\nwrite limit\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (6, 17, "Undefined variable 'limit'".to_string()),
+            (7, 19, "Undefined variable 'missing'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_functions_and_arity_mismatches() {
+    let source = "write f(1, 2), g()\nf(a) {\n    return a\n}\nexit(1, 2)\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (
+                1,
+                7,
+                "Function 'f' expects 1 arguments but 2 were provided".to_string()
+            ),
+            (1, 16, "Function 'g' not found".to_string()),
+            (
+                5,
+                1,
+                "Function 'exit' expects 1 arguments but 2 were provided".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn checks_the_arity_of_builtins_that_take_functions() {
+    let source = "write map([1]), reduce([1], x => x)\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (
+                1,
+                7,
+                "Function 'map' expects 2 arguments but 1 were provided".to_string()
+            ),
+            (
+                1,
+                17,
+                "Function 'reduce' expects 3 arguments but 2 were provided".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn fields_and_elements_are_read_from_their_variable() {
+    let source = "point is {x: 1}\npoint.x is point.y\nother.x is 2\nwrite point[\"x\"], missing[0], point[i]\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (3, 1, "Undefined variable 'other.x'".to_string()),
+            (4, 19, "Undefined variable 'missing'".to_string()),
+            (4, 37, "Undefined variable 'i'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn coincide_patterns_assign_the_names_they_bind() {
+    let source = "coincide p:\n    [x, _] then write x + y\n    {k: v}, 1..n then write v\nwrite x, v\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (1, 10, "Undefined variable 'p'".to_string()),
+            (2, 27, "Undefined variable 'y'".to_string()),
+            (3, 16, "Undefined variable 'n'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn destructuring_assigns_every_name() {
+    let source = "(x, y) is 1, 2\nwrite x + y + z\n";
+    assert_eq!(
+        findings(source),
+        vec![(2, 15, "Undefined variable 'z'".to_string())]
+    );
+}
+
+#[test]
+fn checks_struct_constructions_against_their_declaration() {
+    let source = "struct Point { x: int, y }\np is Point(x: \"0\", z: 1)\nq is Point(1)\ncoincide p:\n    Point(w: 1) then write Shape(r: 2)\nstruct Line { start: Point, end: point }\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (
+                2,
+                6,
+                "Type error: Point.x must be of type int, got string".to_string()
+            ),
+            (2, 6, "Point has no field 'z'".to_string()),
+            (
+                2,
+                6,
+                "Type error: Point() is missing the field 'y'".to_string()
+            ),
+            (
+                3,
+                6,
+                "Function 'Point' expects 2 arguments but 1 were provided".to_string()
+            ),
+            (5, 5, "Point has no field 'w'".to_string()),
+            (5, 28, "Struct 'Shape' not found".to_string()),
+            (6, 34, "Unknown type 'point' for Line.end".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn checks_impl_blocks_and_accepts_method_calls() {
+    let source = "struct Point { x, y }\nimpl Point {\n    len(self) {\n        return self.x\n    }\n    origin() {\n        return Point(0, 0)\n    }\n}\nimpl Shape {\n    area(self) {\n        return 0\n    }\n}\np is Point(1, 2)\nwrite p.len(), Point.len(p), \"a\".upper()\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (
+                6,
+                5,
+                "Method 'Point.origin' has no parameter for the value it is called on".to_string()
+            ),
+            (10, 6, "Unknown type 'Shape' for impl".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn a_variable_named_like_a_type_calls_methods_on_its_value() {
+    let source = "struct Point { x, y }\nimpl Point {\n    len(self) {\n        return self.x\n    }\n}\nPoint is Point(1, 2)\nwrite Point.len(), Point.nope()\n";
+    assert_eq!(findings(source), vec![]);
+}
+
+#[test]
+fn lambdas_see_their_scope_and_variables_holding_functions_can_be_called() {
+    let source = "twice(f, x) {\n    return f(f(x))\n}\nadd is fn(a, b) {\n    c is a + b\n    return c + limit\n}\nlimit is 1\nwrite twice(x => x + y, 1), add(1, 2, 3), twice(twice, c)\n";
+    assert_eq!(
+        findings(source),
+        vec![
+            (9, 22, "Undefined variable 'y'".to_string()),
+            (9, 56, "Undefined variable 'c'".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reports_return_outside_of_a_function() {
+    let source = "if 1 < 2 {\n    return 1\n}\n";
+    assert_eq!(
+        findings(source),
+        vec![(2, 5, "'return' outside of a function".to_string())]
+    );
+}
+
+#[test]
+fn examples_pass_the_checks() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    for entry in fs::read_dir(root.join("examples")).unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+        assert_eq!(findings(&source), vec![], "{}", path.display());
+    }
+}
+
+#[test]
+fn check_reports_a_parse_error_as_a_finding_and_goes_on() {
+    let broken = script("broken.glt", "write 1\nx is (\n");
+    let fine = script("fine.glt", "write y\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(["--no-color", "check"])
+        .args([&broken, &fine])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "{}:2:1: error: Unknown token 'x is ('\n{}:1:7: error: Undefined variable 'y'\n",
+            broken.display(),
+            fine.display()
+        )
+    );
+    // Reported once, as a finding, and not again as a parse failure
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
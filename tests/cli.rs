@@ -0,0 +1,249 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+use common::script;
+
+/// 🚀 Runs `glint` with the given arguments, feeding `stdin` to it
+fn glint(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn global_flags_go_before_or_after_the_subcommand() {
+    let path = script("flags.glt", "write 1 + 2\n");
+    let path = path.to_str().unwrap();
+
+    for args in [
+        ["--dev", "run", path],
+        ["run", path, "--dev"],
+        ["--dev-json", "run", path],
+        ["--no-color", "check", path],
+    ] {
+        let output = glint(&args, "");
+        assert!(output.status.success(), "{:?}: {}", args, stderr(&output));
+    }
+    assert_eq!(stdout(&glint(&["--dev", "run", path], "")), "3\n");
+
+    // --dev-json reports on stderr after the script ran
+    let output = glint(&["--dev-json", "run", path], "");
+    assert_eq!(stdout(&output), "3\n");
+    assert!(
+        stderr(&output).trim_end().ends_with('}'),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn eval_runs_inline_code_with_arguments_after_the_separator() {
+    let output = glint(&["-e", "write 1 + 2"], "");
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "3\n");
+
+    let output = glint(&["--eval", "write args", "--", "a", "-x"], "");
+    assert_eq!(stdout(&output), "[\"a\", \"-x\"]\n");
+}
+
+#[test]
+fn eval_conflicts_with_a_subcommand() {
+    let path = script("conflict.glt", "write \"ran the script\"\n");
+
+    for command in ["run", "check"] {
+        let output = glint(&["-e", "write 1", command, path.to_str().unwrap()], "");
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(stdout(&output), "");
+        assert!(
+            stderr(&output).contains("cannot be used with"),
+            "{}",
+            stderr(&output)
+        );
+    }
+}
+
+#[test]
+fn run_dash_reads_the_script_from_stdin() {
+    let output = glint(&["run", "-", "--", "first"], "write \"piped\", args\n");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "piped [\"first\"]\n");
+}
+
+#[test]
+fn arguments_after_the_separator_reach_the_script_untouched() {
+    let path = script("args.glt", "write args\n");
+
+    let output = glint(
+        &[
+            "run",
+            path.to_str().unwrap(),
+            "--",
+            "a",
+            "--dev",
+            "-e",
+            "--",
+            "b",
+        ],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "[\"a\", \"--dev\", \"-e\", \"--\", \"b\"]\n"
+    );
+}
+
+#[test]
+fn a_script_runs_directly_with_its_arguments() {
+    let path = script(
+        "direct.glt",
+        "#!/usr/bin/env glint\nwrite \"args:\", args\n",
+    );
+
+    // What a `#!/usr/bin/env glint` line expands to
+    let output = glint(&[path.to_str().unwrap(), "a", "b"], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "args: [\"a\", \"b\"]\n");
+
+    let output = glint(&["missing.glt"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("neither a command nor a script"));
+}
+
+#[cfg(unix)]
+#[test]
+fn an_executable_script_runs_through_its_shebang_line() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = script(
+        "shebang.glt",
+        "#!/usr/bin/env glint\nwrite \"args:\", args\n",
+    );
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    // `env` finds the freshly built binary first on the PATH
+    let bin = Path::new(env!("CARGO_BIN_EXE_glint")).parent().unwrap();
+    let path_var = std::env::join_paths(std::iter::once(bin.to_path_buf()).chain(
+        std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()),
+    ))
+    .unwrap();
+    let output = Command::new(&path)
+        .args(["one", "--two"])
+        .env("PATH", path_var)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "args: [\"one\", \"--two\"]\n");
+}
+
+#[test]
+fn scripts_read_their_arguments_and_environment() {
+    let path = script(
+        "environment.glt",
+        "write args\nwrite env(\"GLINT_TEST_SET\")\nwrite env(\"GLINT_TEST_UNSET\")\nwrite env(\"GLINT_TEST_UNSET\", \"fallback\")\nwrite env(\"GLINT_TEST_SET\", \"fallback\")\n",
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(["run", path.to_str().unwrap(), "--", "x", "y"])
+        .env("GLINT_TEST_SET", "value")
+        .env_remove("GLINT_TEST_UNSET")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "[\"x\", \"y\"]\nvalue\nnull\nfallback\nvalue\n"
+    );
+
+    let output = glint(&["-e", "write args"], "");
+    assert_eq!(stdout(&output), "[]\n");
+}
+
+#[test]
+fn exit_sets_the_process_exit_status() {
+    for (code, status) in [("exit()", 0), ("exit(3)", 3), ("exit(255)", 255)] {
+        let output = glint(&["-e", &format!("write 1\n{}\nwrite 2", code)], "");
+        assert_eq!(output.status.code(), Some(status), "{}", code);
+        assert_eq!(stdout(&output), "1\n");
+    }
+
+    // Codes a process cannot report are errors instead of wrapping around to success
+    for code in ["exit(256)", "exit(-1)"] {
+        let output = glint(&["-e", code], "");
+        assert_eq!(output.status.code(), Some(1), "{}", code);
+        assert!(
+            stderr(&output).contains("exit() expects a code from 0 to 255"),
+            "{}",
+            stderr(&output)
+        );
+    }
+}
+
+#[test]
+fn subcommands_parse_their_flags() {
+    let path = script("parse.glt", "write 5\n");
+    let path = path.to_str().unwrap();
+
+    let output = glint(&["fmt", "--check", path], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    let output = glint(&["lint", "--rules"], "");
+    assert!(stdout(&output).contains("unused-variable"));
+    let output = glint(&["completions", "bash"], "");
+    assert!(stdout(&output).contains("_glint()"));
+    let output = glint(&["--version"], "");
+    assert_eq!(
+        stdout(&output),
+        format!("glint {}\n", env!("CARGO_PKG_VERSION"))
+    );
+
+    // The legacy single-dash `-dev` still means `--dev`
+    let output = glint(&["-dev", "run", path], "");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "5\n");
+    assert!(stderr(&output).contains("Dev Info"));
+}
+
+#[test]
+fn usage_errors_exit_with_status_2() {
+    for command in ["fmt", "check", "lint", "ast", "debug", "new", "build"] {
+        let output = glint(&[command], "");
+        assert_eq!(output.status.code(), Some(2), "{}", command);
+        assert!(
+            stderr(&output).contains("required arguments were not provided"),
+            "{}: {}",
+            command,
+            stderr(&output)
+        );
+    }
+
+    for args in [
+        &["bench", "--runs", "0"][..],
+        &["completions", "nope"],
+        &["run", "x.glt", "--frobnicate"],
+    ] {
+        let output = glint(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert_eq!(stdout(&output), "");
+    }
+}
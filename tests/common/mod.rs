@@ -0,0 +1,66 @@
+// 🧰 Helpers shared by the integration tests; each test binary uses only some of them
+#![allow(dead_code)]
+
+use serde_json::Value as Json;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, ExitStatus, Stdio};
+
+use glint::lsp::{read_message, write_message};
+
+/// 📂 A fresh, empty directory for one test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("glint-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 📄 Writes a script to a fresh directory and returns its path
+pub fn script(name: &str, source: &str) -> PathBuf {
+    let path = temp_dir(name).join(name);
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+// 🔌 A `glint` subcommand (`lsp` or `dap`) spoken to with `Content-Length`-framed JSON
+pub struct Connection {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Connection {
+    /// 🚀 Starts `glint <command>` with piped stdin and stdout
+    pub fn start(command: &str) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_glint"))
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|err| panic!("failed to start glint {}: {}", command, err));
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Connection {
+            child,
+            stdin,
+            stdout,
+        }
+    }
+
+    pub fn send(&mut self, message: &Json) {
+        write_message(&mut self.stdin, message).unwrap();
+    }
+
+    /// 📥 The next message, panicking if the process closed its output
+    pub fn receive(&mut self) -> Json {
+        read_message(&mut self.stdout)
+            .unwrap()
+            .expect("glint closed the connection")
+    }
+
+    /// 🏁 Waits for the process to exit
+    pub fn wait(mut self) -> ExitStatus {
+        self.child.wait().unwrap()
+    }
+}
@@ -0,0 +1,282 @@
+use serde_json::{json, Value as Json};
+use std::path::Path;
+
+mod common;
+use common::{script, Connection};
+
+const SOURCE: &str = "\
+limit is 10
+add(a, b) {
+    total is a + b
+    return total
+}
+result is add(limit, 2)
+write result
+";
+
+// 🤖 A scripted DAP client talking to `glint dap`
+struct Client {
+    connection: Connection,
+    seq: u64,
+    events: Vec<Json>,
+}
+
+impl Client {
+    /// 🚀 Starts an adapter, launches `source` and sets breakpoints on `lines`
+    fn launch(name: &str, source: &str, stop_on_entry: bool, lines: &[u64]) -> Self {
+        let path = script(name, source);
+        let mut client = Client::start(&path, stop_on_entry);
+        let response = client.set_breakpoints(&path, lines);
+        assert!(response["breakpoints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|breakpoint| breakpoint["verified"] == true));
+        client.request("configurationDone", json!({}));
+        client
+    }
+
+    /// 🚀 Starts an adapter and launches the script at `path`, without running it yet
+    fn start(path: &Path, stop_on_entry: bool) -> Self {
+        let mut client = Client {
+            connection: Connection::start("dap"),
+            seq: 0,
+            events: Vec::new(),
+        };
+
+        let capabilities = client.request("initialize", json!({ "adapterID": "glint" }));
+        assert_eq!(capabilities["supportsConfigurationDoneRequest"], true);
+        client.request(
+            "launch",
+            json!({ "program": path, "stopOnEntry": stop_on_entry }),
+        );
+        client.event("initialized");
+        client
+    }
+
+    /// 🔴 Replaces the breakpoints of the file at `path`
+    fn set_breakpoints(&mut self, path: &Path, lines: &[u64]) -> Json {
+        let breakpoints: Vec<Json> = lines.iter().map(|line| json!({ "line": line })).collect();
+        self.request(
+            "setBreakpoints",
+            json!({ "source": { "path": path }, "breakpoints": breakpoints }),
+        )
+    }
+
+    fn request(&mut self, command: &str, arguments: Json) -> Json {
+        let response = self.call(command, arguments);
+        assert_eq!(response["success"], true, "{}", response);
+        response["body"].clone()
+    }
+
+    /// 📞 Sends a request and returns the whole response
+    fn call(&mut self, command: &str, arguments: Json) -> Json {
+        self.seq += 1;
+        let seq = self.seq;
+        let message =
+            json!({ "seq": seq, "type": "request", "command": command, "arguments": arguments });
+        self.connection.send(&message);
+        loop {
+            let message = self.connection.receive();
+            if message["type"] == "response" && message["request_seq"] == seq {
+                return message;
+            }
+            self.events.push(message);
+        }
+    }
+
+    /// 📬 Waits for the next event called `name`, skipping others
+    fn event(&mut self, name: &str) -> Json {
+        loop {
+            let message = match self.events.is_empty() {
+                true => self.connection.receive(),
+                false => self.events.remove(0),
+            };
+            if message["event"] == name {
+                return message["body"].clone();
+            }
+        }
+    }
+
+    /// ⏸️ Waits for the program to stop and returns the reason and the innermost frame's line
+    fn stopped(&mut self) -> (String, u64) {
+        let reason = self.event("stopped")["reason"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let frames = self.stack();
+        (reason, frames[0]["line"].as_u64().unwrap())
+    }
+
+    fn stack(&mut self) -> Vec<Json> {
+        let trace = self.request("stackTrace", json!({ "threadId": 1 }));
+        trace["stackFrames"].as_array().unwrap().clone()
+    }
+
+    /// 📋 The variables of the named scope of a frame, as `name = value` strings
+    fn scope(&mut self, frame: &Json, name: &str) -> Vec<String> {
+        let scopes = self.request("scopes", json!({ "frameId": frame["id"] }));
+        let scope = scopes["scopes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|scope| scope["name"] == name)
+            .unwrap()
+            .clone();
+        self.variables(&scope["variablesReference"])
+    }
+
+    fn variables(&mut self, reference: &Json) -> Vec<String> {
+        let variables = self.request("variables", json!({ "variablesReference": reference }));
+        variables["variables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variable| {
+                format!("{} = {}", variable["name"], variable["value"]).replace('"', "")
+            })
+            .collect()
+    }
+
+    /// 🏁 Waits for the program to end and disconnects; returns its exit code
+    fn finish(mut self) -> i64 {
+        let code = self.event("exited")["exitCode"].as_i64().unwrap();
+        self.event("terminated");
+        self.request("disconnect", json!({}));
+        assert!(self.connection.wait().success());
+        code
+    }
+}
+
+#[test]
+fn stops_at_breakpoints_and_shows_the_call_stack() {
+    // Line 2 is the function definition, so the breakpoint moves to its first statement
+    let mut client = Client::launch("breakpoints.glt", SOURCE, false, &[2, 7]);
+    assert_eq!(client.stopped(), ("breakpoint".to_string(), 3));
+
+    let frames = client.stack();
+    let names: Vec<&str> = frames
+        .iter()
+        .map(|frame| frame["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["add(a = 10, b = 2)", "<program>"]);
+    assert_eq!(frames[1]["line"], 6);
+    assert_eq!(frames[0]["source"]["name"], "breakpoints.glt");
+
+    assert_eq!(client.scope(&frames[0], "Locals"), ["a = 10", "b = 2"]);
+    assert_eq!(
+        client.scope(&frames[1], "Globals"),
+        ["args = []", "limit = 10"]
+    );
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.stopped(), ("breakpoint".to_string(), 7));
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.event("output")["output"], "12\n");
+    assert_eq!(client.finish(), 0);
+}
+
+#[test]
+fn steps_in_over_and_out() {
+    let mut client = Client::launch("stepping.glt", SOURCE, true, &[]);
+    assert_eq!(client.stopped(), ("entry".to_string(), 1));
+
+    client.request("next", json!({ "threadId": 1 }));
+    assert_eq!(client.stopped(), ("step".to_string(), 6));
+    client.request("stepIn", json!({ "threadId": 1 }));
+    assert_eq!(client.stopped(), ("step".to_string(), 3));
+    client.request("next", json!({ "threadId": 1 }));
+    assert_eq!(client.stopped(), ("step".to_string(), 4));
+    let frames = client.stack();
+    assert_eq!(
+        client.scope(&frames[0], "Locals"),
+        ["a = 10", "b = 2", "total = 12"]
+    );
+
+    client.request("stepOut", json!({ "threadId": 1 }));
+    assert_eq!(client.stopped(), ("step".to_string(), 7));
+    assert_eq!(client.stack().len(), 1);
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.finish(), 0);
+}
+
+#[test]
+fn evaluates_expressions_in_the_paused_frame() {
+    let mut client = Client::launch("evaluate.glt", SOURCE, false, &[4]);
+    assert_eq!(client.stopped(), ("breakpoint".to_string(), 4));
+
+    let value = client.request(
+        "evaluate",
+        json!({ "expression": "total * a", "frameId": 0 }),
+    );
+    assert_eq!(value["result"], "120");
+    let value = client.request("evaluate", json!({ "expression": "add(1, 2) + limit" }));
+    assert_eq!(value["result"], "13");
+
+    let value = client.request("evaluate", json!({ "expression": "[a, [b]]" }));
+    let reference = value["variablesReference"].clone();
+    assert_ne!(reference, 0);
+    assert_eq!(client.variables(&reference), ["[0] = 10", "[1] = [2]"]);
+
+    let missing = client.call("evaluate", json!({ "expression": "nope" }));
+    assert_eq!(missing["success"], false);
+    assert_eq!(missing["message"], "Undefined variable 'nope'");
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.finish(), 0);
+}
+
+#[test]
+fn reports_runtime_errors_and_exit_codes() {
+    let source = "write \"start\"\nwrite 1 / 0\n";
+    let mut client = Client::launch("error.glt", source, false, &[]);
+    assert_eq!(client.event("output")["output"], "start\n");
+    let error = client.event("output");
+    assert_eq!(error["category"], "stderr");
+    assert_eq!(error["output"], "Runtime error: Division by zero\n");
+    assert_eq!(client.finish(), 1);
+
+    let mut client = Client::launch("exit.glt", "exit(3)\n", false, &[]);
+    let paused = client.call("stackTrace", json!({ "threadId": 1 }));
+    assert_eq!(paused["success"], false);
+    assert_eq!(client.finish(), 3);
+}
+
+#[test]
+fn breakpoints_and_frames_name_the_module_file_they_are_in() {
+    let main = script(
+        "modules.glt",
+        "import utils\nwrite \"start\"\nwrite utils.double(2)\n",
+    );
+    let utils = main.with_file_name("utils.glt");
+    std::fs::write(&utils, "double(n) {\n    x is n * 2\n    return x\n}\n").unwrap();
+    let other = main.with_file_name("other.glt");
+    std::fs::write(&other, "write 1\n").unwrap();
+
+    let mut client = Client::start(&main, false);
+    let response = client.set_breakpoints(&utils, &[3]);
+    assert_eq!(response["breakpoints"][0]["verified"], true);
+    // Setting the program's breakpoints leaves the module's in place
+    client.set_breakpoints(&main, &[]);
+    let response = client.set_breakpoints(&other, &[1]);
+    assert_eq!(response["breakpoints"][0]["verified"], false);
+    client.request("configurationDone", json!({}));
+
+    // Line 3 of the module, not line 3 of the program
+    assert_eq!(client.event("output")["output"], "start\n");
+    assert_eq!(client.stopped(), ("breakpoint".to_string(), 3));
+    let frames = client.stack();
+    assert_eq!(frames[0]["name"], "utils.double(n = 2)");
+    assert_eq!(frames[0]["source"]["name"], "utils.glt");
+    assert_eq!(
+        frames[0]["source"]["path"],
+        json!(utils.canonicalize().unwrap())
+    );
+    assert_eq!(frames[1]["line"], 3);
+    assert_eq!(frames[1]["source"]["name"], "modules.glt");
+
+    client.request("continue", json!({ "threadId": 1 }));
+    assert_eq!(client.event("output")["output"], "4\n");
+    assert_eq!(client.finish(), 0);
+}
@@ -0,0 +1,148 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+mod common;
+use common::script;
+
+const SOURCE: &str = "\
+limit is 10
+add(a, b) {
+    total is a + b
+    return total
+}
+result is add(limit, 2)
+write result
+";
+
+/// 🐞 Runs `glint debug` on `source`, typing `commands`; returns stdout and the exit code
+fn debug(name: &str, source: &str, commands: &str) -> (String, i32) {
+    debug_file(&script(name, source), commands)
+}
+
+/// 🐞 Runs `glint debug` on the script at `path`, typing `commands`
+fn debug_file(path: &Path, commands: &str) -> (String, i32) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .arg("debug")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to start glint debug");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(commands.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Drop the banner naming the temporary file
+    let stdout = stdout.split_once('\n').unwrap().1.to_string();
+    (stdout, output.status.code().unwrap())
+}
+
+#[test]
+fn breaks_and_inspects_the_current_frame() {
+    let (stdout, code) = debug(
+        "break.glt",
+        SOURCE,
+        "break 2\ncontinue\nbacktrace\nlocals\nprint a * b + limit\nprint total\ncontinue\n",
+    );
+    assert_eq!(
+        stdout,
+        "   1 | limit is 10
+(glint) Breakpoint set on line 3
+(glint) Breakpoint, line 3 in add(a = 10, b = 2)
+   3 |     total is a + b
+(glint) #0 add(a = 10, b = 2) at line 3
+#1 <program> at line 6
+(glint) a = 10
+b = 2
+(glint) 30
+(glint) Error: Undefined variable 'total'
+(glint) 12
+"
+    );
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn steps_into_and_over_calls() {
+    let (stdout, _) = debug("step.glt", SOURCE, "next\nstep\nnext\nnext\nlocals\nquit\n");
+    assert_eq!(
+        stdout,
+        "   1 | limit is 10
+(glint)    6 | result is add(limit, 2)
+(glint)    3 |     total is a + b
+(glint)    4 |     return total
+(glint)    7 | write result
+(glint) args = []
+limit = 10
+result = 12
+(glint) "
+    );
+}
+
+#[test]
+fn reports_bad_commands_and_exits_like_the_script() {
+    let (stdout, code) = debug(
+        "exit.glt",
+        "write 1\nexit(4)\n",
+        "break 9\nbreak x\nfrobnicate\ncontinue\n",
+    );
+    assert_eq!(
+        stdout,
+        "   1 | write 1
+(glint) No statement on or after line 9
+(glint) Usage: break [<file>:]<line>
+(glint) Unknown command 'frobnicate'. Type help for a list.
+(glint) 1
+"
+    );
+    assert_eq!(code, 4);
+}
+
+#[test]
+fn breakpoints_tell_the_program_from_the_modules_it_imports() {
+    let main = script(
+        "modules.glt",
+        "import utils\nwrite \"start\"\nwrite utils.double(2)\n",
+    );
+    let dir = main.parent().unwrap();
+    std::fs::write(
+        dir.join("utils.glt"),
+        "double(n) {\n    x is n * 2\n    return x\n}\n",
+    )
+    .unwrap();
+
+    // Line 3 of the program and line 3 of the module are different breakpoints
+    let (stdout, _) = debug_file(&main, "break utils.glt:3\ncontinue\nbacktrace\ncontinue\n");
+    // The module's name is its path, which is in the temporary directory
+    let stdout = stdout.replace(&format!("{}/", dir.display()), "");
+    assert_eq!(
+        stdout,
+        "   2 | write \"start\"
+(glint) Breakpoint set on line 3 of utils.glt
+(glint) start
+Breakpoint, line 3 of utils.glt in utils.double(n = 2)
+utils.glt    3 |     return x
+(glint) #0 utils.double(n = 2) at line 3 of utils.glt
+#1 <program> at line 3
+(glint) 4
+"
+    );
+
+    let (stdout, _) = debug_file(&main, "break 3\ncontinue\nbreak nope.glt:1\ncontinue\n");
+    assert_eq!(
+        stdout,
+        "   2 | write \"start\"
+(glint) Breakpoint set on line 3
+(glint) start
+Breakpoint, line 3 in <program>
+   3 | write utils.double(2)
+(glint) No imported module 'nope.glt'
+(glint) 4
+"
+    );
+}
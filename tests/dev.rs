@@ -0,0 +1,83 @@
+use serde_json::Value as Json;
+use std::process::Command;
+
+use glint::parser::parser::parse_program;
+
+mod common;
+
+/// 📊 Runs `glint run <script> --dev-json` and returns stdout and the parsed report
+/// (the last line of stderr, after any error messages)
+fn dev_report(name: &str, source: &str) -> (String, Json) {
+    let script = common::script(name, source);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .arg("run")
+        .arg(&script)
+        .arg("--dev-json")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let report = serde_json::from_str(stderr.lines().last().unwrap()).expect("a JSON report");
+    (stdout, report)
+}
+
+#[test]
+fn reports_every_phase_after_the_script_ran() {
+    let (stdout, report) = dev_report("phases.glt", "x is 1 + 2\nwrite x\n");
+    assert_eq!(stdout, "3\n");
+
+    let phases: Vec<&str> = report["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases, ["read", "parse", "link", "serialize", "interpret"]);
+    for phase in report["phases"].as_array().unwrap() {
+        assert!(phase["secs"].as_f64().unwrap() >= 0.0);
+        assert!(phase["peak_bytes"].as_u64().unwrap() >= phase["current_bytes"].as_u64().unwrap());
+    }
+    assert_eq!(report["ast_nodes"], 7);
+
+    // The whole run includes the phases, and the overall peak covers every phase's
+    let phase_secs: f64 = report["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["secs"].as_f64().unwrap())
+        .sum();
+    assert!(report["elapsed_secs"].as_f64().unwrap() >= phase_secs);
+    let phase_peak = report["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["peak_bytes"].as_u64().unwrap())
+        .max()
+        .unwrap();
+    assert!(report["peak_bytes"].as_u64().unwrap() >= phase_peak);
+}
+
+#[test]
+fn reports_even_when_the_script_fails() {
+    let (_, report) = dev_report("fails.glt", "write 1 / 0\n");
+    assert_eq!(report["phases"].as_array().unwrap().len(), 5);
+
+    let (_, report) = dev_report("broken.glt", "write (\n");
+    let phases: Vec<&str> = report["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases, ["read", "parse"]);
+    assert_eq!(report["ast_nodes"], Json::Null);
+}
+
+#[test]
+fn counts_ast_nodes_without_position_wrappers() {
+    let ast = parse_program("add(a, b) {\n    return a + b\n}\nwrite add(1, 2)\n").unwrap();
+    // Program, Function, FunctionArgs, a, b, Block, Return, BinaryOp, a, b,
+    // Write, FunctionCall, 1, 2
+    assert_eq!(ast.node_count(), 14);
+}
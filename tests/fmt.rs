@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glint::formatter::format_source;
+use glint::parser::parser::parse_program;
+
+/// 📂 The scripts in a directory of the repository
+fn scripts_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("{}: {}", dir.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "glt"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 📚 Every example script in the repository
+fn examples() -> Vec<PathBuf> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut files = scripts_in(&root.join("examples"));
+    files.push(root.join("input.glt"));
+    files
+}
+
+/// 📚 The examples along with the golden tests and the benchmark programs, which are not all
+/// formatted (some spell things the way the formatter would not, on purpose)
+fn all_scripts() -> Vec<PathBuf> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut files = examples();
+    files.extend(scripts_in(&root.join("tests")));
+    files.extend(scripts_in(&root.join("benches")));
+    files
+}
+
+#[test]
+fn formatted_scripts_parse_to_the_same_ast() {
+    for file in all_scripts() {
+        let source = fs::read_to_string(&file).unwrap();
+        let formatted = format_source(&source).unwrap();
+        let before = parse_program(&source).unwrap().without_locations();
+        let after = parse_program(&formatted)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "{}: formatted output does not parse: {}",
+                    file.display(),
+                    err
+                )
+            })
+            .without_locations();
+        assert_eq!(
+            before,
+            after,
+            "{}: formatting changed the AST",
+            file.display()
+        );
+        assert_eq!(
+            format_source(&formatted).unwrap(),
+            formatted,
+            "{}: formatting is not idempotent",
+            file.display()
+        );
+    }
+}
+
+#[test]
+fn examples_are_formatted() {
+    for file in examples() {
+        let source = fs::read_to_string(&file).unwrap();
+        assert_eq!(
+            format_source(&source).unwrap(),
+            source,
+            "{}",
+            file.display()
+        );
+    }
+}
+
+#[test]
+fn normalizes_spacing_and_indentation_and_keeps_comments() {
+    let source = "#!/usr/bin/env glint\nx    is   5   # five\n    write x,x+1 ,  (x+1)*2\n\n\n\nf(a,b){\n# inside\n  if a>b{ return a-b } else { return b-(a-1) }\n}\n";
+    let expected = "#!/usr/bin/env glint\nx is 5 # five\nwrite x, x + 1, (x + 1) * 2\n\nf(a, b) {\n    # inside\n    if a > b {\n        return a - b\n    } else {\n        return b - (a - 1)\n    }\n}\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+}
+
+#[test]
+fn keeps_floats_and_needed_parentheses() {
+    let source = "x is 3.0 - (2 - 1) * (4 / 2)\n";
+    assert_eq!(
+        format_source(source).unwrap(),
+        "x is 3.0 - (2 - 1) * (4 / 2)\n"
+    );
+}
+
+#[test]
+fn formats_lambdas_and_calls_of_expressions() {
+    let source = "f is fn(x){\nif x>1 { return x }\nreturn (y)=>y*x}\nwrite f(2)(3), ((a,b)=>a+b)(1,2)\n";
+    let expected = "f is fn(x) {\n    if x > 1 {\n        return x\n    }\n    return y => y * x\n}\nwrite f(2)(3), ((a, b) => a + b)(1, 2)\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+}
+
+#[test]
+fn formats_indexes_slices_and_element_assignments() {
+    let source = "x is rows[i+1]\nx[0][-1] is a[ 1 : ]+a[:2]\nd[\"k\"].v is f(1).name\nwrite (d).k, d.k, -1.5\n";
+    let expected = "x is rows[i + 1]\nx[0][-1] is a[1:] + a[:2]\nd[\"k\"].v is f(1).name\nwrite (d).k, d.k, -1.5\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn formats_coincide_patterns_and_block_arms() {
+    let source = "coincide x+1:\n  1,2 then write 1\n  4 .. 9 then {\n  write x\n  }\n  [a,_] then write a\n  string then write {k : v}\n  default {\n}\n";
+    let expected = "coincide x + 1:\n    1, 2 then write 1\n    4..9 then {\n        write x\n    }\n    [a, _] then write a\n    string then write {k: v}\n    default {}\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn keeps_comments_between_coincide_arms() {
+    let source = "coincide x:   # header\n# one\n  1 then write 1   # trailing\n  2 then {\n  write 2\n  } # block\n      # default\n  default write 0\n# after\n";
+    let expected = "coincide x: # header\n    # one\n    1 then write 1 # trailing\n    2 then {\n        write 2\n    } # block\n    # default\n    default write 0\n# after\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn formats_tuples_and_destructuring() {
+    let source = "(a,b) is f( (1,) )\nreturn a,b\nt is ( a+1 , \"x\" )\n";
+    let expected = "a, b is f((1,))\nreturn (a, b)\nt is (a + 1, \"x\")\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn formats_structs_and_constructions() {
+    let source = "struct   Point{x,y :int ,}\np is Point( x:1,y : 2+3 )\n";
+    let expected = "struct Point { x, y: int }\np is Point(x: 1, y: 2 + 3)\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn formats_impl_blocks() {
+    let source = "impl   Point{\nlen( self ){\nreturn self.x+self.y\n}\n}\nwrite p.len( ),\"a\".upper()\n";
+    let expected = "impl Point {\n    len(self) {\n        return self.x + self.y\n    }\n}\nwrite p.len(), \"a\".upper()\n";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
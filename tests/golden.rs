@@ -0,0 +1,122 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use glint::golden::diff_lines;
+
+mod common;
+use common::temp_dir;
+
+fn glint_test(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glint"))
+        .arg("test")
+        .args(args)
+        .arg(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn the_golden_tests_pass() {
+    let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let output = glint_test(&[], &tests);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("PASS"));
+    assert!(stdout.trim_end().ends_with(" passed, 0 failed"));
+}
+
+#[test]
+fn failures_show_a_diff_per_stream() {
+    let dir = temp_dir("fail");
+    std::fs::write(
+        dir.join("annotated.glt"),
+        "write 1 # expect: 1\nwrite 3 # expect: 2\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("files.glt"), "write \"out\"\nwrite missing\n").unwrap();
+    std::fs::write(dir.join("files.out"), "out\n").unwrap();
+
+    let output = glint_test(&[], &dir);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("--- expected stdout\n+++ actual stdout\n 1\n-2\n+3\n"));
+    // A missing `.err` file expects nothing on stderr
+    assert!(stdout.contains(
+        "--- expected stderr\n+++ actual stderr\n+Runtime error: Undefined variable 'missing'\n"
+    ));
+    assert!(stdout.trim_end().ends_with("0 passed, 2 failed"));
+}
+
+#[test]
+fn bless_rewrites_annotations_and_expectation_files() {
+    let dir = temp_dir("bless");
+    let annotated = dir.join("annotated.glt");
+    std::fs::write(
+        &annotated,
+        "write 1 # expect: 0\n# expect: stale\nwrite 2, 3\n    # expect: gone\nwrite missing\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("files.glt"), "write 2 * 21\n").unwrap();
+    std::fs::write(dir.join("files.err"), "stale\n").unwrap();
+
+    let output = glint_test(&["--bless"], &dir);
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(&annotated).unwrap(),
+        "write 1 # expect: 1\n# expect: 2 3\nwrite 2, 3\nwrite missing\n\n# expect-error: Runtime error: Undefined variable 'missing'\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("files.out")).unwrap(),
+        "42\n"
+    );
+    assert!(!dir.join("files.err").exists());
+
+    assert!(glint_test(&[], &dir).status.success());
+}
+
+#[test]
+fn bless_keeps_each_annotation_on_its_statement() {
+    let dir = temp_dir("bless-in-place");
+    let script = dir.join("coincide.glt");
+    std::fs::write(
+        &script,
+        "\
+describe(x) {
+    coincide x:
+        0 then return \"zero\"
+        default return \"other\"
+}
+write describe(0) # expect: nothing
+# A comment between the checks
+write describe(1) # expect: other
+write describe(2)
+",
+    )
+    .unwrap();
+
+    assert!(glint_test(&["--bless"], &dir).status.success());
+    assert_eq!(
+        std::fs::read_to_string(&script).unwrap(),
+        "\
+describe(x) {
+    coincide x:
+        0 then return \"zero\"
+        default return \"other\"
+}
+write describe(0) # expect: zero
+# A comment between the checks
+write describe(1) # expect: other
+write describe(2)
+
+# expect: other
+"
+    );
+    assert!(glint_test(&[], &dir).status.success());
+}
+
+#[test]
+fn diffs_lines() {
+    assert_eq!(diff_lines("a\nb\nc\n", "a\nc\nd\n"), " a\n-b\n c\n+d\n");
+    assert_eq!(diff_lines("x\n", "y\n"), "-x\n+y\n");
+    assert_eq!(diff_lines("a\n", "a"), " a\n\\ the final newline differs\n");
+}
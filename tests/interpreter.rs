@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use glint::error::RuntimeError;
+use glint::interpreter::interpreter::Interpreter;
+use glint::parser::parser::parse_program;
+
+// 📥 A writer that keeps what the program writes
+struct Captured(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// ▶️ Runs a program and returns what it wrote along with how it finished
+fn run(source: &str) -> (String, Result<(), RuntimeError>) {
+    let ast = parse_program(source).unwrap();
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(Captured(output.clone())));
+    interpreter.load_from_json(&serde_json::to_string(&ast).unwrap());
+    let result = interpreter.interpret();
+    drop(interpreter);
+    let output = String::from_utf8(output.borrow().clone()).unwrap();
+    (output, result)
+}
+
+/// ❌ The error a program stops with
+fn error(source: &str) -> RuntimeError {
+    run(source).1.unwrap_err()
+}
+
+#[test]
+fn write_prints_each_value_separated_by_spaces() {
+    let (output, result) =
+        run("write 1, 2.5, true, false, \"a b\"\nwrite [1, \"x\"], {\"k\": 1}\n");
+    assert_eq!(result, Ok(()));
+    assert_eq!(output, "1 2.5 true false a b\n[1, \"x\"] {k: 1}\n");
+}
+
+#[test]
+fn arithmetic_keeps_integers_and_floats_apart() {
+    let (output, _) = run("write 7 / 2, 7.0 / 2, 1 + 2 * 3, 2 - 5, 1 + 0.5, \"a\" + \"b\"\n");
+    assert_eq!(output, "3 3.5 7 -3 1.5 ab\n");
+}
+
+#[test]
+fn functions_take_expressions_as_arguments_and_return_null_without_return() {
+    let source = "fib(n) {\n    if n < 2 {\n        return n\n    }\n    return fib(n - 1) + fib(n - 2)\n}\nnothing() {\n    x is 1\n}\nwrite fib(10), nothing()\n";
+    assert_eq!(run(source).0, "55 null\n");
+}
+
+#[test]
+fn functions_get_their_own_locals() {
+    let source = "x is 1\nf(x) {\n    y is x + 1\n    return y\n}\nwrite f(10), x\nwrite y\n";
+    let (output, result) = run(source);
+    assert_eq!(output, "11 1\n");
+    assert_eq!(
+        result,
+        Err(RuntimeError::UndefinedVariable {
+            name: "y".to_string()
+        })
+    );
+}
+
+#[test]
+fn reports_runtime_errors() {
+    assert_eq!(error("write 1 / 0\n"), RuntimeError::DivisionByZero);
+    assert_eq!(error("write 1.0 / 0\n"), RuntimeError::DivisionByZero);
+    assert_eq!(
+        error("x is 2000000 * 2000000\nwrite x * x\n"),
+        RuntimeError::Overflow {
+            op: "*".to_string()
+        }
+    );
+    assert_eq!(
+        error("nope()\n"),
+        RuntimeError::UndefinedFunction {
+            name: "nope".to_string()
+        }
+    );
+    assert_eq!(
+        error("f(a) {\n    return a\n}\nf(1, 2)\n"),
+        RuntimeError::ArityMismatch {
+            name: "f".to_string(),
+            expected: 1,
+            found: 2
+        }
+    );
+    assert_eq!(
+        error("write \"a\" - 1\n").to_string(),
+        "Type error: unsupported operand types for '-': string and int"
+    );
+}
+
+#[test]
+fn output_before_an_error_is_kept() {
+    let (output, result) = run("write \"before\"\nwrite 1 / 0\nwrite \"after\"\n");
+    assert_eq!(output, "before\n");
+    assert_eq!(result, Err(RuntimeError::DivisionByZero));
+}
@@ -0,0 +1,210 @@
+#![cfg(feature = "jit")]
+
+use std::process::{Command, Output};
+
+mod common;
+use common::script;
+
+/// ⚡ Runs `glint run <script> --jit-stats` and returns the output, with stderr holding the report
+fn run_with_stats(name: &str, source: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(["--no-color", "run"])
+        .arg(script(name, source))
+        .arg("--jit-stats")
+        .output()
+        .unwrap()
+}
+
+/// 📊 The report line of the function `name`
+fn stats_for(output: &Output, name: &str) -> String {
+    let prefix = format!("└─ {}:", name);
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .find(|line| line.trim_start().starts_with(&prefix))
+        .unwrap_or_else(|| panic!("no stats for {}", name))
+        .to_string()
+}
+
+/// 🔁 A driver that calls `body` for n down to 1; its string argument keeps it interpreted
+fn driver(body: &str) -> String {
+    format!(
+        "drive(n, tag) {{\n    if n = 0 {{\n        return 0\n    }}\n    return {} + drive(n - 1, tag)\n}}\n",
+        body
+    )
+}
+
+#[test]
+fn compiles_a_function_once_it_reaches_the_threshold() {
+    let functions = "sq(n) {\n    return n * n\n}\n";
+
+    // One call short of the threshold, everything is interpreted
+    let output = run_with_stats(
+        "below.glt",
+        &format!("{}{}write drive(49, \"\")\n", functions, driver("sq(n)")),
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "40425\n");
+    assert_eq!(
+        stats_for(&output, "sq"),
+        "  └─ sq: calls 49, native 0, guard fallbacks 0, deopts 0 [interpreted]"
+    );
+
+    // The call that reaches it is the first one to run natively
+    let output = run_with_stats(
+        "threshold.glt",
+        &format!("{}{}write drive(50, \"\")\n", functions, driver("sq(n)")),
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42925\n");
+    assert_eq!(
+        stats_for(&output, "sq"),
+        "  └─ sq: calls 50, native 1, guard fallbacks 0, deopts 0 [compiled]"
+    );
+    assert_eq!(
+        stats_for(&output, "drive"),
+        "  └─ drive: calls 51, native 0, guard fallbacks 0, deopts 0 [not compiled: called with non-integer arguments]"
+    );
+}
+
+#[test]
+fn falls_back_to_the_interpreter_for_arguments_that_are_not_integers() {
+    let source = format!(
+        "sq(n) {{\n    return n * n\n}}\n{}drive(50, \"\")\nwrite sq(3), sq(2.5), sq(4)\n",
+        driver("sq(n)")
+    );
+    let output = run_with_stats("fallback.glt", &source);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "9 6.25 16\n");
+    assert_eq!(
+        stats_for(&output, "sq"),
+        "  └─ sq: calls 53, native 3, guard fallbacks 1, deopts 0 [compiled]"
+    );
+}
+
+#[test]
+fn deopts_on_overflow_and_lets_the_interpreter_report_it() {
+    let source = format!(
+        "sq(n) {{\n    return n * n\n}}\n{}drive(50, \"\")\nwrite sq(3000 * 1000000)\nwrite sq(5000 * 1000000)\n",
+        driver("sq(n)")
+    );
+    let output = run_with_stats("overflow.glt", &source);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "9000000000000000000\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Runtime error: Integer overflow"));
+    assert_eq!(
+        stats_for(&output, "sq"),
+        "  └─ sq: calls 52, native 2, guard fallbacks 0, deopts 1 [compiled]"
+    );
+}
+
+#[test]
+fn deopts_on_division_by_zero_inside_a_compiled_callee() {
+    let source = format!(
+        "div(a, b) {{\n    return a / b\n}}\nhalf(n) {{\n    return div(n, 2) + div(n, n - 1)\n}}\n{}drive(50, \"\")\nwrite half(1)\n",
+        driver("half(n + 1)")
+    );
+    let output = run_with_stats("division.glt", &source);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Runtime error: Division by zero"));
+    // The deopt in `div` unwinds through `half`, which is retried by the interpreter
+    assert_eq!(
+        stats_for(&output, "half"),
+        "  └─ half: calls 51, native 1, guard fallbacks 0, deopts 1 [compiled]"
+    );
+    assert!(stats_for(&output, "div").ends_with("[compiled]"));
+}
+
+#[test]
+fn reports_the_threshold_the_compiled_functions_and_why_others_were_not_compiled() {
+    let source = format!(
+        "sq(n) {{\n    return n * n\n}}\nloud(n) {{\n    write n\n    return n\n}}\n{}drive(50, \"\")\nloud(1)\n",
+        driver("sq(n)")
+    );
+    let output = run_with_stats("report.glt", &source);
+    let stats = String::from_utf8(output.stderr.clone()).unwrap();
+    let lines: Vec<&str> = stats.lines().collect();
+    assert_eq!(lines[0], "<=>  JIT Stats  <=>");
+    assert_eq!(lines[1], "Threshold: 50 calls");
+    assert!(
+        lines[2].starts_with("Compiled functions: 1 ("),
+        "{}",
+        lines[2]
+    );
+    assert_eq!(lines[3], "Functions:");
+    assert_eq!(lines.last(), Some(&"<=>  End JIT Stats  <=>"));
+    assert_eq!(
+        stats_for(&output, "loud"),
+        "  └─ loud: calls 1, native 0, guard fallbacks 0, deopts 0 [interpreted]"
+    );
+    assert!(stats_for(&output, "sq").ends_with("[compiled]"));
+}
+
+#[test]
+fn does_not_compile_functions_outside_the_subset() {
+    let source = format!(
+        "loud(n) {{\n    write n\n    return n\n}}\n{}drive(50, \"\")\n",
+        driver("loud(0)")
+    );
+    let output = run_with_stats("rejected.glt", &source);
+    assert_eq!(
+        stats_for(&output, "loud"),
+        "  └─ loud: calls 50, native 0, guard fallbacks 0, deopts 0 [not compiled: `loud` performs I/O (`write`)]"
+    );
+}
+
+#[test]
+fn does_not_compile_a_call_that_builds_a_struct_of_the_same_name() {
+    let source = "\
+struct Pair { a: int, b: int }
+Pair(a, b) {
+    return a + b
+}
+caller(n) {
+    return Pair(n, n)
+}
+warm(n) {
+    if n = 0 {
+        return 0
+    }
+    caller(n)
+    return warm(n - 1)
+}
+warm(60)
+write caller(1)
+";
+    let output = run_with_stats("struct.glt", source);
+    // The interpreter builds the struct; native code would have called the function
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Pair(a: 1, b: 1)\n"
+    );
+    assert!(stats_for(&output, "caller")
+        .ends_with("[not compiled: `caller` constructs the struct `Pair`]"));
+}
+
+#[test]
+fn a_global_function_value_hides_a_compiled_callee() {
+    let source = "\
+sq(n) {
+    return n * n
+}
+caller(n) {
+    return sq(n) + 1
+}
+warm(n) {
+    if n = 0 {
+        return 0
+    }
+    return caller(2) + warm(n - 1)
+}
+write warm(60)
+sq is x => 0
+write caller(5)
+";
+    let output = run_with_stats("shadowed.glt", source);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "300\n1\n");
+    // `caller` was compiled, then fell back to the interpreter once `sq` was hidden
+    let caller = stats_for(&output, "caller");
+    assert!(caller.contains("guard fallbacks 1"), "{}", caller);
+    assert!(caller.ends_with("[compiled]"), "{}", caller);
+}
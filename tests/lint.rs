@@ -0,0 +1,137 @@
+use glint::checker::Severity;
+use glint::linter::{lint_program, Level, LintConfig};
+use glint::parser::parser::parse_program;
+
+/// 🧹 Lints `source` and returns `(line, rule)` for every finding
+fn findings(source: &str, config: &LintConfig) -> Vec<(usize, &'static str)> {
+    let ast = parse_program(source).unwrap();
+    lint_program(&ast, Some(source), config)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.span.line, diagnostic.rule.unwrap_or("")))
+        .collect()
+}
+
+#[test]
+fn reports_each_rule() {
+    let source = "\
+limit is 10
+unused is 3
+f(a, limit) {
+    return a
+    write \"never\"
+}
+f(a) {
+    return a
+}
+if 1 = 1 {
+    write f(limit)
+}
+coincide limit:
+    1 then write \"one\"
+";
+    assert_eq!(
+        findings(source, &LintConfig::default()),
+        vec![
+            (2, "unused-variable"),
+            (3, "unused-variable"),
+            (3, "shadowed-parameter"),
+            (5, "unreachable-code"),
+            (7, "duplicate-function"),
+            (10, "constant-condition"),
+            (13, "coincide-without-default"),
+        ]
+    );
+}
+
+#[test]
+fn reports_unused_functions_but_not_recursive_ones_that_are_called() {
+    let source = "\
+count(n) {
+    if n = 0 {
+        return 0
+    }
+    return count(n - 1)
+}
+loop(n) {
+    return loop(n)
+}
+write count(3)
+";
+    assert_eq!(
+        findings(source, &LintConfig::default()),
+        vec![(7, "unused-function")]
+    );
+}
+
+#[test]
+fn methods_are_not_unused_functions_and_need_not_read_self() {
+    let source = "\
+struct Point { x, y }
+impl Point {
+    origin(self) {
+        return Point(0, 0)
+    }
+    scaled(self, by) {
+        return self
+    }
+}
+";
+    assert_eq!(
+        findings(source, &LintConfig::default()),
+        vec![(6, "unused-variable")]
+    );
+}
+
+#[test]
+fn duplicate_functions_are_errors_by_default() {
+    let source = "f() {\n    return 1\n}\nf() {\n    return 2\n}\nwrite f()\n";
+    let ast = parse_program(source).unwrap();
+    let diagnostics = lint_program(&ast, Some(source), &LintConfig::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(
+        diagnostics[0].message,
+        "function 'f' is already defined on line 1"
+    );
+}
+
+#[test]
+fn comments_suppress_or_enable_rules() {
+    let source = "\
+# glint-file: allow(unused-variable)
+a is 1
+b is 2 # glint: deny(unused-variable)
+# glint: allow(constant-condition, coincide-without-default)
+if 1 < 2 {
+    write \"yes\"
+}
+helper() { # glint: allow(unused-function)
+    return 1
+}
+";
+    let ast = parse_program(source).unwrap();
+    let diagnostics = lint_program(&ast, Some(source), &LintConfig::default());
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.span.line, diagnostic.severity))
+        .collect();
+    assert_eq!(found, vec![(3, Severity::Error)]);
+}
+
+#[test]
+fn config_sets_rule_levels() {
+    let config = LintConfig::from_toml(
+        "[package]\nname = \"demo\"\n\n[lint]\nunused-variable = \"allow\"\nconstant-condition = \"deny\"\n",
+    )
+    .unwrap();
+    assert_eq!(config.level("unused-variable"), Level::Allow);
+    assert_eq!(config.level("constant-condition"), Level::Deny);
+    assert_eq!(config.level("unreachable-code"), Level::Warn);
+    assert_eq!(
+        findings("x is 1\nif 2 > 1 {\n    write x\n}\n", &config),
+        vec![(2, "constant-condition")]
+    );
+
+    let err = LintConfig::from_toml("[lint]\nno-such-rule = \"warn\"\n").unwrap_err();
+    assert_eq!(err, "unknown lint rule 'no-such-rule'");
+}
@@ -0,0 +1,258 @@
+use serde_json::{json, Value as Json};
+
+mod common;
+use common::Connection;
+
+const URI: &str = "file:///tmp/glint-lsp-test/main.glt";
+
+const SOURCE: &str = "\
+limit is 10
+add(a, b) {
+    total is a + b
+    return total
+}
+write add(limit, 2)
+";
+
+// 🤖 A scripted JSON-RPC client talking to `glint lsp`
+struct Client {
+    connection: Connection,
+    next_id: u64,
+    notifications: Vec<Json>,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut client = Client {
+            connection: Connection::start("lsp"),
+            next_id: 0,
+            notifications: Vec::new(),
+        };
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["serverInfo"]["name"], "glint");
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: Json) -> Json {
+        self.call(method, params)["result"].clone()
+    }
+
+    /// 📞 Sends a request and returns the whole response (result or error)
+    fn call(&mut self, method: &str, params: Json) -> Json {
+        self.next_id += 1;
+        let id = self.next_id;
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        self.connection.send(&message);
+        loop {
+            let message = self.connection.receive();
+            if message["id"] == id {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Json) {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        self.connection.send(&message);
+    }
+
+    /// 📬 Waits for the next `publishDiagnostics` notification
+    fn diagnostics(&mut self) -> Vec<Json> {
+        loop {
+            let message = match self.notifications.pop() {
+                Some(message) => message,
+                None => self.connection.receive(),
+            };
+            if message["method"] == "textDocument/publishDiagnostics" {
+                return message["params"]["diagnostics"].as_array().unwrap().clone();
+            }
+        }
+    }
+
+    fn open(&mut self, text: &str) -> Vec<Json> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": URI, "languageId": "glint", "version": 1, "text": text } }),
+        );
+        self.diagnostics()
+    }
+
+    fn at(&mut self, method: &str, line: u64, character: u64) -> Json {
+        self.request(
+            method,
+            json!({
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+            }),
+        )
+    }
+
+    fn shutdown(mut self) {
+        assert_eq!(self.request("shutdown", Json::Null), Json::Null);
+        self.notify("exit", Json::Null);
+        assert!(self.connection.wait().success());
+    }
+}
+
+#[test]
+fn publishes_diagnostics_on_open_and_change() {
+    let mut client = Client::start();
+    assert_eq!(client.open(SOURCE), Vec::<Json>::new());
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "write missing\nunused is 1\n" }],
+        }),
+    );
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0]["message"], "Undefined variable 'missing'");
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(
+        diagnostics[0]["range"],
+        json!({ "start": { "line": 0, "character": 6 }, "end": { "line": 0, "character": 13 } })
+    );
+    assert_eq!(diagnostics[1]["code"], "unused-variable");
+    assert_eq!(diagnostics[1]["severity"], 2);
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "text": "write 1\n) oops\n" }],
+        }),
+    );
+    let diagnostics = client.diagnostics();
+    assert_eq!(
+        diagnostics[0]["range"]["start"],
+        json!({ "line": 1, "character": 0 })
+    );
+    client.shutdown();
+}
+
+#[test]
+fn goes_to_definitions_of_functions_and_variables() {
+    let mut client = Client::start();
+    client.open(SOURCE);
+
+    // `add` in `write add(limit, 2)`
+    let definition = client.at("textDocument/definition", 5, 7);
+    assert_eq!(definition["uri"], URI);
+    assert_eq!(
+        definition["range"]["start"],
+        json!({ "line": 1, "character": 0 })
+    );
+
+    // `limit` in the same call resolves to the global assignment
+    let definition = client.at("textDocument/definition", 5, 12);
+    assert_eq!(
+        definition["range"]["start"],
+        json!({ "line": 0, "character": 0 })
+    );
+
+    // `b` inside `add` resolves to the parameter
+    let definition = client.at("textDocument/definition", 2, 17);
+    assert_eq!(
+        definition["range"]["start"],
+        json!({ "line": 1, "character": 7 })
+    );
+
+    // `total` in `return total` resolves to the local assignment
+    let definition = client.at("textDocument/definition", 3, 12);
+    assert_eq!(
+        definition["range"]["start"],
+        json!({ "line": 2, "character": 4 })
+    );
+    client.shutdown();
+}
+
+#[test]
+fn hovers_completes_and_lists_symbols() {
+    let mut client = Client::start();
+    client.open(SOURCE);
+
+    let hover = client.at("textDocument/hover", 5, 7);
+    assert_eq!(hover["contents"]["value"], "```glint\nadd(a, b)\n```");
+    let hover = client.at("textDocument/hover", 5, 0);
+    assert_eq!(hover, Json::Null);
+
+    let completion = client.at("textDocument/completion", 5, 0);
+    let labels: Vec<&str> = completion
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    for expected in ["write", "coincide", "env", "exit", "add", "limit", "args"] {
+        assert!(labels.contains(&expected), "missing {}", expected);
+    }
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+    assert_eq!(symbols[0]["name"], "add");
+    assert_eq!(symbols[0]["detail"], "(a, b)");
+    assert_eq!(
+        symbols[0]["range"]["end"],
+        json!({ "line": 4, "character": 1 })
+    );
+    client.shutdown();
+}
+
+#[test]
+fn lists_structs_as_symbols() {
+    let mut client = Client::start();
+    client.open("struct Point { x, y: int }\np is Point(x: 1, y: 2)\n");
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+    assert_eq!(symbols[0]["name"], "Point");
+    assert_eq!(symbols[0]["detail"], "{ x, y }");
+    assert_eq!(
+        symbols[0]["selectionRange"]["start"],
+        json!({ "line": 0, "character": 7 })
+    );
+    client.shutdown();
+}
+
+#[test]
+fn lists_methods_as_symbols() {
+    let mut client = Client::start();
+    client.open("impl Point {\n    len(self) {\n        return self.x\n    }\n}\n");
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+    assert_eq!(symbols[0]["name"], "Point.len");
+    assert_eq!(symbols[0]["detail"], "(self)");
+    assert_eq!(symbols[0]["kind"], 6);
+    client.shutdown();
+}
+
+#[test]
+fn formats_documents() {
+    let mut client = Client::start();
+    client.open("x   is  1\nwrite x,x+1\n");
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({ "textDocument": { "uri": URI }, "options": { "tabSize": 4, "insertSpaces": true } }),
+    );
+    assert_eq!(edits[0]["newText"], "x is 1\nwrite x, x + 1\n");
+    assert_eq!(
+        edits[0]["range"]["end"],
+        json!({ "line": 2, "character": 0 })
+    );
+
+    let unknown = client.call("glint/unknown", json!({}));
+    assert_eq!(unknown["error"]["code"], -32601);
+    client.shutdown();
+}
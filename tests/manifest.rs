@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use glint::manifest::Manifest;
+
+mod common;
+use common::temp_dir;
+
+fn glint(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("GLINT_PATH")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn reads_the_package_and_dependencies() {
+    let root = Path::new("/projects/app");
+    let manifest = Manifest::from_toml(
+        "[package]\nname = \"app\"\nversion = \"1.2.0\"\n\n[dependencies]\nshapes = { path = \"../shapes\" }\n\n[lint]\nunused-variable = \"allow\"\n",
+        root,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(manifest.name, "app");
+    assert_eq!(manifest.version, "1.2.0");
+    assert_eq!(manifest.entry_path(), root.join("src/main.glt"));
+    assert_eq!(manifest.sources, [PathBuf::from("src")]);
+    assert_eq!(manifest.dependencies["shapes"], PathBuf::from("../shapes"));
+
+    // A manifest that only configures the linter is not a project
+    assert_eq!(
+        Manifest::from_toml("[lint]\nunused-variable = \"allow\"\n", root),
+        Ok(None)
+    );
+    let err = Manifest::from_toml(
+        "[package]\nname = \"app\"\nversion = \"1\"\nmain = \"x\"\n",
+        root,
+    )
+    .unwrap_err();
+    assert!(err.contains("unknown field `main`"), "{}", err);
+}
+
+#[test]
+fn new_scaffolds_a_project_that_runs_and_tests() {
+    let dir = temp_dir("new");
+
+    let new = glint(&dir, &["new", "hello"]);
+    assert!(new.status.success());
+    let project = dir.join("hello");
+    let manifest = Manifest::find(&project.join("src")).unwrap().unwrap();
+    assert_eq!(manifest.name, "hello");
+    assert_eq!(manifest.version, "0.1.0");
+
+    let run = glint(&project, &["run"]);
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "Hello, world!\n");
+
+    let test = glint(&project, &["test"]);
+    assert!(test.status.success());
+    assert!(String::from_utf8_lossy(&test.stdout).contains("1 passed, 0 failed"));
+
+    let again = glint(&dir, &["new", "hello"]);
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already exists"));
+
+    // Outside of a project there is nothing to run
+    let run = glint(&dir, &["run"]);
+    assert!(!run.status.success());
+    assert!(String::from_utf8_lossy(&run.stderr).contains("no glint.toml found"));
+}
+
+#[test]
+fn path_dependencies_and_sources_are_importable() {
+    let dir = temp_dir("dependencies");
+    let files = [
+        (
+            "app/glint.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nentry = \"bin/app.glt\"\nsources = [\"lib\"]\n\n[dependencies]\nshapes = { path = \"../shapes\" }\n",
+        ),
+        (
+            "app/bin/app.glt",
+            "import util\nimport squares\nimport units\nwrite util.twice(squares.area(3)), units.cm\n",
+        ),
+        ("app/lib/util.glt", "twice(n) {\n    return n * 2\n}\n"),
+        (
+            "shapes/glint.toml",
+            "[package]\nname = \"shapes\"\nversion = \"2.0.0\"\n\n[dependencies]\nunits = { path = \"../units\" }\napp = { path = \"../app\" }\n",
+        ),
+        ("shapes/src/squares.glt", "area(side) {\n    return side * side\n}\n"),
+        ("units/units.glt", "cm is 100\n"),
+    ];
+    for (file, source) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    let app = dir.join("app");
+    let run = glint(&app.join("lib"), &["run"]);
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "18 100\n");
+
+    fs::write(
+        app.join("glint.toml"),
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nmissing = { path = \"../missing\" }\n",
+    )
+    .unwrap();
+    let run = glint(&app, &["run", "bin/app.glt"]);
+    assert!(!run.status.success());
+    assert!(String::from_utf8_lossy(&run.stderr).contains("dependency 'missing' not found"));
+}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use glint::ast::AST;
+use glint::error::ImportError;
+use glint::interpreter::interpreter::Interpreter;
+use glint::modules::link_program;
+use glint::parser::parser::parse_program;
+
+mod common;
+use common::temp_dir;
+
+/// 📂 A fresh directory holding the given files
+fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = temp_dir(name);
+    for (file, source) in files {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    dir
+}
+
+/// 📦 Parses and links `file`, with `search_path` as the module search path
+fn link(file: &Path, search_path: &[PathBuf]) -> Result<AST, ImportError> {
+    let ast = parse_program(&fs::read_to_string(file).unwrap()).unwrap();
+    link_program(ast, Some(file), search_path)
+}
+
+/// ▶️ Runs a linked program and returns what it wrote
+fn output(ast: &AST) -> String {
+    let buffer = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    struct Shared(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+    impl std::io::Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(Box::new(Shared(buffer.clone())));
+    interpreter.load_from_json(&serde_json::to_string(ast).unwrap());
+    interpreter.interpret().unwrap();
+    drop(interpreter);
+    let text = String::from_utf8(buffer.borrow().clone()).unwrap();
+    text
+}
+
+fn glint(dir: &Path, args: &[&str], search_path: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(args)
+        .current_dir(dir)
+        .env("GLINT_PATH", search_path)
+        .output()
+        .unwrap()
+}
+
+const UTILS: &str = "\
+import \"text/strings.glt\"
+scale is 3
+loaded is 0
+
+scaled(n) {
+    return n * scale
+}
+
+twice(scale) {
+    return scaled(scale) * 2
+}
+
+shout(s) {
+    return strings.exclaim(s)
+}
+
+test \"only runs in utils itself\" {
+    assert false
+}
+
+write \"utils loaded\"
+";
+
+#[test]
+fn modules_get_their_own_namespace_and_load_once() {
+    let dir = project(
+        "namespaces",
+        &[
+            ("utils.glt", UTILS),
+            ("text/strings.glt", "exclaim(s) {\n    return s + \"!\"\n}\n"),
+            (
+                "main.glt",
+                "import utils\nimport \"utils.glt\"\nimport strings\nscale is 10\nwrite utils.scaled(2), utils.twice(1), utils.scale, scale\nwrite utils.shout(\"hi\"), strings.exclaim(\"ok\")\n",
+            ),
+        ],
+    );
+    let ast = link(&dir.join("main.glt"), &[dir.join("text")]).unwrap();
+    assert_eq!(output(&ast), "utils loaded\n6 6 3 10\nhi! ok!\n");
+
+    // Both modules appear once, with their functions and globals qualified
+    let AST::Program(statements) = &ast else {
+        panic!("not a program")
+    };
+    let modules: Vec<&str> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            AST::Located { node, .. } => match node.as_ref() {
+                AST::Module { name, .. } => Some(name.as_str()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(modules, ["utils"]);
+    let json = serde_json::to_string(&ast).unwrap();
+    for name in ["utils.scaled", "utils.scale", "strings.exclaim"] {
+        assert!(json.contains(&format!("\"{}\"", name)), "{} missing", name);
+    }
+    assert!(!json.contains("only runs in utils itself"));
+
+    // A missing module lists where it was looked for
+    let err = link(&dir.join("main.glt"), &[]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{}: module 'strings' not found (searched {})",
+            dir.join("main.glt").display(),
+            dir.display()
+        )
+    );
+}
+
+#[test]
+fn structs_of_a_module_are_qualified_with_its_namespace() {
+    let dir = project(
+        "structs",
+        &[
+            (
+                "geometry.glt",
+                "struct Point { x: int, y: int }\norigin() {\n    return Point(0, 0)\n}\n",
+            ),
+            (
+                "main.glt",
+                "import geometry\nstruct Point { name }\np is geometry.Point(x: 1, y: 2)\nwrite p, geometry.origin(), Point(\"local\")\n",
+            ),
+        ],
+    );
+    let ast = link(&dir.join("main.glt"), &[]).unwrap();
+    assert_eq!(
+        output(&ast),
+        "geometry.Point(x: 1, y: 2) geometry.Point(x: 0, y: 0) Point(name: \"local\")\n"
+    );
+}
+
+#[test]
+fn reports_import_cycles_with_the_chain() {
+    let dir = project(
+        "cycle",
+        &[
+            ("main.glt", "import a\n"),
+            ("a.glt", "import \"lib/b.glt\"\n"),
+            ("lib/b.glt", "import \"../a.glt\"\n"),
+            ("nested.glt", "setup() {\n    import a\n}\n"),
+        ],
+    );
+    let err = link(&dir.join("main.glt"), &[]).unwrap_err();
+    let file = |name: &str| dir.join(name).display().to_string();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Import cycle: {} -> {} -> {} -> {}",
+            file("main.glt"),
+            file("a.glt"),
+            file("lib/b.glt"),
+            dir.join("lib/../a.glt").display()
+        )
+    );
+
+    let err = link(&dir.join("nested.glt"), &[]).unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("nested.glt: `import` on line 2 is not at the top level"));
+}
+
+#[test]
+fn run_uses_the_search_path_and_check_knows_imported_names() {
+    let dir = project(
+        "cli",
+        &[
+            ("lib/mathx.glt", "square(x) {\n    return x * x\n}\n"),
+            (
+                "main.glt",
+                "import mathx\nwrite mathx.square(7)\nwrite other.value\n",
+            ),
+        ],
+    );
+
+    let run = glint(&dir, &["run", "main.glt"], "lib");
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "49\n");
+    assert!(String::from_utf8_lossy(&run.stderr).contains("Undefined variable 'other.value'"));
+
+    let run = glint(&dir, &["run", "main.glt"], "");
+    assert!(!run.status.success());
+    assert!(String::from_utf8_lossy(&run.stderr).contains("module 'mathx' not found"));
+
+    let check = glint(&dir, &["check", "main.glt"], "");
+    let stdout = String::from_utf8(check.stdout).unwrap();
+    assert_eq!(
+        stdout.trim_end(),
+        "main.glt:3:7: error: Undefined variable 'other.value'"
+    );
+}
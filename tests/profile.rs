@@ -0,0 +1,133 @@
+use std::process::Command;
+use std::rc::Rc;
+use std::time::Duration;
+
+use glint::interpreter::interpreter::Interpreter;
+use glint::parser::parser::parse_program;
+use glint::profiler::Profile;
+
+mod common;
+use common::script;
+
+const SOURCE: &str = "\
+fib(n) {
+    if n < 2 {
+        return n
+    }
+    return fib(n - 1) + fib(n - 2)
+}
+twice(n) {
+    return fib(n) + fib(n)
+}
+write twice(10)
+";
+
+/// ⏱️ Runs `source` with a profiler attached
+fn profile(source: &str) -> Profile {
+    let ast = parse_program(source).unwrap();
+    let mut interpreter = Interpreter::new();
+    interpreter.load_from_json(&serde_json::to_string(&ast).unwrap());
+    interpreter.set_output(Box::new(std::io::sink()));
+    let profile = Profile::attach(&mut interpreter);
+    let _ = interpreter.interpret();
+    drop(interpreter);
+    let mut profile = Rc::try_unwrap(profile).ok().unwrap().into_inner();
+    profile.finish();
+    profile
+}
+
+#[test]
+fn counts_calls_and_splits_inclusive_from_exclusive_time() {
+    let profile = profile(SOURCE);
+    let fib = &profile.functions["fib"];
+    let twice = &profile.functions["twice"];
+    let program = &profile.functions["<program>"];
+    assert_eq!((fib.calls, twice.calls, program.calls), (354, 1, 1));
+
+    // Recursion is not counted twice, and callers include their callees
+    assert!(fib.inclusive <= twice.inclusive);
+    assert!(twice.inclusive <= program.inclusive);
+    assert_eq!(fib.inclusive, fib.exclusive);
+    assert!(twice.exclusive < twice.inclusive);
+    let exclusive: Duration = profile.functions.values().map(|f| f.exclusive).sum();
+    assert!(exclusive <= program.inclusive);
+
+    assert_eq!(profile.lines[&(None, 2)].hits, 354);
+    assert_eq!(profile.lines[&(None, 5)].hits, 176);
+    assert_eq!(profile.lines[&(None, 8)].hits, 1);
+    assert!(!profile.lines.contains_key(&(None, 1)));
+}
+
+#[test]
+fn profiles_failing_programs_and_folds_stacks() {
+    let profile =
+        profile("inner() {\n    return 1 / 0\n}\nouter() {\n    return inner()\n}\nouter()\n");
+    assert_eq!(profile.functions["inner"].calls, 1);
+    let folded = profile.folded();
+    let stacks: Vec<&str> = folded
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().0)
+        .collect();
+    assert_eq!(
+        stacks,
+        ["<program>", "<program>;outer", "<program>;outer;inner"]
+    );
+}
+
+#[test]
+fn run_prints_a_table_and_writes_folded_stacks() {
+    let script = script("fib.glt", SOURCE);
+    let folded = script.with_extension("folded");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(["run", "--profile", "--profile-out"])
+        .arg(&folded)
+        .arg(&script)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "110\n");
+
+    let table = String::from_utf8(output.stderr).unwrap();
+    assert!(table.starts_with("Profile ("));
+    let fib = table.lines().find(|line| line.starts_with("fib ")).unwrap();
+    assert_eq!(fib.split_whitespace().nth(1), Some("354"));
+    assert!(table.contains("\nHot lines\n"));
+
+    let folded = std::fs::read_to_string(&folded).unwrap();
+    assert!(folded.lines().all(|line| {
+        let (stack, micros) = line.rsplit_once(' ').unwrap();
+        stack.starts_with("<program>") && micros.parse::<u64>().is_ok()
+    }));
+    assert!(folded.contains("<program>;twice;fib;fib "));
+}
+
+#[test]
+fn hot_lines_of_a_module_are_shown_with_its_file() {
+    let main = script("hot.glt", "import utils\nwrite utils.twice(2)\n");
+    std::fs::write(
+        main.with_file_name("utils.glt"),
+        "twice(n) {\n    return n * 2\n}\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .args(["run", "--profile"])
+        .arg(&main)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let table = String::from_utf8(output.stderr).unwrap();
+    let (_, hot) = table.split_once("\nHot lines\n").unwrap();
+    let mut lines: Vec<(&str, &str)> = hot
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let mut columns = line.split_whitespace();
+            (columns.next().unwrap(), columns.next().unwrap())
+        })
+        .collect();
+    lines.sort();
+    let module = format!("{}:2", main.with_file_name("utils.glt").display());
+    assert_eq!(lines, [(module.as_str(), "1"), ("2", "1")]);
+}
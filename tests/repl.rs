@@ -0,0 +1,53 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use glint::repl::open_delimiters;
+
+#[test]
+fn counts_delimiters_outside_strings_and_comments() {
+    assert_eq!(open_delimiters("f(n) {"), 1);
+    assert_eq!(open_delimiters("write \"{ [\""), 0);
+    assert_eq!(open_delimiters("# a { note"), 0);
+    assert_eq!(open_delimiters("if x { # close with }\n"), 1);
+    assert_eq!(open_delimiters("write \"#\", [1,\n"), 1);
+}
+
+/// 💬 Runs `glint repl`, typing `input`; returns what it printed and its exit code
+fn repl(input: &str) -> (String, Option<i32>) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    // Drop the banner
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = stdout.split_once('\n').unwrap().1.to_string();
+    (stdout, output.status.code())
+}
+
+#[test]
+fn exit_ends_the_session_with_its_code() {
+    assert_eq!(
+        repl("write 1\nexit(4)\nwrite 2\n"),
+        ("1\n".to_string(), Some(4))
+    );
+    assert_eq!(
+        repl("f() {\n    exit(7)\n}\nf()\nwrite 2\n"),
+        (String::new(), Some(7))
+    );
+    assert_eq!(
+        repl("write 1\n:quit\nwrite 2\n"),
+        ("1\n".to_string(), Some(0))
+    );
+    // Reaching the end of the input is not an error either
+    assert_eq!(repl("write 1\n"), ("1\n".to_string(), Some(0)));
+}
@@ -0,0 +1,154 @@
+use std::process::Command;
+
+use glint::parser::parser::parse_program;
+use glint::testing::{run_tests, Failure};
+
+mod common;
+use common::script;
+
+const SOURCE: &str = "\
+square(x) {
+    return x * x
+}
+setup is 1
+write \"top level\"
+
+test \"squares\" {
+    assert square(3) = 9
+    leaked is 1
+}
+
+test \"fails with a message\" {
+    write \"before\"
+    assert square(2) = 5, \"square(2) is \" + square(2)
+    write \"after\"
+}
+
+test \"isolated\" {
+    assert leaked = 1
+}
+
+test \"describes its condition\" {
+    assert square(1) > 1
+}
+";
+
+#[test]
+fn runs_each_block_in_a_fresh_interpreter() {
+    let results = run_tests(&parse_program(SOURCE).unwrap());
+    let names: Vec<(&str, usize)> = results
+        .iter()
+        .map(|result| (result.name.as_str(), result.line))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("squares", 7),
+            ("fails with a message", 12),
+            ("isolated", 18),
+            ("describes its condition", 22),
+        ]
+    );
+
+    assert_eq!(results[0].failure, None);
+    // Only what the block wrote is captured, not the top-level code
+    assert_eq!(results[0].output, "");
+    assert_eq!(
+        results[1].failure,
+        Some(Failure {
+            file: None,
+            line: 14,
+            column: Some(5),
+            message: "assertion failed: square(2) is 4".to_string(),
+        })
+    );
+    assert_eq!(results[1].output, "before\n");
+    // Variables from another block (or the top level) are not defined
+    assert_eq!(
+        results[2].failure,
+        Some(Failure {
+            file: None,
+            line: 19,
+            column: None,
+            message: "Undefined variable 'leaked'".to_string(),
+        })
+    );
+    assert_eq!(
+        results[3].failure.as_ref().unwrap().message,
+        "assertion failed: square(1) > 1"
+    );
+}
+
+#[test]
+fn run_skips_test_blocks_and_test_reports_each_one() {
+    let script = script("squares.glt", SOURCE);
+    let glint = |command: &str| {
+        Command::new(env!("CARGO_BIN_EXE_glint"))
+            .arg(command)
+            .arg(&script)
+            .output()
+            .unwrap()
+    };
+
+    let run = glint("run");
+    assert!(run.status.success());
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "top level\n");
+
+    let test = glint("test");
+    assert!(!test.status.success());
+    let stdout = String::from_utf8(test.stdout).unwrap();
+    let location = format!(
+        "{}:14:5: assertion failed: square(2) is 4",
+        script.display()
+    );
+    assert!(stdout.contains(&format!(
+        "PASS {}: squares\nFAIL {}: fails with a message\n  {}\n  | before\n",
+        script.display(),
+        script.display(),
+        location
+    )));
+    assert!(stdout.trim_end().ends_with("1 passed, 3 failed"));
+
+    std::fs::write(&script, "assert 1 + 1 = 3, \"math\"\n").unwrap();
+    let run = glint("run");
+    assert!(!run.status.success());
+    assert!(
+        String::from_utf8_lossy(&run.stderr).contains("Assertion failed on line 1, column 1: math")
+    );
+}
+
+#[test]
+fn failures_inside_a_module_are_located_in_its_file() {
+    let script = script(
+        "located.glt",
+        "import checks\n\ntest \"positive\" {\n    checks.positive(-1)\n}\n\ntest \"divides\" {\n    checks.divide(1)\n}\n",
+    );
+    let module = script.with_file_name("checks.glt");
+    std::fs::write(
+        &module,
+        "positive(n) {\n    assert n > 0\n}\ndivide(n) {\n    return n / 0\n}\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_glint"))
+        .arg("test")
+        .arg(&script)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(&format!(
+            "  {}:2:5: assertion failed: n > 0\n",
+            module.display()
+        )),
+        "{}",
+        stdout
+    );
+    // Other errors point at the statement of the test block that failed
+    assert!(
+        stdout.contains(&format!("  {}:8: Division by zero\n", script.display())),
+        "{}",
+        stdout
+    );
+}